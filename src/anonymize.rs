@@ -0,0 +1,72 @@
+use hat_changer::{ProjectList, ProjectMetadata};
+
+/// Copies a project list with every name and description replaced by a salted hash, keeping
+/// timestamps, durations, and settings. Equal names hash alike within an export, so repeated
+/// descriptions and shared clients still line up. The hash is not cryptographic, so it hides
+/// names from readers but not from a determined attacker.
+pub fn anonymize(list: &ProjectList, salt: u64) -> ProjectList {
+    let hash = |prefix: &str, text: &str| format!("{prefix}-{:016x}", fnv1a(salt, text));
+
+    let mut anonymized = list.clone();
+
+    anonymized.projects = list
+        .projects
+        .iter()
+        .map(|(name, project)| {
+            let mut project = project.clone();
+
+            for time in &mut project.logged_times {
+                time.description = hash("task", &time.description);
+                time.notes = None;
+                time.tags = time.tags.iter().map(|tag| hash("tag", tag)).collect();
+                time.tickets.clear();
+            }
+
+            project.settings.tags = project
+                .settings
+                .tags
+                .iter()
+                .map(|tag| hash("tag", tag))
+                .collect();
+
+            project.metadata = ProjectMetadata {
+                description: None,
+                client: project
+                    .metadata
+                    .client
+                    .as_deref()
+                    .map(|client| hash("client", client)),
+                color: project.metadata.color,
+                url: None,
+            };
+
+            (hash("project", name), project)
+        })
+        .collect();
+
+    anonymized.active_project = list
+        .active_project
+        .as_deref()
+        .map(|name| hash("project", name));
+
+    for invoice in &mut anonymized.invoices.invoices {
+        invoice.client = hash("client", &invoice.client);
+    }
+
+    // Deleted items keep their names, and aren't needed to reproduce anything.
+    anonymized.trash.clear();
+
+    anonymized
+}
+
+/// Hashes text with the 64-bit FNV-1a hash, starting from a salt.
+fn fnv1a(salt: u64, text: &str) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325 ^ salt;
+
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    hash
+}
@@ -0,0 +1,275 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{date, merge, storage::Storage, ProjectList, Result};
+
+/// The suffix added to the names of archive files compressed with zstd.
+const COMPRESSED_SUFFIX: &str = ".zst";
+
+fn year_path(dir: &Path, year: i64, compressed: bool) -> PathBuf {
+    if compressed {
+        dir.join(format!("{year}.json{COMPRESSED_SUFFIX}"))
+    } else {
+        dir.join(format!("{year}.json"))
+    }
+}
+
+/// Whether a file name is that of an archive file, compressed or not.
+fn is_archive(name: &str) -> bool {
+    name.strip_suffix(COMPRESSED_SUFFIX)
+        .unwrap_or(name)
+        .ends_with(".json")
+}
+
+/// The name of an archive file without the suffix of compressed files.
+pub fn plain_name(name: &str) -> &str {
+    name.strip_suffix(COMPRESSED_SUFFIX).unwrap_or(name)
+}
+
+/// Writes an archive file, compressing it if its name says so.
+fn write(storage: &Storage, path: &Path, list: &ProjectList) -> Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    let bytes = if name.ends_with(COMPRESSED_SUFFIX) {
+        storage.encode_compressed(list)?
+    } else {
+        storage.encode(list)?
+    };
+
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+fn read(storage: &Storage, path: &Path) -> Result<ProjectList> {
+    match fs::read(path) {
+        Ok(bytes) => storage.decode(&bytes),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(ProjectList::default()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Moves entries starting before `before` into one archive file per year in `dir`, compressed
+/// with zstd if `compressed` is set. Returns the number of entries archived.
+pub fn archive(
+    list: &mut ProjectList,
+    storage: &Storage,
+    dir: &Path,
+    before: Duration,
+    compressed: bool,
+) -> Result<usize> {
+    let mut years: BTreeMap<i64, ProjectList> = BTreeMap::new();
+    let mut archived = 0;
+
+    for (name, project) in list.projects.iter() {
+        for time in project.logged_times.iter() {
+            if time.start_epoch >= before {
+                continue;
+            }
+
+            let (year, _, _) = date::civil_from_days(date::day_number(time.start_epoch));
+
+            years
+                .entry(year)
+                .or_default()
+                .projects
+                .entry(name.clone())
+                .or_default()
+                .logged_times
+                .push(time.clone());
+
+            archived += 1;
+        }
+    }
+
+    fs::create_dir_all(dir)?;
+
+    // Write every archive file before removing anything, so nothing is lost on failure.
+    for (year, entries) in years {
+        let path = year_path(dir, year, compressed);
+        let mut existing = read(storage, &path)?;
+
+        // A year archived before compression was turned on or off is moved into the new file.
+        let other = year_path(dir, year, !compressed);
+        let previous = read(storage, &other)?;
+        merge::union(&mut existing, previous);
+
+        merge::union(&mut existing, entries);
+        write(storage, &path, &existing)?;
+
+        if other.exists() {
+            fs::remove_file(&other)?;
+        }
+    }
+
+    for project in list.projects.values_mut() {
+        project
+            .logged_times
+            .retain(|time| time.start_epoch >= before);
+    }
+
+    Ok(archived)
+}
+
+/// Reads every archive file in `dir`, keyed by file name.
+pub fn read_all(storage: &Storage, dir: &Path) -> Result<BTreeMap<String, ProjectList>> {
+    let mut archives = BTreeMap::new();
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(archives),
+        Err(err) => return Err(err.into()),
+    };
+
+    for entry in entries {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+        if is_archive(&name) {
+            archives.insert(name.to_string(), read(storage, &path)?);
+        }
+    }
+
+    Ok(archives)
+}
+
+/// Returns a copy of the project list with the entries from every archive file in `dir`.
+pub fn include(list: &ProjectList, storage: &Storage, dir: &Path) -> Result<ProjectList> {
+    let mut combined = list.clone();
+
+    for mut archived in read_all(storage, dir)?.into_values() {
+        // Archived projects that were since deleted are left out.
+        archived
+            .projects
+            .retain(|name, _| combined.projects.contains_key(name));
+
+        merge::union(&mut combined, archived);
+    }
+
+    // The cached totals only cover the entries in the data file.
+    for project in combined.projects.values_mut() {
+        project.logged_times.sort_by_key(|time| time.start_epoch);
+        project.cached_total = None;
+    }
+
+    Ok(combined)
+}
+
+/// Replaces the archive file named `name` in `dir`, keeping the old one next to it with a
+/// `.bak` suffix.
+pub fn replace(storage: &Storage, dir: &Path, name: &str, list: &ProjectList) -> Result<()> {
+    let path = dir.join(name);
+    fs::copy(&path, dir.join(format!("{name}.bak")))?;
+    write(storage, &path, list)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf, process, time::Duration};
+
+    use super::{archive, include, read_all};
+    use crate::{config::Config, date, storage::Storage, LoggedTime, ProjectList};
+
+    fn entry(date: &str, hours: u64, description: &str) -> LoggedTime {
+        LoggedTime {
+            start_epoch: date::parse_date(date).unwrap() + Duration::from_secs(9 * 60 * 60),
+            duration: Duration::from_secs(hours * 60 * 60),
+            description: description.to_string(),
+            notes: None,
+            tags: Vec::new(),
+            billable: None,
+            rounded: None,
+            invoice: None,
+            tickets: Vec::new(),
+        }
+    }
+
+    fn descriptions(list: &ProjectList) -> Vec<&str> {
+        list.projects["web"]
+            .logged_times
+            .iter()
+            .map(|time| time.description.as_str())
+            .collect()
+    }
+
+    /// A directory of its own for each test, removed when the test is done.
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let dir = env::temp_dir().join(format!("hat-{name}-{}", process::id()));
+            fs::remove_dir_all(&dir).ok();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    #[test]
+    fn overlapping_entries_survive_archiving_in_steps() {
+        let dir = TestDir::new("archive-overlaps");
+        let storage = Storage::new(&Config::default(), &dir.0);
+
+        let mut list = ProjectList::default();
+        list.projects
+            .entry("web".to_string())
+            .or_default()
+            .logged_times = vec![
+            entry("2020-05-04", 1, "old"),
+            entry("2020-09-07", 2, "a"),
+            entry("2020-09-07", 1, "b"),
+        ];
+
+        let first = date::parse_date("2020-06-01").unwrap();
+        let second = date::parse_date("2021-01-01").unwrap();
+
+        assert_eq!(
+            archive(&mut list, &storage, &dir.0, first, false).unwrap(),
+            1
+        );
+        assert_eq!(
+            archive(&mut list, &storage, &dir.0, second, false).unwrap(),
+            2
+        );
+        assert!(list.projects["web"].logged_times.is_empty());
+
+        let archives = read_all(&storage, &dir.0).unwrap();
+        assert_eq!(descriptions(&archives["2020.json"]), ["old", "a", "b"]);
+    }
+
+    #[test]
+    fn included_entries_are_never_hidden() {
+        let dir = TestDir::new("archive-include");
+        let storage = Storage::new(&Config::default(), &dir.0);
+
+        let mut list = ProjectList::default();
+        list.projects
+            .entry("web".to_string())
+            .or_default()
+            .logged_times = vec![entry("2020-09-07", 2, "archived")];
+
+        let before = date::parse_date("2021-01-01").unwrap();
+        archive(&mut list, &storage, &dir.0, before, false).unwrap();
+
+        // An entry logged later that overlaps the archived one.
+        list.projects.get_mut("web").unwrap().logged_times =
+            vec![entry("2020-09-07", 1, "backfilled")];
+
+        let combined = include(&list, &storage, &dir.0).unwrap();
+        assert_eq!(descriptions(&combined), ["backfilled", "archived"]);
+
+        // An identical copy, such as one restored from a backup, is only listed once.
+        list.projects.get_mut("web").unwrap().logged_times =
+            vec![entry("2020-09-07", 2, "archived")];
+
+        let combined = include(&list, &storage, &dir.0).unwrap();
+        assert_eq!(descriptions(&combined), ["archived"]);
+    }
+}
@@ -0,0 +1,46 @@
+use hat_changer::LoggedTime;
+use serde::{Deserialize, Serialize};
+
+use crate::{pattern::Pattern, Result};
+
+/// A rule tagging entries whose description matches a pattern, such as `^standup|retro` for
+/// `meeting`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoTagRule {
+    /// A regular expression matched against the description, ignoring case.
+    pub pattern: String,
+
+    /// The tag the matching entries get.
+    pub tag: String,
+}
+
+/// The rules from the config file, with their patterns compiled.
+pub struct AutoTagger {
+    rules: Vec<(Pattern, String)>,
+}
+
+impl AutoTagger {
+    pub fn new(rules: &[AutoTagRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| Ok((Pattern::parse(&rule.pattern)?, rule.tag.trim().to_string())))
+            .collect::<Result<_>>()?;
+
+        Ok(Self { rules })
+    }
+
+    /// Adds the tag of every matching rule that the entry doesn't have yet, returning whether
+    /// any were added.
+    pub fn apply(&self, time: &mut LoggedTime) -> bool {
+        let mut added = false;
+
+        for (pattern, tag) in &self.rules {
+            if !time.tags.contains(tag) && pattern.is_match(&time.description) {
+                time.tags.push(tag.clone());
+                added = true;
+            }
+        }
+
+        added
+    }
+}
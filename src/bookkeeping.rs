@@ -0,0 +1,175 @@
+use std::{io, time::Duration};
+
+use hat_changer::date;
+
+use crate::columns::CsvColumn;
+
+/// A spreadsheet layout that a bookkeeping service imports time entries from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// QuickBooks time activities, with US dates and durations in hours and minutes.
+    QuickBooks,
+
+    /// FreshBooks time entries, with ISO dates and durations in decimal hours.
+    FreshBooks,
+}
+
+/// An entry to export, with the client and rate it is billed to.
+pub struct Row<'a> {
+    pub start_epoch: Duration,
+    pub client: &'a str,
+    pub project: &'a str,
+    pub description: &'a str,
+    pub tags: &'a [String],
+    pub tickets: &'a [String],
+    pub notes: Option<&'a str>,
+
+    /// The duration tracked.
+    pub tracked: Duration,
+
+    /// The duration billed, which is rounded if the entry was.
+    pub duration: Duration,
+
+    pub billable: bool,
+    pub rate: Option<f64>,
+    pub invoice: Option<u64>,
+}
+
+/// Writes entries as CSV in the columns the service expects, with a header row. Each row is
+/// written as soon as it is formatted.
+pub fn write_csv(out: &mut impl io::Write, layout: Layout, rows: &[Row]) -> io::Result<()> {
+    let header: &[&str] = match layout {
+        Layout::QuickBooks => &[
+            "Date",
+            "Customer",
+            "Service",
+            "Description",
+            "Duration",
+            "Billable",
+            "Rate",
+        ],
+        Layout::FreshBooks => &[
+            "Date", "Client", "Project", "Note", "Hours", "Billable", "Rate",
+        ],
+    };
+
+    write_record(out, header, ',')?;
+
+    for row in rows {
+        let rate = row
+            .rate
+            .map(|rate| format!("{rate:.2}"))
+            .unwrap_or_default();
+
+        let fields = match layout {
+            Layout::QuickBooks => {
+                let minutes = (row.duration.as_secs() + 30) / 60;
+                let (year, month, day) = date::civil_from_days(date::day_number(row.start_epoch));
+
+                [
+                    format!("{month:02}/{day:02}/{year:04}"),
+                    row.client.to_string(),
+                    row.project.to_string(),
+                    row.description.to_string(),
+                    format!("{}:{:02}", minutes / 60, minutes % 60),
+                    if row.billable { "Yes" } else { "No" }.to_string(),
+                    rate,
+                ]
+            }
+            Layout::FreshBooks => [
+                date::format_date(row.start_epoch),
+                row.client.to_string(),
+                row.project.to_string(),
+                row.description.to_string(),
+                format!("{:.2}", row.duration.as_secs_f64() / 3600.0),
+                if row.billable { "true" } else { "false" }.to_string(),
+                rate,
+            ],
+        };
+
+        write_record(out, &fields, ',')?;
+    }
+
+    Ok(())
+}
+
+/// Writes entries as CSV in the chosen columns, separated by `delimiter`, with a header row.
+pub fn write_columns(
+    out: &mut impl io::Write,
+    columns: &[CsvColumn],
+    delimiter: char,
+    rows: &[Row],
+) -> io::Result<()> {
+    let header: Vec<&str> = columns.iter().map(|column| column.title()).collect();
+    write_record(out, &header, delimiter)?;
+
+    for row in rows {
+        let fields: Vec<String> = columns.iter().map(|&column| field(row, column)).collect();
+        write_record(out, &fields, delimiter)?;
+    }
+
+    Ok(())
+}
+
+fn field(row: &Row, column: CsvColumn) -> String {
+    let hours = |duration: Duration| format!("{:.2}", duration.as_secs_f64() / 3600.0);
+
+    match column {
+        CsvColumn::Date => date::format_date(row.start_epoch),
+        CsvColumn::Start => date::format_date_time(row.start_epoch),
+        CsvColumn::End => date::format_date_time(row.start_epoch + row.tracked),
+        CsvColumn::Project => row.project.to_string(),
+        CsvColumn::Client => row.client.to_string(),
+        CsvColumn::Description => row.description.to_string(),
+        CsvColumn::Tags => row.tags.join(" "),
+        CsvColumn::Tickets => row.tickets.join(" "),
+        CsvColumn::Notes => row.notes.unwrap_or_default().to_string(),
+        CsvColumn::Hours => hours(row.tracked),
+        CsvColumn::Billed => hours(row.duration),
+        CsvColumn::Duration => {
+            let minutes = (row.duration.as_secs() + 30) / 60;
+            format!("{}:{:02}", minutes / 60, minutes % 60)
+        }
+        CsvColumn::Billable => row.billable.to_string(),
+        CsvColumn::Rate => row
+            .rate
+            .map(|rate| format!("{rate:.2}"))
+            .unwrap_or_default(),
+        CsvColumn::Amount => row
+            .rate
+            .filter(|_| row.billable)
+            .map(|rate| format!("{:.2}", rate * row.duration.as_secs_f64() / 3600.0))
+            .unwrap_or_default(),
+        CsvColumn::Invoice => row
+            .invoice
+            .map(|invoice| invoice.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// Writes one line of fields, quoting those that need it, ended by CRLF as RFC 4180 asks.
+fn write_record(
+    out: &mut impl io::Write,
+    fields: &[impl AsRef<str>],
+    delimiter: char,
+) -> io::Result<()> {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| quote(field.as_ref(), delimiter))
+        .collect();
+
+    write!(out, "{}\r\n", fields.join(&delimiter.to_string()))
+}
+
+/// Quotes a CSV field if it contains the delimiter, a quote, or a line break, or starts or
+/// ends with a space that a reader might trim.
+fn quote(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r'])
+        || field.starts_with(' ')
+        || field.ends_with(' ')
+    {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{date, duration, log, Error, Result};
+
+/// A rule for deducting breaks from entries when a timer is stopped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BreakRule {
+    /// Requires a break of `deduct` in any session longer than `after`, such as `6h` and `30m`.
+    Session { after: String, deduct: String },
+
+    /// Excludes a window every day, given as `HH:MM` times in UTC, such as `12:00` to `12:30`.
+    /// A window that ends before it starts, such as `23:30` to `00:15`, runs past midnight.
+    Daily { from: String, to: String },
+}
+
+/// Works out how much time to deduct for breaks from a session that ran from `start` to `end`
+/// and tracked `duration` of it, having been paused during `pauses`.
+///
+/// Daily windows are deducted first, leaving out the parts of them the timer was paused for,
+/// since that time is not tracked anyway. A session rule then only deducts whatever part of its
+/// break was not already covered by the daily windows, and the largest such rule wins.
+pub fn deduction(
+    rules: &[BreakRule],
+    start: Duration,
+    end: Duration,
+    pauses: &[(Duration, Duration)],
+    duration: Duration,
+) -> Result<Duration> {
+    let mut windows = Duration::ZERO;
+
+    for rule in rules {
+        let BreakRule::Daily { from, to } = rule else {
+            continue;
+        };
+
+        let from = parse_time_of_day(from)?;
+        let to = parse_time_of_day(to)?;
+
+        // A window past midnight may have started the day before the session did.
+        let first_day = date::day_number(start) - 1;
+
+        for day in first_day..=date::day_number(end) {
+            let window_start = date::start_of_day(day) + from;
+            let window_end = if to < from {
+                date::start_of_day(day + 1) + to
+            } else {
+                date::start_of_day(day) + to
+            };
+
+            let window_start = start.max(window_start);
+            let window_end = end.min(window_end);
+
+            let paused: Duration = pauses
+                .iter()
+                .map(|(from, to)| overlap(window_start, window_end, *from, *to))
+                .sum();
+
+            windows += window_end
+                .saturating_sub(window_start)
+                .saturating_sub(paused);
+        }
+    }
+
+    let windows = windows.min(duration);
+    let mut required = Duration::ZERO;
+
+    for rule in rules {
+        let BreakRule::Session { after, deduct } = rule else {
+            continue;
+        };
+
+        if duration > parse_rule_duration(after)? {
+            required = required.max(parse_rule_duration(deduct)?);
+        }
+    }
+
+    log::trace!("Daily windows cover {windows:?}, and session rules require {required:?}.");
+
+    Ok((windows + required.saturating_sub(windows)).min(duration))
+}
+
+/// How long two spans of time overlap.
+fn overlap(start: Duration, end: Duration, other_start: Duration, other_end: Duration) -> Duration {
+    end.min(other_end).saturating_sub(start.max(other_start))
+}
+
+fn parse_rule_duration(text: &str) -> Result<Duration> {
+    duration::parse(text).ok_or_else(|| Error::InvalidDuration(text.to_string()))
+}
+
+fn parse_time_of_day(text: &str) -> Result<Duration> {
+    date::parse_time_of_day(text).ok_or_else(|| Error::InvalidTimeOfDay(text.to_string()))
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use colored::Colorize;
+use pretty_duration::pretty_duration;
+
+/// Draws the time logged up to the end of each day as columns, with a line across at the
+/// estimate, as lines of text. Days are grouped into columns when there are more than `width`,
+/// and time past the estimate is drawn in red.
+pub fn chart(
+    cumulative: &[Duration],
+    estimate: Duration,
+    width: usize,
+    height: usize,
+) -> Vec<String> {
+    let days = cumulative.len();
+    let columns = days.min(width).max(1);
+
+    // Each column shows the total at the end of the last day it covers.
+    let values: Vec<Duration> = (0..columns)
+        .map(|column| {
+            let last = ((column + 1) * days).div_ceil(columns).max(1) - 1;
+            cumulative.get(last).copied().unwrap_or_default()
+        })
+        .collect();
+
+    let top = values
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or_default()
+        .max(estimate);
+
+    if top.is_zero() {
+        return Vec::new();
+    }
+
+    let rows_of = |value: Duration| {
+        (value.as_secs_f64() / top.as_secs_f64() * height as f64).round() as usize
+    };
+    let estimate_row = rows_of(estimate);
+
+    let label = |row: usize| match row {
+        _ if row == height => format_hours(top),
+        _ if row == estimate_row => format_hours(estimate),
+        _ => String::new(),
+    };
+    let label_width = [label(height).len(), label(estimate_row).len(), 2]
+        .into_iter()
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = Vec::new();
+
+    for row in (1..=height).rev() {
+        let mut line = format!("{:>label_width$} │", label(row));
+
+        for &value in &values {
+            let cell = match (rows_of(value) >= row, row == estimate_row) {
+                (true, _) if row > estimate_row => "█".bright_red().to_string(),
+                (true, _) => "█".bright_green().to_string(),
+                (false, true) => "─".bright_yellow().to_string(),
+                (false, false) => " ".to_string(),
+            };
+
+            line.push_str(&cell);
+        }
+
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines.push(format!(
+        "{:>label_width$} └{}",
+        "0h",
+        "─".repeat(values.len())
+    ));
+
+    lines
+}
+
+/// A duration in whole hours, or in minutes if it is shorter than an hour.
+fn format_hours(duration: Duration) -> String {
+    let hours = duration.as_secs() / 3600;
+
+    if hours > 0 {
+        format!("{hours}h")
+    } else {
+        pretty_duration(&Duration::from_secs(duration.as_secs() / 60 * 60), None)
+    }
+}
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+use crate::table::Align;
+
+/// A column of the entries listed by `time`.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeColumn {
+    /// The number of the entry, for editing it.
+    Number,
+    Start,
+    End,
+    Duration,
+    /// The duration rounded for billing.
+    Billed,
+    Description,
+    Tags,
+    /// Whether the entry is billable, and the invoice it is on.
+    Status,
+}
+
+impl TimeColumn {
+    /// The columns shown by `time --verbose`, leaving out those that are empty.
+    pub const VERBOSE: [Self; 7] = [
+        Self::Number,
+        Self::Start,
+        Self::Duration,
+        Self::Billed,
+        Self::Description,
+        Self::Tags,
+        Self::Status,
+    ];
+
+    /// The columns shown by `time`, leaving out those that are empty.
+    pub const DEFAULT: [Self; 4] = [Self::Start, Self::Duration, Self::Billed, Self::Description];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Number => "#",
+            Self::Start => "Start",
+            Self::End => "End",
+            Self::Duration => "Duration",
+            Self::Billed => "Billed",
+            Self::Description => "Description",
+            Self::Tags => "Tags",
+            Self::Status => "Status",
+        }
+    }
+
+    pub fn align(self) -> Align {
+        match self {
+            Self::Number | Self::Duration | Self::Billed => Align::Right,
+            _ => Align::Left,
+        }
+    }
+
+    /// Whether the column is truncated to fit the terminal.
+    pub fn flexible(self) -> bool {
+        matches!(self, Self::Description | Self::Tags)
+    }
+}
+
+/// A column of the projects listed by `list`.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ListColumn {
+    Project,
+    Client,
+    /// The day the project was last worked on.
+    LastActive,
+    /// The number of entries.
+    Entries,
+    /// The time logged this week.
+    Week,
+    /// The time logged in total.
+    Total,
+    /// Whether the project is active, running, or paused.
+    State,
+    /// When the running timer started and how long it has run.
+    Timer,
+}
+
+impl ListColumn {
+    /// The columns shown by `list --detailed`.
+    pub const DETAILED: [Self; 7] = [
+        Self::Project,
+        Self::Client,
+        Self::LastActive,
+        Self::Entries,
+        Self::Week,
+        Self::Total,
+        Self::State,
+    ];
+
+    /// The columns shown by `list`, leaving out the timer when none is running.
+    pub const DEFAULT: [Self; 3] = [Self::Project, Self::Total, Self::Timer];
+
+    /// Whether the column is computed from logged entries rather than the index.
+    pub fn needs_entries(self) -> bool {
+        matches!(self, Self::LastActive | Self::Entries | Self::Week)
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Project => "Project",
+            Self::Client => "Client",
+            Self::LastActive => "Last active",
+            Self::Entries => "Entries",
+            Self::Week => "This week",
+            Self::Total => "Total",
+            Self::State => "State",
+            Self::Timer => "Timer (not in the total)",
+        }
+    }
+
+    pub fn align(self) -> Align {
+        match self {
+            Self::Entries | Self::Week | Self::Total => Align::Right,
+            _ => Align::Left,
+        }
+    }
+
+    /// Whether the column is truncated to fit the terminal.
+    pub fn flexible(self) -> bool {
+        matches!(self, Self::Project | Self::Client | Self::Timer)
+    }
+}
+
+/// A column of the entries exported by `export csv`.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CsvColumn {
+    /// The day the entry started, as `YYYY-MM-DD`.
+    Date,
+    /// When the entry started, as `YYYY-MM-DD HH:MM`.
+    Start,
+    /// When the entry ended, as `YYYY-MM-DD HH:MM`.
+    End,
+    Project,
+    /// The client of the project, or its name if it has none.
+    Client,
+    Description,
+    /// The tags of the entry, separated by spaces.
+    Tags,
+    /// The tickets mentioned in the description, separated by spaces.
+    Tickets,
+    Notes,
+    /// The duration tracked, in decimal hours.
+    Hours,
+    /// The duration billed, in decimal hours, which is rounded if the entry was.
+    Billed,
+    /// The duration billed, as `H:MM`.
+    Duration,
+    Billable,
+    /// The hourly rate of the project.
+    Rate,
+    /// The amount billed, if the entry is billable and the project has a rate.
+    Amount,
+    /// The number of the invoice the entry is on.
+    Invoice,
+}
+
+impl CsvColumn {
+    /// The columns exported when none are chosen.
+    pub const DEFAULT: [Self; 8] = [
+        Self::Date,
+        Self::Client,
+        Self::Project,
+        Self::Description,
+        Self::Tags,
+        Self::Billed,
+        Self::Billable,
+        Self::Rate,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Date => "Date",
+            Self::Start => "Start",
+            Self::End => "End",
+            Self::Project => "Project",
+            Self::Client => "Client",
+            Self::Description => "Description",
+            Self::Tags => "Tags",
+            Self::Tickets => "Tickets",
+            Self::Notes => "Notes",
+            Self::Hours => "Hours",
+            Self::Billed => "Billed",
+            Self::Duration => "Duration",
+            Self::Billable => "Billable",
+            Self::Rate => "Rate",
+            Self::Amount => "Amount",
+            Self::Invoice => "Invoice",
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+    thread,
+};
+
+use crate::{log, Error, Result};
+
+/// The magic number at the start of every zstd frame.
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Whether the contents of a file were compressed with zstd.
+pub fn is_compressed(bytes: &[u8]) -> bool {
+    bytes.starts_with(ZSTD_MAGIC)
+}
+
+fn zstd(args: &[&str], input: &[u8]) -> Result<Vec<u8>> {
+    log::debug!("Running zstd {} on {} bytes.", args.join(" "), input.len());
+
+    let mut child = Command::new("zstd")
+        .args(args)
+        .args(["--quiet", "--stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+
+    // Archives can be larger than the pipe buffer, so the output is read while writing.
+    let output = thread::scope(|scope| {
+        let writer = scope.spawn(move || stdin.write_all(input));
+        let output = child.wait_with_output();
+        writer.join().expect("The zstd input thread panicked.")?;
+        output
+    })?;
+
+    if !output.status.success() {
+        return Err(Error::Compression(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Compresses the data with zstd.
+pub fn compress(data: &[u8]) -> Result<Vec<u8>> {
+    zstd(&["-19"], data)
+}
+
+/// Decompresses zstd data.
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    zstd(&["--decompress"], data)
+}
@@ -0,0 +1,351 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    autotag::AutoTagRule,
+    breaks::BreakRule,
+    columns::{CsvColumn, ListColumn, TimeColumn},
+    date,
+    github::GitHubConfig,
+    gitlab::GitLabConfig,
+    goals::Goals,
+    linear::LinearConfig,
+    log,
+    redmine::RedmineConfig,
+    remote::RemoteConfig,
+    schedule::ScheduledJob,
+    server::ServerConfig,
+};
+
+/// The name of the data file, both in the home directory and in a custom data directory.
+const DATA_FILE_NAME: &str = "timelogger.json";
+
+/// The settings that hold credentials, as `(section, key)`, which `Config::redacted` replaces.
+/// Any new password, token, or API key must be listed here, or in `Config::redacted` itself if
+/// its section is keyed by project.
+const CREDENTIALS: &[(&str, &str)] = &[
+    ("remote", "password"),
+    ("server", "token"),
+    ("github", "token"),
+    ("gitlab", "token"),
+    ("redmine", "api_key"),
+];
+
+/// User settings, read from `~/.timelogger.config.json`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// A directory to store the data file in, instead of the home directory.
+    pub data_dir: Option<PathBuf>,
+
+    /// The project to use when none is selected, which is created if it does not exist.
+    pub default_project: Option<String>,
+
+    /// Commit the data file to a git repository in the data directory after every change.
+    pub auto_commit: bool,
+
+    /// An age identity file used to encrypt and decrypt the data file.
+    pub age_identity: Option<PathBuf>,
+
+    /// A WebDAV or S3 server to synchronize the data file with.
+    pub remote: Option<RemoteConfig>,
+
+    /// A shared hat server to store the data file on instead of the local disk.
+    pub server: Option<ServerConfig>,
+
+    /// Description templates for `off --template`, keyed by name.
+    pub templates: HashMap<String, String>,
+
+    /// How many days deleted projects and entries are kept in the trash, 30 by default.
+    pub trash_retention_days: Option<u64>,
+
+    /// Rounds entries to the nearest multiple of this many minutes for billing when a timer is
+    /// stopped, unless the project has its own rounding.
+    pub round_minutes: Option<u64>,
+
+    /// Entries shorter than this, such as `60s`, prompt to be discarded or merged into the
+    /// previous entry when a timer is stopped.
+    pub minimum_duration: Option<String>,
+
+    /// Stopping a timer with the same description as the previous entry within this long of
+    /// it, such as `5m`, extends that entry instead of logging a new one.
+    pub merge_gap: Option<String>,
+
+    /// Refuse edits that make entries overlap in time, instead of only warning about them.
+    pub refuse_overlaps: bool,
+
+    /// Store each project's entries in its own file, so that changing one project doesn't
+    /// rewrite the others.
+    pub split_projects: bool,
+
+    /// Truncate entries and timers to whole seconds whenever the data file is saved.
+    pub whole_seconds: bool,
+
+    /// Check GitHub for a new release at most once a day, and mention it when there is one.
+    pub check_for_updates: bool,
+
+    /// Compress archive files with zstd, which must be installed, when entries are archived.
+    pub compress_archives: bool,
+
+    /// The columns `time` shows by default, such as `["start", "duration", "description"]`.
+    pub time_columns: Option<Vec<TimeColumn>>,
+
+    /// The columns `list` shows by default, such as `["project", "client", "total"]`.
+    pub list_columns: Option<Vec<ListColumn>>,
+
+    /// The columns `export csv` writes by default, such as `["date", "project", "hours"]`.
+    pub csv_columns: Option<Vec<CsvColumn>>,
+
+    /// The template `report` is written with, unless another is given with `--template`.
+    pub report_template: Option<PathBuf>,
+
+    /// The template `invoice` is written with, unless another is given with `--template`.
+    pub invoice_template: Option<PathBuf>,
+
+    /// The jobs `daemon` runs, such as a weekly report every Friday.
+    pub schedule: Vec<ScheduledJob>,
+
+    /// How much to work each day and week, and the most to work in a day.
+    pub goals: Option<Goals>,
+
+    /// Mention how many weekdays in a row time was logged on when a timer is stopped.
+    pub show_streaks: bool,
+
+    /// The day weeks start on, for `total --week` and `report --week`.
+    pub week_start: WeekStart,
+
+    /// The currency, such as `EUR`, that reports and invoices convert other currencies into.
+    pub base_currency: Option<String>,
+
+    /// How much of the base currency one unit of each other currency is worth, keyed by code.
+    pub exchange_rates: HashMap<String, f64>,
+
+    /// The tax rate in percent, such as `20` for 20% VAT, added to invoices for each client.
+    pub tax_rates: HashMap<String, f64>,
+
+    /// Breaks to deduct from entries when a timer is stopped.
+    pub break_rules: Vec<BreakRule>,
+
+    /// Tags added to entries whose description matches a pattern when they are logged or
+    /// imported.
+    pub auto_tags: Vec<AutoTagRule>,
+
+    /// Shorthands for commands, such as `done` for `off`, keyed by name.
+    pub aliases: HashMap<String, String>,
+
+    /// Where to look up GitHub issues, so that a description of `#123` becomes the issue's
+    /// title.
+    pub github: Option<GitHubConfig>,
+
+    /// Where `sync gitlab` posts the time spent on GitLab issues and merge requests.
+    pub gitlab: Option<GitLabConfig>,
+
+    /// The Linear workspace of each project, keyed by name, for `off --pick-issue` and
+    /// `sync linear`.
+    pub linear: HashMap<String, LinearConfig>,
+
+    /// Where `sync redmine` creates time entries for entries that mention Redmine issues.
+    pub redmine: Option<RedmineConfig>,
+}
+
+/// The day that weeks start on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    /// The time at the start of the week containing the given time.
+    pub fn start_of_week(self, epoch: Duration) -> Duration {
+        match self {
+            Self::Monday => date::start_of_week(epoch),
+            Self::Sunday => date::start_of_week(epoch + ONE_DAY) - ONE_DAY,
+        }
+    }
+
+    /// The time at the start of an ISO week, given the start of its Monday. Weeks starting on
+    /// Sunday begin the day before.
+    pub fn start_of_iso_week(self, monday: Duration) -> Duration {
+        match self {
+            Self::Monday => monday,
+            Self::Sunday => monday.saturating_sub(ONE_DAY),
+        }
+    }
+}
+
+const ONE_DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+impl Config {
+    /// Loads the config file from the home directory, falling back to the defaults.
+    pub fn load(home: &Path) -> Self {
+        let path = home.join(".timelogger.config.json");
+        log::debug!("Reading config file {}.", path.display());
+
+        fs::read_to_string(&path)
+            .map(|text| serde_json::from_str(&text).expect("Could not parse config file."))
+            .unwrap_or_else(|err| {
+                log::debug!("Using the default config: {err}.");
+                Self::default()
+            })
+    }
+
+    /// How many days deleted projects and entries are kept in the trash.
+    pub fn trash_retention_days(&self) -> u64 {
+        self.trash_retention_days.unwrap_or(30)
+    }
+
+    /// The directory that old entries are archived to.
+    pub fn archive_dir(&self, home: &Path) -> PathBuf {
+        match &self.data_dir {
+            Some(dir) => dir.join("archive"),
+            None => home.join(".local/share/hat-changer/archive"),
+        }
+    }
+
+    /// The directory each project's entries are stored in when `split_projects` is set.
+    pub fn projects_dir(&self, home: &Path) -> PathBuf {
+        match &self.data_dir {
+            Some(dir) => dir.join("projects"),
+            None => home.join(".local/share/hat-changer/projects"),
+        }
+    }
+
+    /// The file that remembers when the last check for a new release was.
+    pub fn update_check_path(&self, home: &Path) -> PathBuf {
+        match &self.data_dir {
+            Some(dir) => dir.join("update-check.json"),
+            None => home.join(".local/share/hat-changer/update-check.json"),
+        }
+    }
+
+    /// The path of the file recording which goals were already mentioned.
+    pub fn goals_state_path(&self, home: &Path) -> PathBuf {
+        match &self.data_dir {
+            Some(dir) => dir.join("goals.json"),
+            None => home.join(".local/share/hat-changer/goals.json"),
+        }
+    }
+
+    /// The path of the data file.
+    pub fn data_path(&self, home: &Path) -> PathBuf {
+        match &self.data_dir {
+            Some(dir) => dir.join(DATA_FILE_NAME),
+            None => home.join(format!(".{DATA_FILE_NAME}")),
+        }
+    }
+
+    /// The settings as JSON with every credential replaced, so that they can be handed to
+    /// someone else, along with the names of the settings that were replaced.
+    pub fn redacted(&self) -> (Value, Vec<String>) {
+        let mut settings = serde_json::to_value(self).expect("Could not serialize config.");
+        let mut redacted = Vec::new();
+
+        for (section, key) in CREDENTIALS {
+            if let Some(value) = settings
+                .get_mut(section)
+                .and_then(|value| value.get_mut(key))
+            {
+                redact(value, format!("{section}.{key}"), &mut redacted);
+            }
+        }
+
+        if let Some(linear) = settings["linear"].as_object_mut() {
+            for (project, workspace) in linear {
+                if let Some(value) = workspace.get_mut("api_key") {
+                    redact(value, format!("linear.{project}.api_key"), &mut redacted);
+                }
+            }
+        }
+
+        (settings, redacted)
+    }
+}
+
+/// Replaces a credential that is set, and records its name.
+fn redact(value: &mut Value, name: String, redacted: &mut Vec<String>) {
+    if !value.is_null() {
+        *value = "<redacted>".into();
+        redacted.push(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+
+    #[test]
+    fn credentials_are_redacted() {
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "remote": {
+                "kind": "webdav",
+                "url": "https://dav.example.com/hat.json",
+                "password": "hunter2",
+            },
+            "server": { "url": "https://hat.example.com", "token": "server-token" },
+            "github": { "token": "ghp_secret" },
+            "gitlab": { "token": "glpat-secret" },
+            "redmine": { "url": "https://redmine.example.com", "api_key": "redmine-key" },
+            "linear": {
+                "web": { "api_key": "lin_api_web", "team": "ENG" },
+                "app": { "api_key": "lin_api_app" },
+            },
+        }))
+        .unwrap();
+
+        let (settings, redacted) = config.redacted();
+        let text = settings.to_string();
+
+        for secret in [
+            "hunter2",
+            "server-token",
+            "ghp_secret",
+            "glpat-secret",
+            "lin_api_web",
+            "lin_api_app",
+            "redmine-key",
+        ] {
+            assert!(!text.contains(secret), "{secret} was exported");
+        }
+
+        assert_eq!(
+            redacted,
+            [
+                "remote.password",
+                "server.token",
+                "github.token",
+                "gitlab.token",
+                "redmine.api_key",
+                "linear.app.api_key",
+                "linear.web.api_key",
+            ]
+        );
+        assert_eq!(
+            settings["remote"]["url"],
+            "https://dav.example.com/hat.json"
+        );
+        assert_eq!(settings["linear"]["web"]["team"], "ENG");
+    }
+
+    #[test]
+    fn unset_credentials_are_not_listed() {
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "github": { "repos": { "web": "acme/web" } },
+        }))
+        .unwrap();
+
+        let (settings, redacted) = config.redacted();
+
+        assert!(redacted.is_empty());
+        assert!(settings["github"]["token"].is_null());
+    }
+}
@@ -0,0 +1,60 @@
+use std::{
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+};
+
+use crate::{log, Error, Result};
+
+/// The header at the start of every binary age file.
+const AGE_HEADER: &[u8] = b"age-encryption.org/";
+
+/// The header at the start of every ASCII-armored age file.
+const AGE_ARMOR_HEADER: &[u8] = b"-----BEGIN AGE ENCRYPTED FILE-----";
+
+/// Whether the contents of a file were encrypted with age.
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(AGE_HEADER) || bytes.starts_with(AGE_ARMOR_HEADER)
+}
+
+fn age(args: &[&str], identity: &Path, input: &[u8]) -> Result<Vec<u8>> {
+    log::debug!("Running age {} on {} bytes.", args.join(" "), input.len());
+
+    let mut child = Command::new("age")
+        .args(args)
+        .arg("--identity")
+        .arg(identity)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+
+    // The data file can be larger than the pipe buffer, so the output is read while writing.
+    let output = thread::scope(|scope| {
+        let writer = scope.spawn(move || stdin.write_all(input));
+        let output = child.wait_with_output();
+        writer.join().expect("The age input thread panicked.")?;
+        output
+    })?;
+
+    if !output.status.success() {
+        return Err(Error::Encryption(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Encrypts the data to the recipient of the age identity file.
+pub fn encrypt(identity: &Path, plaintext: &[u8]) -> Result<Vec<u8>> {
+    age(&["--encrypt"], identity, plaintext)
+}
+
+/// Decrypts the data with the age identity file.
+pub fn decrypt(identity: &Path, ciphertext: &[u8]) -> Result<Vec<u8>> {
+    age(&["--decrypt"], identity, ciphertext)
+}
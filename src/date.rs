@@ -0,0 +1,313 @@
+use std::time::Duration;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Converts a date in the proleptic Gregorian calendar to days since the epoch.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Converts days since the epoch to a year, month, and day.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// The number of whole days between the epoch and the given time.
+pub fn day_number(epoch: Duration) -> i64 {
+    (epoch.as_secs() / SECONDS_PER_DAY) as i64
+}
+
+/// The time at the start of a day, given as days since the epoch.
+pub fn start_of_day(days: i64) -> Duration {
+    Duration::from_secs(days.max(0) as u64 * SECONDS_PER_DAY)
+}
+
+/// The time at the start of the Monday of the week containing the given time.
+pub fn start_of_week(epoch: Duration) -> Duration {
+    let days = day_number(epoch);
+    // The epoch was a Thursday, three days after a Monday.
+    start_of_day(days - (days + 3).rem_euclid(7))
+}
+
+/// The ISO 8601 year and number of the week containing the given time.
+pub fn iso_week(epoch: Duration) -> (i64, u32) {
+    let days = day_number(epoch);
+    // ISO weeks belong to the year that their Thursday is in.
+    let thursday = days - (days + 3).rem_euclid(7) + 3;
+    let (year, _, _) = civil_from_days(thursday);
+    let week = (thursday - days_from_civil(year, 1, 1)) / 7 + 1;
+    (year, week as u32)
+}
+
+/// Parses an ISO 8601 week such as `2024-W23` into the time at the start of its Monday.
+pub fn parse_iso_week(text: &str) -> Option<Duration> {
+    let (year, week) = text.trim().split_once(['W', 'w'])?;
+    let year: i64 = year.strip_suffix('-').unwrap_or(year).parse().ok()?;
+    let week: u32 = week.parse().ok()?;
+
+    // The first week of a year is the one containing January 4th.
+    let january_4th = days_from_civil(year, 1, 4);
+    let monday = january_4th - (january_4th + 3).rem_euclid(7) + (week as i64 - 1) * 7;
+
+    // Years have 52 or 53 weeks, so week 53 may belong to the next year.
+    let start = start_of_day(monday);
+    (week >= 1 && monday >= 0 && iso_week(start) == (year, week)).then_some(start)
+}
+
+/// Parses a `YYYY-MM` month into the time at the start of its first day, in UTC.
+pub fn parse_month(text: &str) -> Option<Duration> {
+    parse_date(&format!("{}-01", text.trim()))
+}
+
+/// The time at the start of the month after the one containing the given time.
+pub fn start_of_next_month(epoch: Duration) -> Duration {
+    let (year, month, _) = civil_from_days(day_number(epoch));
+    let (year, month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    start_of_day(days_from_civil(year, month, 1))
+}
+
+/// The time at the start of the month `months` after the one containing the given time, or
+/// before it if `months` is negative.
+pub fn add_months(epoch: Duration, months: i64) -> Duration {
+    let (year, month, _) = civil_from_days(day_number(epoch));
+    let index = year * 12 + i64::from(month) - 1 + months;
+    start_of_day(days_from_civil(
+        index.div_euclid(12),
+        index.rem_euclid(12) as u32 + 1,
+        1,
+    ))
+}
+
+/// Parses an `HH:MM` time of day into the time since the start of the day.
+pub fn parse_time_of_day(text: &str) -> Option<Duration> {
+    let (hours, minutes) = text.trim().split_once(':')?;
+    let hours: u64 = hours.parse().ok()?;
+    let minutes: u64 = minutes.parse().ok()?;
+
+    if hours > 24 || minutes > 59 || (hours == 24 && minutes > 0) {
+        return None;
+    }
+
+    Some(Duration::from_secs(hours * 60 * 60 + minutes * 60))
+}
+
+/// Parses a `YYYY-MM-DD` date into the time at the start of that day, in UTC.
+pub fn parse_date(text: &str) -> Option<Duration> {
+    let mut parts = text.trim().splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+
+    // Reject days that roll over into the next month, such as February 30th.
+    if civil_from_days(days) != (year, month, day) || days < 0 {
+        return None;
+    }
+
+    Some(start_of_day(days))
+}
+/// Formats a time as a `YYYY-MM-DD` date, in UTC.
+pub fn format_date(epoch: Duration) -> String {
+    let (year, month, day) = civil_from_days(day_number(epoch));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Formats a time as a `YYYY-MM-DD HH:MM` date and time, in UTC.
+pub fn format_date_time(epoch: Duration) -> String {
+    let minutes = epoch.as_secs() / 60 % (24 * 60);
+    format!(
+        "{} {:02}:{:02}",
+        format_date(epoch),
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// Parses a `YYYY-MM-DD HH:MM` date and time, in UTC. A time of day alone (`HH:MM`) is taken
+/// to be on the same day as `reference`, and a date alone to be at the start of that day.
+pub fn parse_date_time(text: &str, reference: Duration) -> Option<Duration> {
+    let text = text.trim();
+
+    match text.split_once(char::is_whitespace) {
+        Some((date, time)) => Some(parse_date(date)? + parse_time_of_day(time)?),
+        None if text.contains(':') => {
+            Some(start_of_day(day_number(reference)) + parse_time_of_day(text)?)
+        }
+        None => parse_date(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// 2024-06-03 00:00 UTC, a Monday.
+    const JUNE_3RD: Duration = Duration::from_secs(1_717_372_800);
+
+    const HOUR: u64 = 60 * 60;
+
+    #[test]
+    fn civil_days_round_trip() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2024, 6, 3), 19877);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+
+        for days in -1000..30_000 {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn parses_dates() {
+        assert_eq!(parse_date("2024-06-03"), Some(JUNE_3RD));
+        assert_eq!(parse_date(" 2024-06-03 "), Some(JUNE_3RD));
+        assert_eq!(
+            parse_date("2024-02-29"),
+            Some(Duration::from_secs(1_709_164_800))
+        );
+        assert_eq!(parse_date("1970-01-01"), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        for text in [
+            "2023-02-29",
+            "2024-02-30",
+            "2024-13-01",
+            "2024-00-10",
+            "2024-06-00",
+            "2024-06-32",
+            "1969-12-31",
+            "2024-06",
+            "June 3rd",
+            "",
+        ] {
+            assert_eq!(parse_date(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn parses_times_of_day() {
+        assert_eq!(parse_time_of_day("00:00"), Some(Duration::ZERO));
+        assert_eq!(
+            parse_time_of_day("9:05"),
+            Some(Duration::from_secs(9 * HOUR + 5 * 60))
+        );
+        assert_eq!(
+            parse_time_of_day("24:00"),
+            Some(Duration::from_secs(24 * HOUR))
+        );
+
+        for text in ["24:01", "25:00", "12:60", "12", "12:xx", ""] {
+            assert_eq!(parse_time_of_day(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn parses_dates_and_times() {
+        let evening = JUNE_3RD + Duration::from_secs(4 * 24 * HOUR + 17 * HOUR + 45 * 60);
+
+        assert_eq!(
+            parse_date_time("2024-06-07 17:45", Duration::ZERO),
+            Some(evening)
+        );
+        assert_eq!(parse_date_time("17:45", evening), Some(evening));
+        assert_eq!(parse_date_time("2024-06-03", evening), Some(JUNE_3RD));
+        assert_eq!(parse_date_time("2024-06-07 25:00", evening), None);
+    }
+
+    #[test]
+    fn formats_dates_and_times() {
+        assert_eq!(format_date(JUNE_3RD), "2024-06-03");
+        assert_eq!(format_date(Duration::ZERO), "1970-01-01");
+        assert_eq!(
+            format_date_time(JUNE_3RD + Duration::from_secs(9 * HOUR + 5 * 60 + 59)),
+            "2024-06-03 09:05"
+        );
+
+        let text = format_date_time(JUNE_3RD + Duration::from_secs(13 * HOUR));
+        assert_eq!(
+            parse_date_time(&text, Duration::ZERO),
+            Some(JUNE_3RD + Duration::from_secs(13 * HOUR))
+        );
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        assert_eq!(start_of_week(JUNE_3RD), JUNE_3RD);
+        assert_eq!(
+            start_of_week(JUNE_3RD + Duration::from_secs(6 * 24 * HOUR + 23 * HOUR)),
+            JUNE_3RD
+        );
+        assert_eq!(
+            start_of_week(JUNE_3RD - Duration::from_secs(1)),
+            JUNE_3RD - Duration::from_secs(7 * 24 * HOUR)
+        );
+    }
+
+    #[test]
+    fn iso_weeks() {
+        assert_eq!(iso_week(JUNE_3RD), (2024, 23));
+        assert_eq!(iso_week(parse_date("2021-01-03").unwrap()), (2020, 53));
+        assert_eq!(iso_week(parse_date("2026-12-31").unwrap()), (2026, 53));
+        assert_eq!(iso_week(parse_date("2021-12-31").unwrap()), (2021, 52));
+
+        assert_eq!(parse_iso_week("2024-W23"), Some(JUNE_3RD));
+        assert_eq!(parse_iso_week("2024w23"), Some(JUNE_3RD));
+        assert_eq!(
+            parse_iso_week("2020-W53"),
+            Some(Duration::from_secs(1_609_113_600))
+        );
+        assert_eq!(parse_iso_week("2021-W53"), None);
+        assert_eq!(parse_iso_week("2024-W0"), None);
+    }
+
+    #[test]
+    fn months() {
+        assert_eq!(parse_month("2024-06"), parse_date("2024-06-01"));
+        assert_eq!(parse_month("2024-13"), None);
+        assert_eq!(
+            start_of_next_month(JUNE_3RD),
+            parse_date("2024-07-01").unwrap()
+        );
+        assert_eq!(
+            start_of_next_month(parse_date("2024-12-31").unwrap()),
+            parse_date("2025-01-01").unwrap()
+        );
+        assert_eq!(add_months(JUNE_3RD, -6), parse_date("2023-12-01").unwrap());
+        assert_eq!(add_months(JUNE_3RD, 7), parse_date("2025-01-01").unwrap());
+    }
+}
@@ -0,0 +1,110 @@
+use colored::Colorize;
+use pretty_duration::pretty_duration;
+
+use crate::{LoggedTime, ProjectList};
+
+/// Prints the projects, entries, timers, and invoices that differ between two project lists,
+/// such as the list before and after a dry run.
+pub fn print_changes(before: &ProjectList, after: &ProjectList) {
+    let mut names: Vec<&String> = before
+        .projects
+        .keys()
+        .chain(after.projects.keys())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    let mut lines = Vec::new();
+
+    for name in names {
+        let (old, new) = (before.projects.get(name), after.projects.get(name));
+
+        match (old, new) {
+            (Some(_), None) => lines.push(format!("- project {}", name.bright_cyan())),
+            (None, Some(_)) => lines.push(format!("+ project {}", name.bright_cyan())),
+            _ => {}
+        }
+
+        let old_times = old.map(|project| project.logged_times.as_slice());
+        let new_times = new.map(|project| project.logged_times.as_slice());
+
+        for time in old_times.unwrap_or_default() {
+            if !new_times.unwrap_or_default().contains(time) {
+                lines.push(format!("- {}", entry(name, time)));
+            }
+        }
+
+        for time in new_times.unwrap_or_default() {
+            if !old_times.unwrap_or_default().contains(time) {
+                lines.push(format!("+ {}", entry(name, time)));
+            }
+        }
+
+        let old_running = old.is_some_and(|project| project.start_epoch.is_some());
+        let new_running = new.is_some_and(|project| project.start_epoch.is_some());
+
+        if old_running && !new_running {
+            lines.push(format!("  timer for {} stopped", name.bright_cyan()));
+        } else if !old_running && new_running {
+            lines.push(format!("  timer for {} started", name.bright_cyan()));
+        }
+    }
+
+    if before.active_project != after.active_project {
+        let active = after.active_project.as_deref().unwrap_or("none");
+        lines.push(format!("  active project set to {}", active.bright_cyan()));
+    }
+
+    for invoice in &after.invoices.invoices {
+        if !before.invoices.invoices.contains(invoice) {
+            lines.push(format!(
+                "+ invoice {} for {}",
+                format!("{:04}", invoice.number).bright_yellow(),
+                invoice.client.bright_blue()
+            ));
+        }
+    }
+
+    if lines.is_empty() {
+        println!("{}", "Nothing would change.".bright_yellow());
+        return;
+    }
+
+    println!("{}", "Would make these changes:".bright_yellow());
+
+    for line in lines {
+        println!("  {line}");
+    }
+}
+
+fn entry(project: &str, time: &LoggedTime) -> String {
+    let mut line = format!(
+        "{}: {} - {}",
+        project.bright_cyan(),
+        pretty_duration(&time.duration, None).bright_red(),
+        time.description.bright_blue()
+    );
+
+    if !time.tags.is_empty() {
+        let tags = format!("[{}]", time.tags.join(", ")).bright_magenta();
+        line.push_str(&format!(" {tags}"));
+    }
+
+    if let Some(billable) = time.billable {
+        let note = if billable {
+            "(billable)"
+        } else {
+            "(not billable)"
+        };
+        line.push_str(&format!(" {}", note.bright_yellow()));
+    }
+
+    if let Some(number) = time.invoice {
+        line.push_str(&format!(
+            " {}",
+            format!("(invoice {number:04})").bright_yellow()
+        ));
+    }
+
+    line
+}
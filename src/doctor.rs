@@ -0,0 +1,284 @@
+use std::{fmt, time::Duration};
+
+use colored::Colorize;
+
+use crate::{LoggedTime, ProjectList};
+
+/// A problem found while validating the data file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// Two entries of the same project overlap in time. Indices are zero-based.
+    Overlap {
+        project: String,
+        first: usize,
+        second: usize,
+    },
+
+    /// An entry was logged with no duration.
+    ZeroDuration { project: String, index: usize },
+
+    /// An entry ends after the current time.
+    FutureEntry { project: String, index: usize },
+
+    /// A running timer was started after the current time.
+    FutureTimer { project: String },
+
+    /// The active project does not exist.
+    DanglingActive(String),
+
+    /// Several projects have the same name when case is ignored.
+    CaseDuplicates(Vec<String>),
+}
+
+impl Issue {
+    /// A short hint on how the issue can be resolved.
+    pub fn suggestion(&self) -> String {
+        match self {
+            Self::Overlap { .. } => {
+                "Edit or undo one of the entries, or run `hat fix --dedupe` if they are duplicates."
+                    .to_string()
+            }
+            Self::ZeroDuration { .. } => "Run `hat fix --drop-zero` to remove it.".to_string(),
+            Self::FutureEntry { .. } => {
+                "Run `hat fix --clamp-future` to end it at the current time.".to_string()
+            }
+            Self::FutureTimer { .. } => {
+                "Run `hat fix --clamp-future` to restart the timer now.".to_string()
+            }
+            Self::DanglingActive(_) => {
+                "Select another project with `hat <project>` or recreate it with `hat new`."
+                    .to_string()
+            }
+            Self::CaseDuplicates(_) => "Delete or rename all but one of the projects.".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overlap {
+                project,
+                first,
+                second,
+            } => write!(
+                f,
+                "Entries #{} and #{} of project {} overlap.",
+                first + 1,
+                second + 1,
+                project.bright_cyan()
+            ),
+            Self::ZeroDuration { project, index } => write!(
+                f,
+                "Entry #{} of project {} has no duration.",
+                index + 1,
+                project.bright_cyan()
+            ),
+            Self::FutureEntry { project, index } => write!(
+                f,
+                "Entry #{} of project {} ends in the future.",
+                index + 1,
+                project.bright_cyan()
+            ),
+            Self::FutureTimer { project } => write!(
+                f,
+                "The timer for project {} was started in the future.",
+                project.bright_cyan()
+            ),
+            Self::DanglingActive(project) => write!(
+                f,
+                "The active project {} does not exist.",
+                project.bright_cyan()
+            ),
+            Self::CaseDuplicates(projects) => {
+                let names: Vec<String> = projects
+                    .iter()
+                    .map(|name| name.bright_cyan().to_string())
+                    .collect();
+                write!(f, "Projects {} differ only in case.", names.join(", "))
+            }
+        }
+    }
+}
+
+/// Checks the project list for inconsistencies, given the current time since the epoch.
+pub fn find_issues(list: &ProjectList, now: Duration) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    let mut names: Vec<&String> = list.projects.keys().collect();
+    names.sort();
+
+    for name in names.iter().copied() {
+        let project = &list.projects[name];
+
+        if project.start_epoch.is_some_and(|start| start > now) {
+            issues.push(Issue::FutureTimer {
+                project: name.clone(),
+            });
+        }
+
+        for (index, time) in project.logged_times.iter().enumerate() {
+            if time.duration.is_zero() {
+                issues.push(Issue::ZeroDuration {
+                    project: name.clone(),
+                    index,
+                });
+            }
+
+            if time.start_epoch + time.duration > now {
+                issues.push(Issue::FutureEntry {
+                    project: name.clone(),
+                    index,
+                });
+            }
+        }
+
+        let mut order: Vec<usize> = (0..project.logged_times.len()).collect();
+        order.sort_by_key(|&index| project.logged_times[index].start_epoch);
+
+        // A long entry can overlap several that start after it, not only the next one, so
+        // each entry is compared with the one that ends last among those before it.
+        let mut furthest: Option<usize> = None;
+
+        for index in order {
+            let time = &project.logged_times[index];
+
+            if let Some(previous) = furthest {
+                if project.logged_times[previous].end_epoch() > time.start_epoch {
+                    issues.push(Issue::Overlap {
+                        project: name.clone(),
+                        first: previous.min(index),
+                        second: previous.max(index),
+                    });
+                }
+            }
+
+            if furthest.is_none_or(|previous| {
+                project.logged_times[previous].end_epoch() < time.end_epoch()
+            }) {
+                furthest = Some(index);
+            }
+        }
+    }
+
+    if let Some(active) = &list.active_project {
+        if !list.projects.contains_key(active) {
+            issues.push(Issue::DanglingActive(active.clone()));
+        }
+    }
+
+    let mut seen: Vec<String> = Vec::new();
+
+    for name in names.iter() {
+        let lower = name.to_lowercase();
+
+        if seen.contains(&lower) {
+            continue;
+        }
+
+        let duplicates: Vec<String> = names
+            .iter()
+            .filter(|other| other.to_lowercase() == lower)
+            .map(|other| other.to_string())
+            .collect();
+
+        if duplicates.len() > 1 {
+            issues.push(Issue::CaseDuplicates(duplicates));
+        }
+
+        seen.push(lower);
+    }
+
+    issues
+}
+
+/// Removes entries that exactly duplicate an earlier entry of the same project.
+/// Returns the number of entries removed.
+pub fn dedupe(list: &mut ProjectList) -> usize {
+    let mut removed = 0;
+
+    for project in list.projects.values_mut() {
+        let mut kept: Vec<LoggedTime> = Vec::new();
+
+        for time in project.logged_times.drain(..) {
+            if kept.contains(&time) {
+                removed += 1;
+            } else {
+                kept.push(time);
+            }
+        }
+
+        project.logged_times = kept;
+        project.cached_total = None;
+    }
+
+    removed
+}
+
+/// Ends entries and timers that extend past the current time at the current time.
+/// Returns the number of entries and timers changed.
+pub fn clamp_future(list: &mut ProjectList, now: Duration) -> usize {
+    let mut changed = 0;
+
+    for project in list.projects.values_mut() {
+        if let Some(start) = project.start_epoch {
+            if start > now {
+                project.start_epoch = Some(now);
+                changed += 1;
+            }
+        }
+
+        for time in project.logged_times.iter_mut() {
+            if time.start_epoch + time.duration > now {
+                time.start_epoch = time.start_epoch.min(now);
+                time.duration = now - time.start_epoch;
+                project.cached_total = None;
+                changed += 1;
+            }
+        }
+    }
+
+    changed
+}
+
+/// Removes entries with no duration. Returns the number of entries removed.
+pub fn drop_zero(list: &mut ProjectList) -> usize {
+    let mut removed = 0;
+
+    for project in list.projects.values_mut() {
+        let before = project.logged_times.len();
+        project.logged_times.retain(|time| !time.duration.is_zero());
+        project.cached_total = None;
+        removed += before - project.logged_times.len();
+    }
+
+    removed
+}
+
+/// A targeted repair that can be applied by the fix command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repair {
+    Dedupe,
+    ClampFuture,
+    DropZero,
+}
+
+impl Repair {
+    /// Applies the repair, returning the number of entries and timers affected.
+    pub fn apply(self, list: &mut ProjectList, now: Duration) -> usize {
+        match self {
+            Self::Dedupe => dedupe(list),
+            Self::ClampFuture => clamp_future(list, now),
+            Self::DropZero => drop_zero(list),
+        }
+    }
+
+    /// A question asking whether the repair should be applied to `count` items.
+    pub fn prompt(self, count: usize) -> String {
+        match self {
+            Self::Dedupe => format!("Remove {count} duplicate entries?"),
+            Self::ClampFuture => format!("End {count} future entries and timers now?"),
+            Self::DropZero => format!("Remove {count} entries with no duration?"),
+        }
+    }
+}
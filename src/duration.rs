@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use go_parse_duration::parse_duration;
+
+/// Phrases that stand for an amount of time, replaced before the words are read.
+const PHRASES: [(&str, &str); 6] = [
+    ("half an hour", "30 minutes"),
+    ("half hour", "30 minutes"),
+    ("quarter of an hour", "15 minutes"),
+    ("quarter hour", "15 minutes"),
+    ("an hour", "1 hour"),
+    ("a minute", "1 minute"),
+];
+
+/// Words that are skipped between amounts, as in `2 hours and 15 minutes`.
+const FILLER: [&str; 1] = ["and"];
+
+/// Parses a duration such as `1h30m`, `90 min`, `1 hour 30`, `1.5 hours`, or `half an hour`.
+pub fn parse(text: &str) -> Option<Duration> {
+    let compact: String = text.split_whitespace().collect();
+
+    if let Ok(nanos) = parse_duration(&compact) {
+        return u64::try_from(nanos).ok().map(Duration::from_nanos);
+    }
+
+    let mut text = text.trim().to_lowercase();
+
+    for (phrase, replacement) in PHRASES {
+        text = text.replace(phrase, replacement);
+    }
+
+    parse_words(&text)
+}
+
+/// The number of seconds in a unit, such as `min` or `hours`.
+fn unit_seconds(word: &str) -> Option<u64> {
+    match word {
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(60 * 60),
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(60),
+        "s" | "sec" | "secs" | "second" | "seconds" => Some(1),
+        _ => None,
+    }
+}
+
+/// Reads amounts followed by units. An amount without a unit is taken to be in the unit below
+/// the previous one, as the 30 in `1 hour 30`.
+fn parse_words(text: &str) -> Option<Duration> {
+    let mut tokens = tokenize(text).into_iter().peekable();
+    let mut seconds = 0.0;
+    let mut previous_unit: Option<u64> = None;
+    let mut found = false;
+
+    while let Some(token) = tokens.next() {
+        let Token::Number(amount) = token else {
+            return None;
+        };
+
+        let unit = match tokens.peek() {
+            Some(Token::Word(word)) => {
+                let unit = unit_seconds(word)?;
+                tokens.next();
+                unit
+            }
+            _ => match previous_unit? {
+                3600 => 60,
+                60 => 1,
+                _ => return None,
+            },
+        };
+
+        seconds += amount * unit as f64;
+        previous_unit = Some(unit);
+        found = true;
+    }
+
+    found
+        .then(|| Duration::try_from_secs_f64(seconds).ok())
+        .flatten()
+}
+
+enum Token {
+    Number(f64),
+    Word(String),
+}
+
+/// Splits text into numbers and words, dropping filler words and punctuation.
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+                number.push(c);
+                chars.next();
+            }
+
+            // An unreadable number, such as `1.2.3`, can't match a unit and fails the parse.
+            match number.parse() {
+                Ok(number) => tokens.push(Token::Number(number)),
+                Err(_) => tokens.push(Token::Word(number)),
+            }
+        } else if c.is_alphabetic() {
+            let mut word = String::new();
+
+            while let Some(&c) = chars.peek().filter(|c| c.is_alphabetic()) {
+                word.push(c);
+                chars.next();
+            }
+
+            if !FILLER.contains(&word.as_str()) {
+                tokens.push(Token::Word(word));
+            }
+        } else {
+            chars.next();
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::parse;
+
+    fn minutes(minutes: u64) -> Option<Duration> {
+        Some(Duration::from_secs(minutes * 60))
+    }
+
+    #[test]
+    fn compact() {
+        assert_eq!(parse("1h30m"), minutes(90));
+        assert_eq!(parse("1h 30m"), minutes(90));
+        assert_eq!(parse("45s"), Some(Duration::from_secs(45)));
+        assert_eq!(parse("1.5h"), minutes(90));
+        assert_eq!(parse("250ms"), Some(Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn words() {
+        assert_eq!(parse("90 min"), minutes(90));
+        assert_eq!(parse("2 hours and 15 minutes"), minutes(135));
+        assert_eq!(parse("1.5 hours"), minutes(90));
+        assert_eq!(parse("1 Hour, 5 Mins"), minutes(65));
+        assert_eq!(parse("10 seconds"), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn amounts_without_units() {
+        // An amount without a unit is in the unit below the previous one.
+        assert_eq!(parse("1 hour 30"), minutes(90));
+        assert_eq!(parse("2 min 30"), Some(Duration::from_secs(150)));
+        assert_eq!(parse("30"), None);
+        assert_eq!(parse("1 sec 30"), None);
+    }
+
+    #[test]
+    fn phrases() {
+        assert_eq!(parse("half an hour"), minutes(30));
+        assert_eq!(parse("quarter of an hour"), minutes(15));
+        assert_eq!(parse("an hour and a minute"), minutes(61));
+        assert_eq!(parse("1 hour and half an hour"), minutes(90));
+    }
+
+    #[test]
+    fn rejects_nonsense() {
+        for text in ["", "hour", "1 fortnight", "1.2.3 hours", "-5m", "soon"] {
+            assert_eq!(parse(text), None, "{text}");
+        }
+    }
+}
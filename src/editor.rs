@@ -0,0 +1,43 @@
+use std::{env, fs, process::Command};
+
+use crate::{log, tempfile::TempFile, Error, Result};
+
+/// Opens the user's editor on a temporary file containing `initial`, and returns the
+/// edited text once the editor exits. The file is private to the user and deleted afterwards.
+pub fn edit(initial: &str) -> Result<String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    let file = TempFile::create(".txt", initial.as_bytes())?;
+    let path = file.path();
+
+    // The editor may include arguments, such as `code --wait`.
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    log::debug!("Opening {} in {editor}.", path.display());
+    let status = Command::new(program).args(parts).arg(path).status();
+
+    let text = fs::read_to_string(path);
+    drop(file);
+
+    if !status?.success() {
+        return Err(Error::EditorFailed(editor));
+    }
+
+    Ok(text?)
+}
+
+/// Opens the user's editor on `template`, and returns the text joined into a single line
+/// with lines starting with `#` removed.
+pub fn edit_line(template: &str) -> Result<String> {
+    let text = edit(template)?;
+
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+
+    Ok(lines.join(" "))
+}
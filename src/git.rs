@@ -0,0 +1,85 @@
+use std::{path::Path, process::Command};
+
+use crate::{log, Error, Result};
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    log::debug!("Running git -C {} {}.", dir.display(), args.join(" "));
+
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+
+    if !output.status.success() {
+        return Err(Error::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Commits the data files in `dir`, initializing the repository if needed.
+pub fn commit(dir: &Path, files: &[&Path], message: &str) -> Result<()> {
+    if !dir.join(".git").exists() {
+        git(dir, &["init", "--quiet"])?;
+    }
+
+    let files: Vec<String> = files
+        .iter()
+        .map(|file| file.to_string_lossy().to_string())
+        .collect();
+    let files: Vec<&str> = files.iter().map(String::as_str).collect();
+
+    // Adding a directory also stages the files removed from it.
+    git(dir, &[&["add", "--all", "--"], files.as_slice()].concat())?;
+
+    if git(
+        dir,
+        &[&["status", "--porcelain", "--"], files.as_slice()].concat(),
+    )?
+    .is_empty()
+    {
+        return Ok(());
+    }
+
+    git(
+        dir,
+        &[
+            &["commit", "--quiet", "--message", message, "--"],
+            files.as_slice(),
+        ]
+        .concat(),
+    )?;
+
+    Ok(())
+}
+
+/// Pulls changes from the upstream remote and pushes local commits to it.
+pub fn sync(dir: &Path) -> Result<()> {
+    git(dir, &["pull", "--rebase", "--quiet"])?;
+    git(dir, &["push", "--quiet"])?;
+    Ok(())
+}
+
+/// Fetches from the upstream remote, and returns how many commits would be pushed and pulled.
+pub fn pending(dir: &Path) -> Result<(usize, usize)> {
+    git(dir, &["fetch", "--quiet"])?;
+
+    let counts = git(
+        dir,
+        &["rev-list", "--left-right", "--count", "HEAD...@{upstream}"],
+    )?;
+    let mut counts = counts
+        .split_whitespace()
+        .map(|count| count.parse().unwrap_or_default());
+
+    Ok((
+        counts.next().unwrap_or_default(),
+        counts.next().unwrap_or_default(),
+    ))
+}
+
+/// The branch checked out in the repository containing `dir`, if there is one.
+pub fn current_branch(dir: &Path) -> Option<String> {
+    git(dir, &["rev-parse", "--abbrev-ref", "HEAD"])
+        .ok()
+        .filter(|branch| !branch.is_empty() && branch != "HEAD")
+}
@@ -0,0 +1,94 @@
+use std::{collections::HashMap, env, process::Command};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{log, tempfile};
+
+/// How long to wait for GitHub, so that stopping a timer is never held up for long.
+const TIMEOUT_SECS: &str = "5";
+
+/// Where to look up the titles of GitHub issues, read from the config file.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitHubConfig {
+    /// A personal access token, needed for private repositories. Defaults to the
+    /// `GITHUB_TOKEN` environment variable.
+    pub token: Option<String>,
+
+    /// The repository of each project, as `owner/name`. Projects whose URL points to a GitHub
+    /// repository don't need one.
+    pub repos: HashMap<String, String>,
+}
+
+impl GitHubConfig {
+    /// The repository of a project, from the config file or the project's URL.
+    pub fn repo(&self, project: &str, url: Option<&str>) -> Option<String> {
+        if let Some(repo) = self.repos.get(project) {
+            return Some(repo.clone());
+        }
+
+        let path = url?
+            .trim_end_matches('/')
+            .trim_end_matches(".git")
+            .split_once("github.com/")?
+            .1;
+
+        let mut parts = path.split('/');
+        let (owner, name) = (parts.next()?, parts.next()?);
+
+        (!owner.is_empty() && !name.is_empty()).then(|| format!("{owner}/{name}"))
+    }
+
+    /// Asks GitHub for the title of an issue or pull request. Failures, such as being offline,
+    /// are only logged, so that the reference is kept as it was typed.
+    pub fn issue_title(&self, repo: &str, number: u64) -> Option<String> {
+        let url = format!("https://api.github.com/repos/{repo}/issues/{number}");
+        log::debug!("Looking up the title of {url}.");
+
+        // The token goes in a config file rather than the arguments, which anyone can see.
+        let authorization = self
+            .token
+            .clone()
+            .or_else(|| env::var("GITHUB_TOKEN").ok())
+            .map(|token| format!("Authorization: Bearer {token}"));
+
+        let options: Vec<(&str, &str)> = authorization
+            .iter()
+            .map(|header| ("header", header.as_str()))
+            .collect();
+
+        let config = match tempfile::curl_config(&options) {
+            Ok(config) => config,
+            Err(err) => {
+                log::debug!("Could not write the curl config: {err}");
+                return None;
+            }
+        };
+
+        let mut command = Command::new("curl");
+        command
+            .args(["--silent", "--show-error", "--fail", "--location"])
+            .args(["--max-time", TIMEOUT_SECS])
+            .args(["--header", "Accept: application/vnd.github+json"])
+            .arg("--config")
+            .arg(config.path());
+
+        let output = match command.arg(&url).output() {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                let err = String::from_utf8_lossy(&output.stderr);
+                log::debug!("Could not look up the issue: {}", err.trim());
+                return None;
+            }
+            Err(err) => {
+                log::debug!("Could not run curl: {err}");
+                return None;
+            }
+        };
+
+        let issue: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+        let title = issue["title"].as_str()?.trim();
+
+        (!title.is_empty()).then(|| title.to_string())
+    }
+}
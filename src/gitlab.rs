@@ -0,0 +1,147 @@
+use std::{collections::HashMap, env, process::Command};
+
+use hat_changer::ProjectList;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    log, tempfile,
+    tracker::{self, SyncState},
+    Error, Result,
+};
+
+/// Where to post the time spent on GitLab issues and merge requests, read from the config file.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitLabConfig {
+    /// The GitLab instance, `https://gitlab.com` by default.
+    pub url: Option<String>,
+
+    /// A personal access token with the `api` scope. Defaults to the `GITLAB_TOKEN`
+    /// environment variable.
+    pub token: Option<String>,
+
+    /// The GitLab project of each project, as its path, such as `group/web`. Entries of other
+    /// projects are not synced.
+    pub projects: HashMap<String, String>,
+}
+
+/// Time to add to an issue or merge request for an entry that mentions it.
+#[derive(Debug, Clone)]
+pub struct Spend {
+    /// The name of the project the entry is logged for.
+    pub project: String,
+
+    /// The GitLab project of the issue or merge request.
+    pub path: String,
+
+    /// The reference to the issue or merge request, such as `#12` or `!3`.
+    pub reference: String,
+
+    /// When the entry started.
+    pub start_epoch: u64,
+
+    /// The minutes to add, which are negative if the entry was shortened since it was synced.
+    pub minutes: i64,
+}
+
+impl Spend {
+    /// The key the minutes posted for the entry are recorded under.
+    pub fn key(&self) -> String {
+        tracker::key(&self.project, self.start_epoch, &self.reference)
+    }
+}
+
+impl GitLabConfig {
+    /// Finds the time that is not posted yet, for every entry of a synced project that mentions
+    /// issues or merge requests. An entry's time is split evenly between the ones it mentions.
+    pub fn pending(&self, list: &ProjectList, state: &SyncState) -> Vec<Spend> {
+        let mut spends = Vec::new();
+
+        for (name, project) in &list.projects {
+            let Some(path) = self.projects.get(name) else {
+                continue;
+            };
+
+            for time in &project.logged_times {
+                let references: Vec<&String> = time
+                    .tickets
+                    .iter()
+                    .filter(|ticket| ticket.starts_with(['#', '!']))
+                    .collect();
+
+                if references.is_empty() {
+                    continue;
+                }
+
+                let minutes = (time.duration.as_secs() / references.len() as u64 + 30) as i64 / 60;
+
+                for reference in references {
+                    let mut spend = Spend {
+                        project: name.clone(),
+                        path: path.clone(),
+                        reference: reference.clone(),
+                        start_epoch: time.start_epoch.as_secs(),
+                        minutes,
+                    };
+
+                    spend.minutes -= state.posted(&spend.key());
+
+                    if spend.minutes != 0 {
+                        spends.push(spend);
+                    }
+                }
+            }
+        }
+
+        spends
+    }
+
+    /// Adds time to an issue or merge request with GitLab's time tracking API, as `/spend`
+    /// does.
+    pub fn add_spent_time(&self, spend: &Spend) -> Result<()> {
+        let (kind, iid) = match spend.reference.split_at(1) {
+            ("!", iid) => ("merge_requests", iid),
+            (_, iid) => ("issues", iid),
+        };
+
+        let base = self.url.as_deref().unwrap_or("https://gitlab.com");
+        let url = format!(
+            "{}/api/v4/projects/{}/{kind}/{iid}/add_spent_time",
+            base.trim_end_matches('/'),
+            spend.path.replace('/', "%2F")
+        );
+
+        log::debug!("Adding {} minutes to {url}.", spend.minutes);
+
+        // The token goes in a config file rather than the arguments, which anyone can see.
+        let header = self
+            .token
+            .clone()
+            .or_else(|| env::var("GITLAB_TOKEN").ok())
+            .map(|token| format!("PRIVATE-TOKEN: {token}"));
+
+        let options: Vec<(&str, &str)> = header
+            .iter()
+            .map(|header| ("header", header.as_str()))
+            .collect();
+
+        let config = tempfile::curl_config(&options)?;
+
+        let output = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--request", "POST"])
+            .arg("--config")
+            .arg(config.path())
+            .arg("--data-urlencode")
+            .arg(format!("duration={}m", spend.minutes))
+            .arg(&url)
+            .output()?;
+
+        if !output.status.success() {
+            return Err(Error::GitLab(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use std::{fs, path::Path, process::Command, time::Duration};
+
+use hat_changer::{date, ProjectList};
+use pretty_duration::pretty_duration;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::WeekStart, duration, log, Error, Result};
+
+/// How much time to work, across every project, which `hat` mentions once when it is reached.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Goals {
+    /// The time to work each day, such as `7h 30m`.
+    pub daily: Option<String>,
+
+    /// The time to work each week, such as `38h`.
+    pub weekly: Option<String>,
+
+    /// The most time to work in a day, such as `10h`, which is warned about once it is passed.
+    pub daily_maximum: Option<String>,
+
+    /// Show a desktop notification too, with `notify-send` on Linux and `osascript` on macOS.
+    pub desktop_notifications: bool,
+}
+
+/// The days that each goal was last mentioned on, kept between runs so that it is mentioned
+/// once. Weeks are recorded by the day they start.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NotifiedState {
+    daily: Option<i64>,
+    weekly: Option<i64>,
+    daily_maximum: Option<i64>,
+}
+
+/// Finds the goals reached since they were last mentioned, records them as mentioned, and
+/// returns a message for each. Running timers count towards today.
+pub fn check(
+    goals: &Goals,
+    list: &ProjectList,
+    now: Duration,
+    week_start: WeekStart,
+    state_path: &Path,
+) -> Result<Vec<String>> {
+    let mut state: NotifiedState = fs::read(state_path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default();
+
+    let today = date::day_number(now);
+    let week = date::day_number(week_start.start_of_week(now));
+
+    let worked_since = |from: Duration| -> Duration {
+        list.projects
+            .values()
+            .map(|project| {
+                let logged: Duration = project
+                    .entries_between(Some(from), None)
+                    .iter()
+                    .map(|time| time.duration)
+                    .sum();
+
+                logged + project.elapsed(now).unwrap_or_default()
+            })
+            .sum()
+    };
+
+    let worked_today = worked_since(date::start_of_day(today));
+    let worked_this_week = worked_since(date::start_of_day(week));
+
+    let mut messages = Vec::new();
+    let format = |duration: Duration| pretty_duration(&duration, None);
+
+    if let Some(goal) = parse_goal(&goals.daily)? {
+        if worked_today >= goal && state.daily != Some(today) {
+            state.daily = Some(today);
+            messages.push(format!("You reached your daily goal of {}.", format(goal)));
+        }
+    }
+
+    if let Some(goal) = parse_goal(&goals.weekly)? {
+        if worked_this_week >= goal && state.weekly != Some(week) {
+            state.weekly = Some(week);
+            messages.push(format!("You reached your weekly goal of {}.", format(goal)));
+        }
+    }
+
+    if let Some(maximum) = parse_goal(&goals.daily_maximum)? {
+        if worked_today > maximum && state.daily_maximum != Some(today) {
+            state.daily_maximum = Some(today);
+            messages.push(format!(
+                "You have worked more than your daily maximum of {} today. Time to stop.",
+                format(maximum)
+            ));
+        }
+    }
+
+    if messages.is_empty() {
+        return Ok(messages);
+    }
+
+    if let Some(dir) = state_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    fs::write(
+        state_path,
+        serde_json::to_vec_pretty(&state).expect("Could not serialize JSON file."),
+    )?;
+
+    Ok(messages)
+}
+
+fn parse_goal(text: &Option<String>) -> Result<Option<Duration>> {
+    text.as_deref()
+        .map(|text| duration::parse(text).ok_or_else(|| Error::InvalidDuration(text.to_string())))
+        .transpose()
+}
+
+/// Shows a desktop notification. Failures are only logged, since notifications are a bonus.
+pub fn notify_desktop(message: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification \"{}\" with title \"hat\"",
+            message.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+
+        let mut command = Command::new("osascript");
+        command.args(["-e", &script]);
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.args(["hat", message]);
+        command
+    };
+
+    log::debug!("Showing a desktop notification: {message}");
+
+    match command.status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => log::debug!("Could not show a desktop notification: {status}"),
+        Err(err) => log::debug!("Could not show a desktop notification: {err}"),
+    }
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use hat_changer::{date, LoggedTime};
+
+/// The blocks that draw the last part of a bar, in eighths of a column.
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+const ONE_HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// The time logged on each weekday, starting from Monday, with entries that run past midnight
+/// split between their days.
+pub fn by_weekday<'a>(times: impl IntoIterator<Item = &'a LoggedTime>) -> [Duration; 7] {
+    let mut totals = [Duration::ZERO; 7];
+
+    for (start, duration) in split(times, ONE_HOUR * 24) {
+        // The epoch was a Thursday, three days after a Monday.
+        let weekday = (date::day_number(start) + 3).rem_euclid(7) as usize;
+        totals[weekday] += duration;
+    }
+
+    totals
+}
+
+/// The time logged in each hour of the day, in UTC, with entries split between the hours
+/// they cover.
+pub fn by_hour<'a>(times: impl IntoIterator<Item = &'a LoggedTime>) -> [Duration; 24] {
+    let mut totals = [Duration::ZERO; 24];
+
+    for (start, duration) in split(times, ONE_HOUR) {
+        totals[(start.as_secs() / 60 / 60 % 24) as usize] += duration;
+    }
+
+    totals
+}
+
+/// The time logged on each of `days` days from `first`, given as days since the epoch, with
+/// entries that run past midnight split between their days.
+pub fn by_day<'a>(
+    times: impl IntoIterator<Item = &'a LoggedTime>,
+    first: i64,
+    days: usize,
+) -> Vec<Duration> {
+    let mut totals = vec![Duration::ZERO; days];
+
+    for (start, duration) in split(times, ONE_HOUR * 24) {
+        let day = date::day_number(start) - first;
+
+        if let Some(total) = usize::try_from(day)
+            .ok()
+            .and_then(|day| totals.get_mut(day))
+        {
+            *total += duration;
+        }
+    }
+
+    totals
+}
+
+/// Splits entries into the parts that fall within each period of `length`, as their start and
+/// duration. Periods are counted from the epoch.
+fn split<'a>(
+    times: impl IntoIterator<Item = &'a LoggedTime>,
+    length: Duration,
+) -> Vec<(Duration, Duration)> {
+    let mut parts = Vec::new();
+
+    for time in times {
+        let mut start = time.start_epoch;
+        let end = time.end_epoch();
+
+        while start < end {
+            let period = start.as_secs() / length.as_secs();
+            let period_end = Duration::from_secs((period + 1) * length.as_secs());
+            let part_end = end.min(period_end);
+
+            parts.push((start, part_end - start));
+            start = part_end;
+        }
+    }
+
+    parts
+}
+
+/// A bar of up to `width` columns, as long relative to `width` as `value` is to `max`.
+pub fn bar(value: Duration, max: Duration, width: usize) -> String {
+    if max.is_zero() {
+        return String::new();
+    }
+
+    let eighths = (value.as_secs_f64() / max.as_secs_f64() * (width * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    let partial = eighths % 8;
+
+    if partial > 0 {
+        bar.push(PARTIAL_BLOCKS[partial]);
+    }
+
+    bar
+}
+
+/// The widths of the segments of a bar stacked from `values`, which is as long relative to
+/// `width` as their sum is to `max`. The ends of the segments are rounded rather than their
+/// widths, so that the segments add up to the rounded length of the whole bar.
+pub fn stacked(values: &[Duration], max: Duration, width: usize) -> Vec<usize> {
+    if max.is_zero() {
+        return vec![0; values.len()];
+    }
+
+    let mut sum = Duration::ZERO;
+    let mut end = 0;
+
+    values
+        .iter()
+        .map(|&value| {
+            sum += value;
+
+            let start = end;
+            end = (sum.as_secs_f64() / max.as_secs_f64() * width as f64).round() as usize;
+            end - start
+        })
+        .collect()
+}
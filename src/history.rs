@@ -0,0 +1,252 @@
+use std::time::Duration;
+
+use crate::{date, duration, Error, LoggedTime, Result};
+
+/// An entry as listed for `edit --interactive`, identified by its number in `time -v`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct EditedEntry {
+    pub id: usize,
+    pub start_epoch: Duration,
+    pub duration: Duration,
+    pub description: String,
+}
+
+/// Lists entries one per line, for the user to change in their editor.
+pub fn format(project: &str, entries: &[(usize, &LoggedTime)]) -> String {
+    let mut text = format!(
+        "# Entries of project {project}, one per line: id, start date and time (UTC), duration,\n\
+         # and description. Change any of them except the id. Lines that are removed are left\n\
+         # unchanged, and nothing is saved if any line is invalid.\n"
+    );
+
+    for (index, time) in entries {
+        text.push_str(&line(index + 1, time));
+        text.push('\n');
+    }
+
+    text
+}
+
+/// The line listing an entry.
+pub fn line(id: usize, time: &LoggedTime) -> String {
+    format!(
+        "#{id}  {}  {}  {}",
+        date::format_date_time(time.start_epoch),
+        format_duration(time.duration),
+        time.description
+    )
+}
+
+/// Formats a duration in whole seconds in the form accepted by `edit`, such as `1h30m5s`.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    let mut text = String::new();
+
+    if hours > 0 {
+        text.push_str(&format!("{hours}h"));
+    }
+
+    if minutes > 0 {
+        text.push_str(&format!("{minutes}m"));
+    }
+
+    if seconds > 0 || text.is_empty() {
+        text.push_str(&format!("{seconds}s"));
+    }
+
+    text
+}
+
+/// Parses the edited list, skipping blank lines and comments.
+pub fn parse(text: &str) -> Result<Vec<EditedEntry>> {
+    let mut entries: Vec<EditedEntry> = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+
+        // Ids start with `#` too, so comments are told apart by the space after it.
+        if line.is_empty() || (line.starts_with('#') && !line[1..].starts_with(char::is_numeric)) {
+            continue;
+        }
+
+        let entry = parse_line(line)
+            .map_err(|reason| Error::InvalidEditedLine(number + 1, reason.to_string()))?;
+
+        if entries.iter().any(|other| other.id == entry.id) {
+            return Err(Error::InvalidEditedLine(
+                number + 1,
+                format!("entry #{} is listed more than once", entry.id),
+            ));
+        }
+
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+fn parse_line(line: &str) -> std::result::Result<EditedEntry, &'static str> {
+    let (id, rest) = split_word(line).ok_or("the id is missing")?;
+    let (day, rest) = split_word(rest).ok_or("the date is missing")?;
+    let (time_of_day, rest) = split_word(rest).ok_or("the time is missing")?;
+    let (duration, description) = split_word(rest).ok_or("the duration is missing")?;
+
+    let id = id
+        .strip_prefix('#')
+        .and_then(|id| id.parse().ok())
+        .ok_or("the id must be a number such as #3")?;
+    let day = date::parse_date(day).ok_or("the date must be in the YYYY-MM-DD format")?;
+    let time_of_day =
+        date::parse_time_of_day(time_of_day).ok_or("the time must be in the HH:MM format")?;
+    let duration =
+        duration::parse(duration).ok_or("the duration must be in a form such as 1h30m")?;
+
+    let description = description.trim();
+
+    if description.is_empty() {
+        return Err("the description is missing");
+    }
+
+    Ok(EditedEntry {
+        id,
+        start_epoch: day + time_of_day,
+        duration,
+        description: description.to_string(),
+    })
+}
+
+/// Splits the first word off of some text, returning it and the rest of the text.
+fn split_word(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_start();
+
+    if text.is_empty() {
+        return None;
+    }
+
+    Some(text.split_once(char::is_whitespace).unwrap_or((text, "")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{format, format_duration, line, parse, EditedEntry};
+    use crate::{Error, LoggedTime};
+
+    /// 2024-06-03 09:00 UTC.
+    const START: Duration = Duration::from_secs(1_717_405_200);
+
+    fn entry(start_epoch: Duration, duration: Duration, description: &str) -> LoggedTime {
+        LoggedTime {
+            start_epoch,
+            duration,
+            description: description.to_string(),
+            notes: None,
+            tags: Vec::new(),
+            billable: None,
+            rounded: None,
+            invoice: None,
+            tickets: Vec::new(),
+        }
+    }
+
+    fn invalid_line(text: &str) -> Option<usize> {
+        match parse(text) {
+            Err(Error::InvalidEditedLine(number, _)) => Some(number),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn durations_are_written_as_edit_reads_them() {
+        assert_eq!(format_duration(Duration::ZERO), "0s");
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(5400)), "1h30m");
+        assert_eq!(format_duration(Duration::from_secs(3605)), "1h5s");
+        assert_eq!(format_duration(Duration::from_millis(1999)), "1s");
+    }
+
+    #[test]
+    fn lines() {
+        let time = entry(START, Duration::from_secs(5400), "Sprint planning");
+        assert_eq!(
+            line(3, &time),
+            "#3  2024-06-03 09:00  1h30m  Sprint planning"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let first = entry(START, Duration::from_secs(5400), "Sprint planning");
+        let second = entry(
+            START + Duration::from_secs(2 * 60 * 60),
+            Duration::from_secs(61),
+            "Review #12 with  two  spaces",
+        );
+
+        let text = format("web", &[(0, &first), (4, &second)]);
+        let entries = parse(&text).unwrap();
+
+        assert_eq!(
+            entries,
+            [
+                EditedEntry {
+                    id: 1,
+                    start_epoch: START,
+                    duration: Duration::from_secs(5400),
+                    description: "Sprint planning".to_string(),
+                },
+                EditedEntry {
+                    id: 5,
+                    start_epoch: START + Duration::from_secs(2 * 60 * 60),
+                    duration: Duration::from_secs(61),
+                    description: "Review #12 with  two  spaces".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn edited_fields() {
+        // The duration is a single word, so `45 min` reads as a duration of `45`.
+        assert_eq!(invalid_line("#2 2024-06-04 13:15 45 min  Pairing"), Some(1));
+
+        let entries = parse("  #2   2024-06-04 13:15   45m   Pairing  ").unwrap();
+        assert_eq!(entries[0].id, 2);
+        assert_eq!(
+            entries[0].start_epoch,
+            START + Duration::from_secs(24 * 60 * 60 + 4 * 60 * 60 + 15 * 60)
+        );
+        assert_eq!(entries[0].duration, Duration::from_secs(45 * 60));
+        assert_eq!(entries[0].description, "Pairing");
+    }
+
+    #[test]
+    fn skips_comments_and_blank_lines() {
+        let text = "# A comment\n\n#not an id either\n   \n#1 2024-06-03 09:00 1h One\n";
+        assert_eq!(parse(text).unwrap().len(), 1);
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn reports_the_invalid_line() {
+        assert_eq!(
+            invalid_line("# comment\n1 2024-06-03 09:00 1h No hash"),
+            Some(2)
+        );
+        assert_eq!(invalid_line("#1 2024-02-30 09:00 1h Bad date"), Some(1));
+        assert_eq!(invalid_line("#1 2024-06-03 9am 1h Bad time"), Some(1));
+        assert_eq!(
+            invalid_line("#1 2024-06-03 09:00 soon Bad duration"),
+            Some(1)
+        );
+        assert_eq!(invalid_line("#1 2024-06-03 09:00 1h"), Some(1));
+        assert_eq!(invalid_line("#1 2024-06-03"), Some(1));
+        assert_eq!(
+            invalid_line("#1 2024-06-03 09:00 1h One\n\n#1 2024-06-03 10:00 1h Two"),
+            Some(3)
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::LoggedTime;
+
+/// The start of each of a project's entries, in whole seconds, with the entry's position,
+/// sorted by start. Entries in a range of dates are found with a binary search instead of
+/// scanning every entry.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DateIndex(Vec<(u64, usize)>);
+
+impl DateIndex {
+    /// Indexes the entries of a project.
+    pub fn build(times: &[LoggedTime]) -> Self {
+        let mut starts: Vec<(u64, usize)> = times
+            .iter()
+            .enumerate()
+            .map(|(position, time)| (time.start_epoch.as_secs(), position))
+            .collect();
+
+        starts.sort_unstable();
+        Self(starts)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether the index still matches every entry, which takes a scan of them all.
+    pub fn is_current(&self, times: &[LoggedTime]) -> bool {
+        self.0.len() == times.len()
+            && self.0.iter().all(|&(start, position)| {
+                times
+                    .get(position)
+                    .is_some_and(|time| time.start_epoch.as_secs() == start)
+            })
+    }
+
+    /// The positions of the entries starting from `from` up to but not including `to`, sorted
+    /// by start. Returns `None` if the index is out of date, as far as can be told without
+    /// scanning every entry: entries were added or removed, or an entry in the range moved.
+    pub fn range(&self, times: &[LoggedTime], from: Duration, to: Duration) -> Option<Vec<usize>> {
+        if self.0.len() != times.len() {
+            return None;
+        }
+
+        let first = self.0.partition_point(|&(start, _)| start < from.as_secs());
+        let last = self.0.partition_point(|&(start, _)| start <= to.as_secs());
+        let mut positions = Vec::new();
+
+        for &(start, position) in &self.0[first..last] {
+            let time = times.get(position)?;
+
+            if time.start_epoch.as_secs() != start {
+                return None;
+            }
+
+            // Whole seconds can't tell apart entries starting within a second of the bounds.
+            if time.start_epoch >= from && time.start_epoch < to {
+                positions.push(position);
+            }
+        }
+
+        Some(positions)
+    }
+}
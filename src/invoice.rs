@@ -0,0 +1,104 @@
+use std::{fmt, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// Every invoice issued, along with the last number used so that numbers are never reused.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InvoiceRegistry {
+    pub last_number: u64,
+    pub invoices: Vec<Invoice>,
+}
+
+/// An invoice issued to a client for the time logged over a period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Invoice {
+    pub number: u64,
+    pub client: String,
+    pub issued_epoch: Duration,
+
+    /// The first day of the period invoiced.
+    pub from_epoch: Duration,
+
+    /// The last day of the period invoiced, which is included.
+    pub to_epoch: Duration,
+
+    /// The amount due, including tax.
+    pub amount: f64,
+
+    /// The tax included in the amount.
+    #[serde(default)]
+    pub tax: f64,
+
+    /// The currency of the amount, as a code such as `EUR`.
+    #[serde(default)]
+    pub currency: Option<String>,
+
+    pub status: InvoiceStatus,
+}
+
+/// What an invoice charges for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Charge {
+    /// The amount due, including tax.
+    pub amount: f64,
+
+    /// The tax included in the amount.
+    pub tax: f64,
+
+    /// The currency of the amount, as a code such as `EUR`.
+    pub currency: Option<String>,
+}
+
+/// Whether an invoice has been paid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InvoiceStatus {
+    #[default]
+    Outstanding,
+    Paid,
+}
+
+impl fmt::Display for InvoiceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Outstanding => write!(f, "outstanding"),
+            Self::Paid => write!(f, "paid"),
+        }
+    }
+}
+
+impl InvoiceRegistry {
+    /// The number the next invoice will be issued with.
+    pub fn next_number(&self) -> u64 {
+        // Imported invoices may be numbered past the counter, which must not reuse them.
+        let highest = self.invoices.iter().map(|invoice| invoice.number).max();
+        self.last_number.max(highest.unwrap_or(0)) + 1
+    }
+
+    /// Records an invoice under the next number, and returns it.
+    pub fn issue(
+        &mut self,
+        client: String,
+        issued_epoch: Duration,
+        period: (Duration, Duration),
+        charge: Charge,
+    ) -> &Invoice {
+        let number = self.next_number();
+        self.last_number = number;
+
+        self.invoices.push(Invoice {
+            number,
+            client,
+            issued_epoch,
+            from_epoch: period.0,
+            to_epoch: period.1,
+            amount: charge.amount,
+            tax: charge.tax,
+            currency: charge.currency,
+            status: InvoiceStatus::Outstanding,
+        });
+
+        &self.invoices[self.invoices.len() - 1]
+    }
+}
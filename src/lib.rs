@@ -0,0 +1,628 @@
+//! The core of hat-changer: the project list stored in the data file, and typed operations on
+//! it. Operations report what happened through their return values instead of printing, so
+//! other tools can reuse them.
+//!
+//! The core does not read the clock or assume a file system, so it also compiles to wasm32
+//! with `default-features = false`. Times are passed in as durations since the epoch, and
+//! data is read and written through a [`Store`].
+//!
+//! ```no_run
+//! use std::{path::Path, time::{SystemTime, UNIX_EPOCH}};
+//!
+//! let path = Path::new("timelogger.json");
+//! let mut list = hat_changer::ProjectList::load(path)?;
+//! let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+//!
+//! list.start("client-a", now)?;
+//! let entry = list.stop("client-a", "Code review", now)?;
+//! println!("Logged {:?}", entry.duration);
+//!
+//! list.save(path)?;
+//! # Ok::<(), hat_changer::Error>(())
+//! ```
+
+pub mod date;
+pub mod index;
+pub mod invoice;
+pub mod ticket;
+pub mod trash;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs, io, path::Path};
+
+use index::DateIndex;
+use invoice::InvoiceRegistry;
+use serde::{Deserialize, Serialize, Serializer};
+use thiserror::Error;
+use trash::TrashItem;
+
+/// An error returned by an operation on a project list.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("There is no project named {0}.")]
+    UnknownProject(String),
+
+    #[error("The timer is already running.")]
+    AlreadyStarted,
+
+    #[error("The timer is not running.")]
+    NotStarted,
+
+    #[error("No time has been logged for this project.")]
+    NoTimeLogged,
+
+    #[error("Cannot log entry with no description.")]
+    NoDescription,
+
+    #[error("An entry with the same start and description is already logged.")]
+    DuplicateEntry,
+
+    #[error("An error occurred while accessing the stored data: {0}")]
+    Store(String),
+
+    #[error("Could not parse the stored data: {0}")]
+    InvalidData(#[from] serde_json::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Where a project list is kept, such as a file or a browser's local storage.
+pub trait Store {
+    /// Reads the stored data, or returns `None` if nothing is stored yet.
+    fn read(&self) -> Result<Option<Vec<u8>>>;
+
+    /// Replaces the stored data.
+    fn write(&self, bytes: &[u8]) -> Result<()>;
+}
+
+/// Stores the project list in an unencrypted data file.
+#[cfg(not(target_arch = "wasm32"))]
+impl Store for Path {
+    fn read(&self) -> Result<Option<Vec<u8>>> {
+        match fs::read(self) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(Error::Store(err.to_string())),
+        }
+    }
+
+    fn write(&self, bytes: &[u8]) -> Result<()> {
+        fs::write(self, bytes).map_err(|err| Error::Store(err.to_string()))
+    }
+}
+
+/// Rounds a duration to the nearest multiple of the given number of minutes.
+pub fn round_to_minutes(duration: Duration, minutes: u64) -> Duration {
+    let step = minutes.max(1) * 60;
+    Duration::from_secs((duration.as_secs() + step / 2) / step * step)
+}
+
+/// Drops the fractional seconds of a duration, for display.
+pub fn whole_seconds(duration: Duration) -> Duration {
+    Duration::from_secs(duration.as_secs())
+}
+
+/// Every project and its entries, as stored in the data file.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ProjectList {
+    #[serde(serialize_with = "serialize_sorted")]
+    pub projects: HashMap<String, Project>,
+    pub active_project: Option<String>,
+    #[serde(default)]
+    pub modified_epoch: Option<Duration>,
+    #[serde(default)]
+    pub trash: Vec<TrashItem>,
+    #[serde(default)]
+    pub invoices: InvoiceRegistry,
+}
+
+/// Writes projects sorted by name, so that saving the data file doesn't reorder them.
+fn serialize_sorted<S: Serializer>(
+    projects: &HashMap<String, Project>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    let sorted: BTreeMap<&String, &Project> = projects.iter().collect();
+    sorted.serialize(serializer)
+}
+
+/// A project, with its running timer and logged entries.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub start_epoch: Option<Duration>,
+    pub logged_times: Vec<LoggedTime>,
+    #[serde(default)]
+    pub paused_epoch: Option<Duration>,
+    #[serde(default)]
+    pub paused_duration: Duration,
+
+    /// The pauses of the running timer that have ended, as their start and end, so that breaks
+    /// taken while paused are not deducted again.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pauses: Vec<(Duration, Duration)>,
+    #[serde(default)]
+    pub settings: ProjectSettings,
+    #[serde(default)]
+    pub metadata: ProjectMetadata,
+
+    /// The entries sorted by start, rebuilt with [`ProjectList::reindex`] after they change.
+    #[serde(default, skip_serializing_if = "DateIndex::is_empty")]
+    pub date_index: DateIndex,
+
+    /// The total duration of the entries, so that projects can be listed without reading every
+    /// entry. Operations that change entries clear it, and [`ProjectList::reindex`] sets it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_total: Option<Duration>,
+
+    /// When the entry that was logged most recently starts, so that `undo` and `edit` act on
+    /// it even if it was logged for a time before other entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_logged: Option<Duration>,
+}
+
+/// Descriptive information about a project.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectMetadata {
+    /// What the project is about.
+    pub description: Option<String>,
+
+    /// The client the project is for.
+    pub client: Option<String>,
+
+    /// The color the project is shown in.
+    pub color: Option<String>,
+
+    /// A link to the project, such as its repository or issue tracker.
+    pub url: Option<String>,
+}
+
+/// Settings that apply to a single project, stored with it in the data file.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectSettings {
+    /// Tags added to every entry logged for the project.
+    pub tags: Vec<String>,
+
+    /// Whether time spent on the project can be billed.
+    pub billable: bool,
+
+    /// The hourly rate charged for the project.
+    pub rate: Option<f64>,
+
+    /// The currency of the rate, as a code such as `EUR`.
+    pub currency: Option<String>,
+
+    /// Rounds entries to the nearest multiple of this many minutes for billing when the timer
+    /// is stopped.
+    pub round_minutes: Option<u64>,
+
+    /// A time of day (`HH:MM`, in UTC) at which a running timer counts as stopped.
+    pub auto_stop: Option<String>,
+
+    /// How long the project is expected to take in total.
+    pub estimate: Option<Duration>,
+}
+
+/// An entry of time logged for a project.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LoggedTime {
+    pub start_epoch: Duration,
+    pub duration: Duration,
+    pub description: String,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Overrides whether the project's time is billable, for this entry only.
+    #[serde(default)]
+    pub billable: Option<bool>,
+    /// The duration rounded for billing, if the entry was rounded. `duration` keeps the time
+    /// that was actually tracked.
+    #[serde(default)]
+    pub rounded: Option<Duration>,
+    /// The number of the invoice the entry was billed on, if it was.
+    #[serde(default)]
+    pub invoice: Option<u64>,
+    /// The tickets mentioned in the description, such as `#123` or `PROJ-42`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tickets: Vec<String>,
+}
+
+impl ProjectSettings {
+    /// When a timer started at `start` stops if it is stopped at `now`, which is earlier
+    /// than `now` if the auto-stop time passed in between.
+    pub fn stop_time(&self, start: Duration, now: Duration) -> Duration {
+        let Some(time_of_day) = self.auto_stop.as_deref().and_then(date::parse_time_of_day) else {
+            return now;
+        };
+
+        let mut auto_stop = date::start_of_day(date::day_number(start)) + time_of_day;
+
+        if auto_stop < start {
+            auto_stop += Duration::from_secs(24 * 60 * 60);
+        }
+
+        now.min(auto_stop)
+    }
+
+    /// Rounds a duration to the nearest multiple of the rounding interval, if there is one.
+    pub fn round(&self, duration: Duration) -> Option<Duration> {
+        self.round_minutes
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| round_to_minutes(duration, minutes))
+    }
+
+    /// Whether an entry of the project is billable, which the entry may override.
+    pub fn is_billable(&self, time: &LoggedTime) -> bool {
+        time.billable.unwrap_or(self.billable)
+    }
+
+    /// The amount billed for a duration at the hourly rate, if there is one.
+    pub fn amount(&self, duration: Duration) -> Option<f64> {
+        self.rate
+            .map(|rate| rate * duration.as_secs_f64() / (60.0 * 60.0))
+    }
+}
+
+impl LoggedTime {
+    /// The duration the entry is billed for, which is rounded if the entry was.
+    pub fn billed(&self) -> Duration {
+        self.rounded.unwrap_or(self.duration)
+    }
+
+    /// When the entry ended.
+    pub fn end_epoch(&self) -> Duration {
+        self.start_epoch + self.duration
+    }
+
+    /// Whether two entries overlap in wall-clock time.
+    pub fn overlaps(&self, other: &LoggedTime) -> bool {
+        self.start_epoch < other.end_epoch() && other.start_epoch < self.end_epoch()
+    }
+}
+
+impl Project {
+    /// The time tracked by the running timer so far, excluding pauses.
+    pub fn elapsed(&self, now: Duration) -> Option<Duration> {
+        let start = self.start_epoch?;
+        let paused = self.paused_duration
+            + self
+                .paused_epoch
+                .map_or(Duration::ZERO, |paused| now.saturating_sub(paused));
+
+        Some(now.saturating_sub(start).saturating_sub(paused))
+    }
+
+    /// The pauses of the running timer up to the given time, including the current one.
+    pub fn pauses(&self, now: Duration) -> Vec<(Duration, Duration)> {
+        let mut pauses = self.pauses.clone();
+        pauses.extend(self.paused_epoch.map(|paused| (paused, now.max(paused))));
+        pauses
+    }
+
+    /// Stops the running timer without logging it, and returns its start and the time it tracked.
+    pub fn take_timer(&mut self, now: Duration) -> Option<(Duration, Duration)> {
+        let elapsed = self.elapsed(now)?;
+        let start = self.start_epoch.take()?;
+        self.paused_epoch = None;
+        self.paused_duration = Duration::ZERO;
+        self.pauses.clear();
+        Some((start, elapsed))
+    }
+
+    /// The index of the entry that was logged most recently, or of the latest one if that is
+    /// not known.
+    pub fn last_logged_index(&self) -> Option<usize> {
+        self.last_logged
+            .and_then(|start| {
+                self.logged_times
+                    .iter()
+                    .rposition(|time| time.start_epoch == start)
+            })
+            .or_else(|| self.logged_times.len().checked_sub(1))
+    }
+
+    /// Whether an entry with the same start and description is already logged, which makes
+    /// `time` a probable duplicate.
+    pub fn is_duplicate(&self, time: &LoggedTime) -> bool {
+        self.logged_times.iter().any(|other| {
+            other.start_epoch == time.start_epoch && other.description == time.description
+        })
+    }
+
+    /// The entries starting from `from` up to but not including `to`, sorted by start. They
+    /// are found with the date index if it is up to date, and by scanning every entry otherwise.
+    pub fn entries_between(
+        &self,
+        from: Option<Duration>,
+        to: Option<Duration>,
+    ) -> Vec<&LoggedTime> {
+        let from = from.unwrap_or(Duration::ZERO);
+        let to = to.unwrap_or(Duration::MAX);
+
+        if let Some(positions) = self.date_index.range(&self.logged_times, from, to) {
+            return positions
+                .into_iter()
+                .map(|position| &self.logged_times[position])
+                .collect();
+        }
+
+        let mut times: Vec<&LoggedTime> = self
+            .logged_times
+            .iter()
+            .filter(|time| time.start_epoch >= from && time.start_epoch < to)
+            .collect();
+
+        times.sort_by_key(|time| time.start_epoch);
+        times
+    }
+
+    /// The total duration of the entries, from the cache if it is set.
+    pub fn total_logged(&self) -> Duration {
+        self.cached_total
+            .unwrap_or_else(|| self.logged_times.iter().map(|time| time.duration).sum())
+    }
+
+    /// The total time logged for the project, including its running timer.
+    pub fn total(&self, now: Duration) -> Duration {
+        let logged: Duration = self.logged_times.iter().map(|time| time.duration).sum();
+        logged + self.elapsed(now).unwrap_or_default()
+    }
+}
+
+impl ProjectList {
+    /// Loads the list from a store, or returns an empty list if nothing is stored yet.
+    pub fn load(store: &(impl Store + ?Sized)) -> Result<Self> {
+        match store.read()? {
+            Some(bytes) => Self::from_json(&bytes),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Saves the list to a store.
+    pub fn save(&self, store: &(impl Store + ?Sized)) -> Result<()> {
+        store.write(&self.to_json())
+    }
+
+    /// Rebuilds the date index and cached total of every project whose entries changed since
+    /// they were built, and returns how many projects were rebuilt.
+    pub fn reindex(&mut self) -> usize {
+        let mut rebuilt = 0;
+
+        for project in self.projects.values_mut() {
+            let total = project.logged_times.iter().map(|time| time.duration).sum();
+
+            if !project.date_index.is_current(&project.logged_times)
+                || project.cached_total != Some(total)
+            {
+                project.date_index = DateIndex::build(&project.logged_times);
+                project.cached_total = Some(total);
+                rebuilt += 1;
+            }
+        }
+
+        rebuilt
+    }
+
+    /// Links every entry to the tickets mentioned in its description, so that the links stay
+    /// current after descriptions are edited. Returns how many entries changed.
+    pub fn link_tickets(&mut self) -> usize {
+        let mut changed = 0;
+
+        for time in self
+            .projects
+            .values_mut()
+            .flat_map(|project| project.logged_times.iter_mut())
+        {
+            let tickets = ticket::find_all(&time.description);
+
+            if time.tickets != tickets {
+                time.tickets = tickets;
+                changed += 1;
+            }
+        }
+
+        changed
+    }
+
+    /// Drops the fractional seconds of every entry and timer, so that the data file only
+    /// changes where something was edited. Returns how many entries and timers changed.
+    pub fn normalize(&mut self) -> usize {
+        let mut changed = 0;
+
+        let truncate = |duration: &mut Duration| {
+            if duration.subsec_nanos() != 0 {
+                *duration = whole_seconds(*duration);
+                true
+            } else {
+                false
+            }
+        };
+
+        for project in self.projects.values_mut() {
+            let timer = [project.start_epoch.as_mut(), project.paused_epoch.as_mut()]
+                .into_iter()
+                .flatten()
+                .chain([&mut project.paused_duration])
+                .fold(false, |changed, duration| truncate(duration) | changed);
+
+            changed += usize::from(timer);
+
+            for time in project.logged_times.iter_mut() {
+                let entry = [&mut time.start_epoch, &mut time.duration]
+                    .into_iter()
+                    .chain(time.rounded.as_mut())
+                    .fold(false, |changed, duration| truncate(duration) | changed);
+
+                changed += usize::from(entry);
+            }
+        }
+
+        if let Some(modified) = self.modified_epoch.as_mut() {
+            truncate(modified);
+        }
+
+        changed
+    }
+
+    /// Parses the contents of an unencrypted data file or export.
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// Serializes the list in the format of the data file.
+    pub fn to_json(&self) -> Vec<u8> {
+        serde_json::to_vec_pretty(self).expect("Could not serialize JSON file.")
+    }
+
+    /// Looks up a project by name.
+    pub fn project(&self, name: &str) -> Result<&Project> {
+        self.projects
+            .get(name)
+            .ok_or_else(|| Error::UnknownProject(name.to_string()))
+    }
+
+    /// Looks up a project by name, for changing it.
+    pub fn project_mut(&mut self, name: &str) -> Result<&mut Project> {
+        self.projects
+            .get_mut(name)
+            .ok_or_else(|| Error::UnknownProject(name.to_string()))
+    }
+
+    /// Starts the timer of a project.
+    pub fn start(&mut self, project: &str, now: Duration) -> Result<()> {
+        let project = self.project_mut(project)?;
+
+        if project.start_epoch.is_some() {
+            return Err(Error::AlreadyStarted);
+        }
+
+        project.start_epoch = Some(now);
+        Ok(())
+    }
+
+    /// Stops the timer of a project and logs an entry for the time it tracked.
+    /// The project's settings decide when the timer stopped, how the time is rounded,
+    /// and which tags the entry gets.
+    pub fn stop(&mut self, project: &str, description: &str, now: Duration) -> Result<&LoggedTime> {
+        let description = description.trim();
+
+        if description.is_empty() {
+            return Err(Error::NoDescription);
+        }
+
+        let project = self.project_mut(project)?;
+
+        let Some(start) = project.start_epoch else {
+            return Err(Error::NotStarted);
+        };
+
+        let end = project.settings.stop_time(start, now);
+
+        let Some((start_epoch, duration)) = project.take_timer(end) else {
+            return Err(Error::NotStarted);
+        };
+
+        project.cached_total = None;
+        project.last_logged = Some(start_epoch);
+
+        project.logged_times.push(LoggedTime {
+            start_epoch,
+            duration,
+            description: description.to_string(),
+            notes: None,
+            tags: project.settings.tags.clone(),
+            billable: None,
+            rounded: project.settings.round(duration),
+            invoice: None,
+            tickets: ticket::find_all(description),
+        });
+
+        Ok(project
+            .logged_times
+            .last()
+            .expect("an entry was just logged"))
+    }
+
+    /// Adds an entry to a project, keeping its entries in order of their start times, and
+    /// returns its index. Probable duplicates of existing entries are rejected unless
+    /// `allow_duplicate` is set.
+    pub fn log(&mut self, project: &str, time: LoggedTime, allow_duplicate: bool) -> Result<usize> {
+        if time.description.trim().is_empty() {
+            return Err(Error::NoDescription);
+        }
+
+        let project = self.project_mut(project)?;
+
+        if !allow_duplicate && project.is_duplicate(&time) {
+            return Err(Error::DuplicateEntry);
+        }
+
+        project.cached_total = None;
+        project.last_logged = Some(time.start_epoch);
+
+        let logged_times = &mut project.logged_times;
+        let index = logged_times.partition_point(|other| other.start_epoch <= time.start_epoch);
+        logged_times.insert(index, time);
+        Ok(index)
+    }
+
+    /// Changes the duration of the entry of a project that was logged most recently, and
+    /// returns its old duration. The entry is billed for the new duration, even if it was
+    /// rounded before.
+    pub fn edit_last(&mut self, project: &str, duration: Duration) -> Result<Duration> {
+        let project = self.project_mut(project)?;
+        project.cached_total = None;
+
+        let Some(index) = project.last_logged_index() else {
+            return Err(Error::NoTimeLogged);
+        };
+
+        let time = &mut project.logged_times[index];
+
+        time.rounded = None;
+        Ok(std::mem::replace(&mut time.duration, duration))
+    }
+
+    /// The entries of every project that overlap the entry at `index` of a project, which is
+    /// itself left out. Returns nothing if there is no such entry.
+    pub fn overlapping(&self, project: &str, index: usize) -> Vec<(&str, &LoggedTime)> {
+        let Some(time) = self
+            .projects
+            .get(project)
+            .and_then(|project| project.logged_times.get(index))
+        else {
+            return Vec::new();
+        };
+
+        let mut overlapping: Vec<(&str, &LoggedTime)> = self
+            .projects
+            .iter()
+            .flat_map(|(name, project)| {
+                project
+                    .logged_times
+                    .iter()
+                    .map(move |other| (name.as_str(), other))
+            })
+            .filter(|(_, other)| !std::ptr::eq(*other, time) && other.overlaps(time))
+            .collect();
+
+        overlapping.sort_by_key(|(name, other)| (other.start_epoch, *name));
+        overlapping
+    }
+
+    /// The projects with running timers, and how long each has been running.
+    pub fn running(&self, now: Duration) -> Vec<(&str, Duration)> {
+        let mut running: Vec<(&str, Duration)> = self
+            .projects
+            .iter()
+            .filter_map(|(name, project)| Some((name.as_str(), project.elapsed(now)?)))
+            .collect();
+        running.sort();
+        running
+    }
+}
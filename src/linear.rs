@@ -0,0 +1,138 @@
+use std::{env, process::Command};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{log, tempfile, Error, Result};
+
+/// The endpoint of Linear's GraphQL API.
+const API_URL: &str = "https://api.linear.app/graphql";
+
+/// How many assigned issues to offer when picking one.
+const ISSUE_LIMIT: usize = 50;
+
+/// The Linear workspace of a project, read from the config file.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinearConfig {
+    /// A personal API key for the workspace. Defaults to the `LINEAR_API_KEY` environment
+    /// variable.
+    pub api_key: Option<String>,
+
+    /// The key of the team whose issues belong to the project, such as `ENG`. Without one,
+    /// issues of every team are used.
+    pub team: Option<String>,
+}
+
+/// An issue assigned to the user.
+#[derive(Debug, Clone)]
+pub struct Issue {
+    /// The identifier of the issue, such as `ENG-42`.
+    pub identifier: String,
+    pub title: String,
+}
+
+impl LinearConfig {
+    /// Whether a ticket reference, such as `ENG-42`, could be an issue of the team.
+    pub fn is_issue(&self, ticket: &str) -> bool {
+        match (&self.team, ticket.split_once('-')) {
+            (_, None) => false,
+            (Some(team), Some((key, _))) => key.eq_ignore_ascii_case(team),
+            (None, Some((key, _))) => !key.starts_with(['#', '!']),
+        }
+    }
+
+    fn request(&self, query: &str, variables: Value) -> Result<Value> {
+        let Some(api_key) = self
+            .api_key
+            .clone()
+            .or_else(|| env::var("LINEAR_API_KEY").ok())
+        else {
+            return Err(Error::Linear("No API key is set.".to_string()));
+        };
+
+        let body = json!({ "query": query, "variables": variables });
+        log::debug!("Sending {body} to Linear.");
+
+        // The key goes in a config file rather than the arguments, which anyone can see.
+        let authorization = format!("Authorization: {api_key}");
+        let config = tempfile::curl_config(&[("header", &authorization)])?;
+
+        let output = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--request", "POST"])
+            .args(["--header", "Content-Type: application/json"])
+            .arg("--config")
+            .arg(config.path())
+            .arg("--data")
+            .arg(body.to_string())
+            .arg(API_URL)
+            .output()?;
+
+        if !output.status.success() {
+            return Err(Error::Linear(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        let response: Value = serde_json::from_slice(&output.stdout)
+            .map_err(|err| Error::Linear(format!("Could not parse the response: {err}")))?;
+
+        if let Some(message) = response["errors"][0]["message"].as_str() {
+            return Err(Error::Linear(message.to_string()));
+        }
+
+        Ok(response["data"].clone())
+    }
+
+    /// The open issues assigned to the user, most recently updated first.
+    pub fn assigned_issues(&self) -> Result<Vec<Issue>> {
+        let mut filter = json!({
+            "state": { "type": { "nin": ["completed", "canceled"] } },
+        });
+
+        if let Some(team) = &self.team {
+            filter["team"] = json!({ "key": { "eqIgnoreCase": team } });
+        }
+
+        let data = self.request(
+            "query Assigned($first: Int, $filter: IssueFilter) {
+                viewer {
+                    assignedIssues(first: $first, filter: $filter, orderBy: updatedAt) {
+                        nodes { identifier title }
+                    }
+                }
+            }",
+            json!({ "first": ISSUE_LIMIT, "filter": filter }),
+        )?;
+
+        let nodes = data["viewer"]["assignedIssues"]["nodes"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(nodes
+            .iter()
+            .filter_map(|node| {
+                Some(Issue {
+                    identifier: node["identifier"].as_str()?.to_string(),
+                    title: node["title"].as_str()?.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// Adds a comment to an issue, given its identifier.
+    pub fn comment(&self, identifier: &str, body: &str) -> Result<()> {
+        let data = self.request(
+            "mutation Comment($issueId: String!, $body: String!) {
+                commentCreate(input: { issueId: $issueId, body: $body }) { success }
+            }",
+            json!({ "issueId": identifier, "body": body }),
+        )?;
+
+        match data["commentCreate"]["success"].as_bool() {
+            Some(true) => Ok(()),
+            _ => Err(Error::Linear(format!("Could not comment on {identifier}."))),
+        }
+    }
+}
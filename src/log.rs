@@ -0,0 +1,46 @@
+use std::{
+    fmt,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use colored::Colorize;
+
+/// Diagnostics about files, external commands, and requests.
+pub const DEBUG: u8 = 1;
+
+/// Diagnostics about the time math, on top of the debug ones.
+pub const TRACE: u8 = 2;
+
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+/// Sets how many diagnostics are printed, from the number of times `--debug` was passed.
+pub fn set_level(level: u8) {
+    LEVEL.store(level, Ordering::Relaxed);
+}
+
+/// Prints a diagnostic to stderr if the level is enabled.
+pub fn print(level: u8, args: fmt::Arguments) {
+    if LEVEL.load(Ordering::Relaxed) < level {
+        return;
+    }
+
+    let prefix = if level >= TRACE { "trace:" } else { "debug:" };
+    eprintln!("{}", format!("{prefix} {args}").dimmed());
+}
+
+/// Logs a diagnostic about files, external commands, and requests.
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        $crate::log::print($crate::log::DEBUG, format_args!($($arg)*))
+    };
+}
+
+/// Logs a diagnostic about the time math.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        $crate::log::print($crate::log::TRACE, format_args!($($arg)*))
+    };
+}
+
+pub(crate) use debug;
+pub(crate) use trace;
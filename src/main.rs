@@ -1,15 +1,94 @@
+mod anonymize;
+mod archive;
+mod autotag;
+mod bookkeeping;
+mod breaks;
+mod burndown;
+mod columns;
+mod compress;
+mod config;
+mod crypto;
+mod diff;
+mod doctor;
+mod duration;
+mod editor;
+mod git;
+mod github;
+mod gitlab;
+mod goals;
+mod histogram;
+mod history;
+mod linear;
+mod log;
+mod merge;
+mod money;
+mod msgpack;
+mod pattern;
+mod plugin;
+mod prune;
+mod redmine;
+mod remote;
+mod render;
+mod schedule;
+mod server;
+mod sha256;
+mod storage;
+mod streak;
+mod suggest;
+mod table;
+mod tempfile;
+mod template;
+mod tracker;
+mod update;
+mod when;
+mod zip;
+
 use std::{
+    borrow::Cow,
+    cmp::Reverse,
     collections::HashMap,
-    fs,
+    env, fmt, fs,
+    io::{self, BufWriter, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use clap::Parser;
-use colored::Colorize;
-use go_parse_duration::parse_duration;
+use autotag::AutoTagger;
+use bookkeeping::{Layout, Row};
+use clap::{ArgGroup, CommandFactory, Parser};
+use colored::{Color, ColoredString, Colorize};
+use columns::{CsvColumn, ListColumn, TimeColumn};
+use config::{Config, WeekStart};
+use doctor::Repair;
+use hat_changer::{
+    date,
+    invoice::{Charge, Invoice, InvoiceStatus},
+    round_to_minutes, ticket,
+    trash::{self, TrashItem, Trashed},
+    whole_seconds, LoggedTime, Project, ProjectList, ProjectMetadata, ProjectSettings,
+};
 use pretty_duration::pretty_duration;
-use serde::{Deserialize, Serialize};
+use render::Template;
+use schedule::Schedule;
+use storage::{Format, Storage};
+use table::{Align, Cell, Table};
 use thiserror::Error;
+use tracker::SyncState;
+
+/// Whether `--quiet` was passed, which hides success messages.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Prints a success message, unless `--quiet` was passed.
+macro_rules! success {
+    ($($arg:tt)*) => {
+        if !QUIET.load(Ordering::Relaxed) {
+            println!("{}", format!($($arg)*).bright_green());
+        }
+    };
+}
 
 /// An extremely lightweight time tracking tool for work.
 #[derive(Parser, Debug)]
@@ -20,35 +99,328 @@ struct Args {
 
     /// The name of the project.
     project_name: Option<String>,
+
+    /// Run the command on this project without making it the active one.
+    #[arg(long, global = true, value_name = "NAME")]
+    project: Option<String>,
+
+    /// Only print errors and requested output, without success messages.
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Print diagnostics about files, commands, and requests. Pass twice to include time math.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    debug: u8,
 }
 
 #[derive(Parser, Debug)]
 enum Commands {
     /// List all projects and their total time.
-    List,
+    List {
+        /// Include entries from the archive.
+        #[arg(short = 'a', long)]
+        include_archive: bool,
+
+        /// Print uncolored tab-separated columns: running timer start epoch seconds (empty if
+        /// stopped), total duration seconds, and project.
+        #[arg(long, visible_alias = "tsv")]
+        plain: bool,
+
+        /// Show a table of each project's client, last activity, entry count, time this week
+        /// and in total, and whether it is active or running.
+        #[arg(long, conflicts_with = "plain")]
+        detailed: bool,
+
+        /// Show names and clients in full instead of truncating them to the width of the
+        /// terminal.
+        #[arg(long, conflicts_with = "plain")]
+        no_truncate: bool,
+
+        /// The columns to show, separated by commas, such as `project,client,week`.
+        #[arg(long, value_enum, value_delimiter = ',', conflicts_with_all = ["plain", "detailed"])]
+        columns: Vec<ListColumn>,
+    },
 
     /// Start the timer for the active project.
-    On,
+    On {
+        /// Keep the timers of other projects running.
+        #[arg(long)]
+        parallel: bool,
+    },
 
-    /// Finish the active timer and log an entry.
-    Off {
-        /// The description of the logged time.
+    /// Show all running timers.
+    Status,
+
+    /// Print the name of the active project, exiting with code 1 if there is none.
+    Current {
+        /// Only succeed if the active project's timer is running.
+        #[arg(long)]
+        running: bool,
+    },
+
+    /// Print the total time of a project, including its running timer, for use in scripts.
+    Total {
+        /// The project to total, instead of the active one.
+        project: Option<String>,
+
+        /// Only include time from today.
+        #[arg(long, conflicts_with_all = ["week", "from", "to"])]
+        today: bool,
+
+        /// Only include time from this week, starting on the day set by `week_start` (Monday by
+        /// default).
+        #[arg(long, conflicts_with_all = ["from", "to"])]
+        week: bool,
+
+        /// Only include entries starting on or after this date, such as `2024-06-01` or `last monday`.
+        #[arg(long, value_parser = parse_date_arg)]
+        from: Option<Duration>,
+
+        /// Only include entries starting on or before this date, such as `2024-06-01` or `last monday`.
+        #[arg(long, value_parser = parse_date_arg)]
+        to: Option<Duration>,
+
+        /// Print whole seconds instead of a readable duration.
+        #[arg(long)]
+        seconds: bool,
+
+        /// Include entries from the archive.
+        #[arg(short = 'a', long)]
+        include_archive: bool,
+    },
+
+    /// Show the time logged for each project in a week, month, or range of dates.
+    /// Shows the current week if no period is given.
+    Report {
+        /// Only include this project.
+        #[arg(short, long)]
+        project: Option<String>,
+
+        #[command(flatten)]
+        period: PeriodArgs,
+
+        /// Only include entries that are not on an invoice yet.
+        #[arg(long)]
+        uninvoiced: bool,
+
+        #[command(flatten)]
+        tags: TagFilter,
+
+        /// Write the report with this Handlebars-style template instead of the built-in layout.
+        #[arg(long)]
+        template: Option<PathBuf>,
+
+        /// Compare the time of each project in two periods, such as `"this month" "last month"`.
+        /// Periods can be `this week`, `last month`, `2 years ago`, `2024-W23`, `2024-06`,
+        /// `2024`, or a day.
+        #[arg(
+            long,
+            num_args = 2,
+            value_names = ["PERIOD", "OTHER"],
+            value_parser = parse_period_arg,
+            conflicts_with_all = ["week", "month", "from", "to", "template"],
+        )]
+        compare: Option<Vec<NamedPeriod>>,
+
+        /// Include entries from the archive.
+        #[arg(short = 'a', long)]
+        include_archive: bool,
+    },
+
+    /// Generate an invoice for the billable time of a client or project over a period, and
+    /// record it under the next invoice number. Entries already on an invoice are left out, and
+    /// the rest are marked as invoiced. Covers the current week if no period is given.
+    #[command(group(ArgGroup::new("billed").required(true)))]
+    Invoice {
+        /// Invoice every project of this client.
+        #[arg(short, long, group = "billed")]
+        client: Option<String>,
+
+        /// Invoice this project alone.
+        #[arg(short, long, group = "billed")]
+        project: Option<String>,
+
+        #[command(flatten)]
+        period: PeriodArgs,
+
+        /// Show the invoice without recording it or using up its number.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Write the invoice with this Handlebars-style template instead of the built-in layout.
+        #[arg(long)]
+        template: Option<PathBuf>,
+    },
+
+    /// Show the invoices that were generated.
+    Invoices {
+        #[command(subcommand)]
+        command: InvoicesCommand,
+    },
+
+    /// Stop the running timer and start one for another project at the same moment.
+    Switch {
+        /// The project to switch to.
+        project_name: String,
+
+        /// The description for the stopped timer. You are asked for one if this is omitted.
         #[arg(trailing_var_arg = true)]
         description: Vec<String>,
     },
 
-    /// Edit the last logged time.
+    /// Pause the running timer without logging an entry.
+    Pause {
+        /// The project to pause, instead of the active one.
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Resume a paused timer.
+    Resume {
+        /// The project to resume, instead of the active one.
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Finish the active timer and log an entry.
+    Off(OffArgs),
+
+    /// Log an entry that was not timed, such as one remembered from your calendar.
+    Log(LogArgs),
+
+    /// Edit the last logged time, or recent entries in your editor.
     Edit {
-        /// The new duration of the last logged time.
-        #[arg(trailing_var_arg = true)]
+        /// Show what would change without saving it.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// List recent entries in your editor, to change their start times, durations,
+        /// and descriptions at once.
+        #[arg(short, long, conflicts_with_all = ["duration", "start", "id"])]
+        interactive: bool,
+
+        /// The number of recent entries to list with `--interactive`.
+        #[arg(long, requires = "interactive")]
+        limit: Option<usize>,
+
+        /// Edit the entry with this number, as shown by `time --verbose`, instead of the last one.
+        #[arg(long)]
+        id: Option<usize>,
+
+        /// Move the start of the entry to this time (HH:MM, or YYYY-MM-DD HH:MM, in UTC).
+        /// A time alone is taken to be on the day the entry started.
+        #[arg(long)]
+        start: Option<String>,
+
+        /// Keep the entry ending at the same time when moving its start, changing its duration.
+        #[arg(long, requires = "start", conflicts_with = "duration")]
+        keep_end: bool,
+
+        /// The new duration of the entry, or a change to it such as `+30m` or `-15m`.
+        #[arg(
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            required_unless_present_any = ["interactive", "start"]
+        )]
         duration: Vec<String>,
     },
 
+    /// Extend the last logged time up to now, such as after stopping the timer too early.
+    Amend {
+        /// Show what would change without saving it.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Add text to the description of the last logged time, such as a detail remembered just
+    /// after stopping the timer.
+    Append {
+        /// Show what would change without saving it.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// The text to add.
+        #[arg(trailing_var_arg = true, required = true)]
+        text: Vec<String>,
+    },
+
     /// Undo the last logged time, or cancel the current entry.
+    /// Undone entries are moved to the trash.
     Undo,
 
-    /// List all logged times for the active project.
-    Time,
+    /// Remove old entries, or collapse them into monthly summaries.
+    Prune {
+        /// Prune entries starting before this date, such as `2024-06-01` or `last monday`.
+        #[arg(long, value_parser = parse_date_arg)]
+        before: Duration,
+
+        /// Only prune entries of this project.
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Replace the entries with one summary entry per month, preserving totals.
+        #[arg(short, long)]
+        summarize: bool,
+
+        /// Prune without asking for confirmation.
+        #[arg(short, long)]
+        force: bool,
+
+        /// Show what would change without saving it.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Move old entries into one archive file per year.
+    ArchiveEntries {
+        /// Archive entries starting before this year (YYYY) or date (YYYY-MM-DD).
+        #[arg(long, value_parser = parse_year_or_date_arg)]
+        before: Duration,
+    },
+
+    /// Change every entry that matches the filters at once.
+    BulkEdit(BulkEditArgs),
+
+    /// List, rename, or merge the tags of entries.
+    Tag {
+        #[command(subcommand)]
+        command: TagCommand,
+    },
+
+    /// Replace text in the descriptions and notes of every entry, including archived and
+    /// deleted ones, such as a client's name that must no longer appear. Backups of the
+    /// data file and changed archive files are written first.
+    Redact(RedactArgs),
+
+    /// Show or change the settings of a project.
+    Project {
+        #[command(subcommand)]
+        command: ProjectCommand,
+    },
+
+    /// Show deleted projects and entries.
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommand,
+    },
+
+    /// Restore a project or entry from the trash.
+    Restore {
+        /// The number of the item, as shown by `trash list`.
+        id: usize,
+    },
+
+    /// List all logged times for the active project, or another one.
+    Time(TimeArgs),
+
+    /// Write longer notes for an entry in your editor.
+    Note {
+        /// The number of the entry, as shown by `time --verbose`. Defaults to the last entry.
+        id: Option<usize>,
+    },
+
+    /// Print the most used descriptions for the active project, for shell completion.
+    Suggestions,
 
     /// Add a new project.
     New {
@@ -56,346 +428,6642 @@ enum Commands {
         project_name: String,
     },
 
-    /// Delete a project.
+    /// Move a project to the trash.
     Delete {
         /// The name of the project.
         project_name: String,
+
+        /// Delete without asking for confirmation.
+        #[arg(short, long)]
+        force: bool,
+
+        /// Show what would change without saving it.
+        #[arg(long)]
+        dry_run: bool,
     },
-}
 
-#[derive(Default, Serialize, Deserialize)]
-struct ProjectList {
-    projects: HashMap<String, Project>,
-    active_project: Option<String>,
-}
+    /// Move the entries of a project within a date range to the trash.
+    #[command(group(ArgGroup::new("range").required(true).multiple(true)))]
+    DeleteEntries {
+        /// The project to delete entries from, instead of the active one.
+        #[arg(short, long)]
+        project: Option<String>,
 
-#[derive(Default, Serialize, Deserialize)]
-struct Project {
-    start_epoch: Option<Duration>,
-    logged_times: Vec<LoggedTime>,
-}
+        /// Delete entries starting on or after this date, such as `2024-06-01` or `last monday`.
+        #[arg(long, value_parser = parse_date_arg, group = "range")]
+        from: Option<Duration>,
 
-#[derive(Serialize, Deserialize)]
-struct LoggedTime {
-    start_epoch: Duration,
-    duration: Duration,
-    description: String,
-}
+        /// Delete entries starting on or before this date, such as `2024-06-01` or `last monday`.
+        #[arg(long, value_parser = parse_date_arg, group = "range")]
+        to: Option<Duration>,
 
-#[derive(Debug, Error)]
-enum Error {
-    #[error("Could not parse duration with invalid format.")]
-    ParseDuration(go_parse_duration::Error),
+        /// Delete without asking for confirmation.
+        #[arg(short, long)]
+        force: bool,
 
-    #[error("An error occurred while trying to get the system's current time.")]
-    SystemTime(#[from] std::time::SystemTimeError),
+        /// Show what would change without saving it.
+        #[arg(long)]
+        dry_run: bool,
+    },
 
-    #[error("There is no project named {}", .0.bright_cyan())]
-    UnknownProject(String),
+    /// Check the data file for inconsistencies.
+    Doctor,
 
-    #[error("You do not currently have a project selected.")]
-    NoActiveProject,
+    /// Repair issues found by the doctor command.
+    #[command(group(ArgGroup::new("repairs").required(true).multiple(true)))]
+    Fix {
+        /// Remove entries that exactly duplicate another entry.
+        #[arg(long, group = "repairs")]
+        dedupe: bool,
 
-    #[error("The active project does not exist anymore.")]
-    UnknownActiveProject,
+        /// End entries and timers that extend into the future at the current time.
+        #[arg(long, group = "repairs")]
+        clamp_future: bool,
 
-    #[error("You are already tracking your time.")]
-    AlreadyStarted,
+        /// Remove entries with no duration.
+        #[arg(long, group = "repairs")]
+        drop_zero: bool,
 
-    #[error("You have not started tracking your time.")]
-    NotStarted,
+        /// Apply the repairs without asking for confirmation.
+        #[arg(short, long)]
+        yes: bool,
+    },
 
-    #[error("You have not logged any time for this project.")]
-    NoTimeLogged,
+    /// Export data to standard output.
+    Export {
+        #[command(subcommand)]
+        scope: ExportScope,
+    },
 
-    #[error("Cannot log entry with no description.")]
-    NoDescription,
+    /// Import previously exported data.
+    Import {
+        #[command(subcommand)]
+        scope: ImportScope,
+    },
 
-    #[error("project {} already exists", .0.bright_cyan())]
-    ProjectExists(String),
-}
+    /// Pull and push the git repository in the data directory, or sync with a server.
+    Sync {
+        #[command(subcommand)]
+        target: Option<SyncTarget>,
 
-type Result<T> = std::result::Result<T, Error>;
+        /// Show what would be synchronized without changing anything.
+        #[arg(long, global = true)]
+        dry_run: bool,
+    },
 
-fn main() {
-    let args = Args::parse();
+    /// Merge a data file from another machine into this one.
+    MergeFile {
+        /// The path of the other data file.
+        file: PathBuf,
 
-    let home = homedir::get_my_home()
-        .expect("Could not read home directory.")
-        .expect("Home directory not found.");
+        /// Keep this machine's data on conflicts instead of asking.
+        #[arg(long, conflicts_with = "theirs")]
+        ours: bool,
 
-    let path = home.join(".timelogger.json");
+        /// Keep the other file's data on conflicts instead of asking.
+        #[arg(long)]
+        theirs: bool,
+    },
 
-    let mut list: ProjectList = fs::read_to_string(path.as_path())
-        .map(|text| serde_json::from_str(&text).unwrap())
-        .unwrap_or_default();
+    /// Serve data files to other users over HTTP.
+    Serve {
+        /// The directory containing `users.json` and each user's data file.
+        dir: PathBuf,
 
-    let result = match args.command {
-        Some(Commands::List) => handle_list(&list),
-        Some(Commands::On) => handle_on(&mut list),
-        Some(Commands::Off { description }) => handle_off(&mut list, &description.join(" ")),
-        Some(Commands::Edit { duration }) => handle_edit(&mut list, &duration.join(" ")),
-        Some(Commands::Undo) => handle_undo(&mut list),
-        Some(Commands::Time) => handle_time(&list),
-        Some(Commands::New { project_name }) => handle_new(&mut list, &project_name),
-        Some(Commands::Delete { project_name }) => handle_delete(&mut list, &project_name),
-        None => {
-            if let Some(project_name) = args.project_name {
-                handle_hat(&mut list, &project_name)
-            } else {
-                handle_time(&list)
-            }
-        }
-    };
+        /// The address to listen on.
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        addr: String,
+    },
 
-    if let Err(err) = result {
-        eprintln!("{}", err.to_string().bright_yellow());
-    }
+    /// Run the jobs scheduled in the config file as their times come, such as a weekly report,
+    /// until stopped.
+    Daemon,
 
-    fs::write(
-        path.as_path(),
-        serde_json::to_string_pretty(&list).expect("Could not serialize JSON file."),
-    )
-    .expect("Could not write JSON file.");
-}
+    /// Summarize time per person and project across a team.
+    TeamReport {
+        /// A directory of exported data files named after each person.
+        /// The shared server is used if this is omitted.
+        dir: Option<PathBuf>,
 
-fn handle_list(list: &ProjectList) -> Result<()> {
-    if list.projects.is_empty() {
-        println!("{}", "No projects found.".bright_red());
-        return Ok(());
-    } else {
-        println!("{}", "Project list:".bright_yellow());
-    }
-    for (name, project) in list.projects.iter() {
-        let name = if list.active_project == Some(name.clone()) {
-            name.bright_green()
-        } else {
-            name.bright_cyan()
-        };
+        /// Only include entries starting on or after this date, such as `2024-06-01` or `last monday`.
+        #[arg(long, value_parser = parse_date_arg)]
+        from: Option<Duration>,
 
-        let time = project
-            .logged_times
-            .iter()
-            .fold(Duration::default(), |acc, time| acc + time.duration);
+        /// Only include entries starting on or before this date, such as `2024-06-01` or `last monday`.
+        #[arg(long, value_parser = parse_date_arg)]
+        to: Option<Duration>,
+    },
 
-        let time = pretty_duration(&time, None).bright_red();
+    /// Encrypt the data file with the configured age identity.
+    Encrypt,
 
-        println!("  {name} - {time}");
-    }
+    /// Store the data file unencrypted.
+    Decrypt,
 
-    Ok(())
-}
+    /// Rewrite the data file, project files, and archives in another format. JSON is easy to
+    /// edit by hand, while MessagePack is much smaller and faster for large histories.
+    ConvertFormat {
+        #[arg(value_enum)]
+        format: Format,
+    },
 
-fn handle_on(list: &mut ProjectList) -> Result<()> {
-    let Some(active) = list.active_project.clone() else {
-        return Err(Error::NoActiveProject);
-    };
+    /// Rebuild the index of entry dates that reports use, such as after editing the data file
+    /// by hand.
+    Reindex,
 
-    let Some(project) = list.projects.get_mut(&active) else {
-        return Err(Error::UnknownActiveProject);
-    };
+    /// Truncate every entry and timer to whole seconds and rewrite the data file in canonical
+    /// order, so that its diffs stay small under git.
+    Normalize,
 
-    if project.start_epoch.is_some() {
-        return Err(Error::AlreadyStarted);
-    }
+    /// Download the release binary for this platform from GitHub and replace this one with it.
+    SelfUpdate {
+        /// Only check whether a new version exists, without installing it.
+        #[arg(long)]
+        check: bool,
+    },
 
-    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
-    project.start_epoch = Some(now);
+    /// Chart the time logged for a project against its estimate, and when it will be done at
+    /// the current pace.
+    Burndown {
+        /// The name of the project, instead of the active one.
+        name: Option<String>,
+    },
 
-    let name = active.bright_cyan();
+    /// Chart the hours logged on each of the last days, with a segment for each project.
+    Chart {
+        /// The number of days to chart, ending today.
+        #[arg(long, default_value_t = 14, value_parser = clap::value_parser!(u16).range(1..))]
+        days: u16,
+    },
 
-    println!(
-        "{}",
-        format!("Now tracking time for project {}.", name).bright_green()
-    );
+    /// Open the URL of a project, such as its repository or ticket board, in the browser.
+    Open {
+        /// The name of the project, instead of the active one.
+        name: Option<String>,
+    },
 
-    Ok(())
+    /// Show how many projects and entries there are, and how much time was logged.
+    Stats {
+        /// Show the size of the data file and archives, how many entries each project has,
+        /// and suggestions for keeping them small.
+        #[arg(long, conflicts_with = "distribution")]
+        storage: bool,
+
+        /// Show how the time logged is spread over the days of the week and the hours of the
+        /// day, as bar charts.
+        #[arg(long)]
+        distribution: bool,
+
+        /// Include entries from the archive.
+        #[arg(short = 'a', long, conflicts_with = "storage")]
+        include_archive: bool,
+    },
 }
 
-fn handle_off(list: &mut ProjectList, description: &str) -> Result<()> {
-    let Some(active) = list.active_project.clone() else {
-        return Err(Error::NoActiveProject);
-    };
+impl Commands {
+    /// Whether the command reads or changes logged entries, rather than only projects and
+    /// their timers. Commands that don't can skip loading entries kept in project files.
+    fn needs_entries(&self, config: &Config) -> bool {
+        if let Self::List {
+            include_archive,
+            detailed,
+            columns,
+            ..
+        } = self
+        {
+            let columns = match (columns.is_empty(), &config.list_columns) {
+                (true, Some(configured)) => configured,
+                _ => columns,
+            };
 
-    let Some(project) = list.projects.get_mut(&active) else {
-        return Err(Error::UnknownActiveProject);
-    };
+            return *include_archive
+                || *detailed
+                || columns.iter().any(|column| column.needs_entries());
+        }
 
-    if description.trim().is_empty() {
-        return Err(Error::NoDescription);
+        !matches!(
+            self,
+            Self::On { .. }
+                | Self::Status
+                | Self::Current { .. }
+                | Self::Pause { .. }
+                | Self::Resume { .. }
+                | Self::Open { .. }
+        )
     }
 
-    let Some(start_epoch) = project.start_epoch.take() else {
-        return Err(Error::NotStarted);
-    };
-
-    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
-    let duration = now - start_epoch;
+    /// Whether the command should only show what it would change.
+    fn dry_run(&self) -> bool {
+        match self {
+            Self::Off(args) => args.dry_run,
+            Self::Edit { dry_run, .. }
+            | Self::Amend { dry_run }
+            | Self::Append { dry_run, .. }
+            | Self::Invoice { dry_run, .. }
+            | Self::Prune { dry_run, .. }
+            | Self::BulkEdit(BulkEditArgs { dry_run, .. })
+            | Self::Redact(RedactArgs { dry_run, .. })
+            | Self::Delete { dry_run, .. }
+            | Self::DeleteEntries { dry_run, .. }
+            | Self::Sync { dry_run, .. }
+            | Self::Import {
+                scope: ImportScope::All(ImportAllArgs { dry_run, .. }),
+            } => *dry_run,
+            _ => false,
+        }
+    }
+}
 
-    project.logged_times.push(LoggedTime {
-        start_epoch,
-        duration,
-        description: description.trim().to_string(),
-    });
+#[derive(clap::Args, Debug)]
+struct RedactArgs {
+    /// The text to replace, ignoring case.
+    #[arg(long = "match", value_parser = clap::builder::NonEmptyStringValueParser::new())]
+    pattern: String,
 
-    let name = active.bright_cyan();
-    let time = pretty_duration(&duration, None).bright_red();
+    /// Only redact entries of this project, instead of every project.
+    #[arg(short, long)]
+    project: Option<String>,
 
-    println!(
-        "{}",
-        format!("Logged {} for project {}.", time, name).bright_green()
-    );
+    /// The text to put in place of each match.
+    #[arg(long = "with", default_value = "[redacted]")]
+    replacement: String,
 
-    Ok(())
+    /// Show what would change without saving it.
+    #[arg(long)]
+    dry_run: bool,
 }
 
-fn handle_edit(list: &mut ProjectList, duration: &str) -> Result<()> {
-    let Some(active) = list.active_project.clone() else {
-        return Err(Error::NoActiveProject);
-    };
+#[derive(clap::Args, Debug)]
+#[command(group(ArgGroup::new("changes").required(true).multiple(true)))]
+struct BulkEditArgs {
+    /// Only change entries of this project, instead of every project.
+    #[arg(short, long)]
+    project: Option<String>,
 
-    let Some(project) = list.projects.get_mut(&active) else {
-        return Err(Error::UnknownActiveProject);
-    };
+    /// Only change entries starting on or after this date, such as `2024-06-01` or `last monday`.
+    #[arg(long, value_parser = parse_date_arg)]
+    from: Option<Duration>,
 
-    let Some(time) = project.logged_times.last_mut() else {
-        return Err(Error::NoTimeLogged);
-    };
+    /// Only change entries starting on or before this date, such as `2024-06-01` or `last monday`.
+    #[arg(long, value_parser = parse_date_arg)]
+    to: Option<Duration>,
 
-    let duration = Duration::from_nanos(
-        parse_duration(&duration.replace(' ', "")).map_err(Error::ParseDuration)? as u64,
-    );
+    /// Only change entries whose description contains this text, ignoring case.
+    #[arg(long = "match")]
+    pattern: Option<String>,
 
-    let old_duration = pretty_duration(&time.duration, None).bright_red();
-    time.duration = duration;
+    /// Add a tag to the entries.
+    #[arg(long, group = "changes")]
+    set_tag: Vec<String>,
 
-    let duration = pretty_duration(&duration, None).bright_red();
+    /// Remove a tag from the entries.
+    #[arg(long, group = "changes")]
+    remove_tag: Vec<String>,
 
-    println!(
-        "{}",
-        format!("Modified the last entry from {old_duration} to {duration}").bright_green()
-    );
+    /// Whether the entries are billable, regardless of their project's setting.
+    #[arg(long, group = "changes")]
+    set_billable: Option<bool>,
 
-    Ok(())
+    /// Replace the description of the entries.
+    #[arg(long, group = "changes")]
+    set_description: Option<String>,
+
+    /// Show what would change without saving it.
+    #[arg(long)]
+    dry_run: bool,
 }
 
-fn handle_undo(list: &mut ProjectList) -> Result<()> {
-    let Some(active) = list.active_project.clone() else {
-        return Err(Error::NoActiveProject);
-    };
+#[derive(clap::Args, Debug)]
+#[command(group(ArgGroup::new("length").required(true)))]
+struct LogArgs {
+    /// Log the entry for this project instead of the active one.
+    #[arg(long)]
+    project: Option<String>,
 
-    let Some(project) = list.projects.get_mut(&active) else {
-        return Err(Error::UnknownActiveProject);
-    };
+    /// When the entry started, such as `2024-06-03 09:00`, `yesterday 15:00`, or `2h ago`, in
+    /// UTC. A time alone is taken to be today.
+    #[arg(long, visible_alias = "at")]
+    start: String,
 
-    if let Some(start) = project.start_epoch {
-        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
-        let duration = now - start;
-        let time = pretty_duration(&duration, None).bright_red();
+    /// When the entry ended (HH:MM, or YYYY-MM-DD HH:MM). A time alone is taken to be on
+    /// the day the entry started.
+    #[arg(long, group = "length")]
+    end: Option<String>,
 
-        project.start_epoch = None;
+    /// How long the entry took, instead of when it ended.
+    #[arg(long, group = "length")]
+    duration: Option<String>,
 
-        println!(
-            "{}",
-            format!("Cancelled {time} of unlogged time.").bright_green()
-        );
+    /// Log the entry even if one with the same start and description is already logged.
+    #[arg(long)]
+    allow_duplicate: bool,
 
-        return Ok(());
-    }
+    /// Reuse the description, tags, and billable setting of the previous entry.
+    #[arg(short, long)]
+    same: bool,
 
-    let Some(time) = project.logged_times.pop() else {
-        return Err(Error::NoTimeLogged);
-    };
+    /// The description of the entry. Your editor opens if this is omitted.
+    #[arg(trailing_var_arg = true)]
+    description: Vec<String>,
+}
 
-    let description = time.description.bright_blue();
-    let time = pretty_duration(&time.duration, None).bright_red();
+#[derive(clap::Args, Debug)]
+struct OffArgs {
+    /// Stop the timer of this project instead of the active one.
+    #[arg(long)]
+    project: Option<String>,
 
-    println!(
-        "{}",
-        format!("Removed the last entry with duration {time}: {description}").bright_green()
-    );
+    /// Start the description from a template in the config file.
+    #[arg(short, long)]
+    template: Option<String>,
 
-    Ok(())
-}
+    /// Pick the description from the ones used most in this project.
+    #[arg(short, long, conflicts_with = "template")]
+    pick: bool,
 
-fn handle_time(list: &ProjectList) -> Result<()> {
-    let Some(active) = list.active_project.clone() else {
-        return Err(Error::NoActiveProject);
-    };
+    /// Reuse the description, tags, and billable setting of the previous entry.
+    #[arg(short, long, conflicts_with_all = ["template", "pick"])]
+    same: bool,
 
-    let Some(project) = list.projects.get(&active) else {
-        return Err(Error::UnknownActiveProject);
-    };
+    /// Pick the description from the open Linear issues assigned to you.
+    #[arg(long, conflicts_with_all = ["template", "pick", "same"])]
+    pick_issue: bool,
 
-    let name = active.bright_cyan();
+    /// Read the description from stdin. Its first line is the description, and any lines
+    /// after it become the entry's notes.
+    #[arg(long, conflicts_with_all = ["template", "pick", "same", "pick_issue", "description"])]
+    stdin: bool,
 
-    if project.logged_times.is_empty() {
-        println!(
-            "{}",
-            format!("No logged times for project {}.", name).bright_red()
-        );
-        return Ok(());
-    }
+    /// Round the entry to the nearest multiple of this many minutes for billing, instead of
+    /// the project's or the config file's setting. Pass 0 to not round it.
+    #[arg(long, value_name = "MINUTES")]
+    round: Option<u64>,
 
-    let total_duration = project
-        .logged_times
-        .iter()
-        .fold(Duration::default(), |acc, time| acc + time.duration);
-    let total = pretty_duration(&total_duration, None).bright_red();
+    /// Show what would change without saving it.
+    #[arg(long)]
+    dry_run: bool,
 
-    println!(
-        "{}",
-        format!("Logged times for {name}, totaling {total}:").bright_yellow()
-    );
+    /// The description of the logged time. You are asked for one if this is omitted.
+    #[arg(trailing_var_arg = true)]
+    description: Vec<String>,
+}
 
-    for logged_time in project.logged_times.iter() {
-        let time = pretty_duration(&logged_time.duration, None).bright_red();
-        let description = logged_time.description.bright_blue();
+#[derive(clap::Args, Debug, Default)]
+struct TimeArgs {
+    /// The project to list, instead of the active one.
+    #[arg(value_name = "PROJECT")]
+    project_name: Option<String>,
 
-        println!("  {time} - {description}");
-    }
+    /// Show entry numbers and notes.
+    #[arg(short, long)]
+    verbose: bool,
 
-    Ok(())
-}
+    /// Include entries from the archive.
+    #[arg(short = 'a', long)]
+    include_archive: bool,
+
+    /// The maximum number of entries to show, 20 by default.
+    #[arg(short = 'n', long, conflicts_with = "all")]
+    limit: Option<usize>,
+
+    /// Show every entry.
+    #[arg(long)]
+    all: bool,
+
+    /// The number of entries to skip.
+    #[arg(long, default_value_t = 0)]
+    offset: usize,
+
+    /// Show the oldest entries first.
+    #[arg(short, long)]
+    reverse: bool,
+
+    /// Collapse entries with the same description into one line.
+    #[arg(short, long, conflicts_with = "verbose")]
+    group: bool,
+
+    /// Print every entry, oldest first, as uncolored tab-separated columns: start epoch
+    /// seconds, duration seconds, project, and description.
+    #[arg(long, visible_alias = "tsv", conflicts_with_all = ["verbose", "group"])]
+    plain: bool,
+
+    /// Show descriptions in full instead of truncating them to the width of the terminal.
+    #[arg(long)]
+    no_truncate: bool,
+
+    /// The columns to show, separated by commas, such as `start,end,duration,description`.
+    #[arg(long, value_enum, value_delimiter = ',', conflicts_with_all = ["plain", "group"])]
+    columns: Vec<TimeColumn>,
+
+    #[command(flatten)]
+    tags: TagFilter,
+
+    /// Only include entries that mention this ticket, such as `PROJ-42` or `#123`, to total
+    /// the time spent on it. Repeat to include entries that mention any of them.
+    #[arg(long = "ticket", value_name = "TICKET")]
+    tickets: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+enum ProjectCommand {
+    /// Show the settings of a project.
+    Show {
+        /// The name of the project, instead of the active one.
+        name: Option<String>,
+    },
+
+    /// Change the settings of a project.
+    Set(Box<ProjectSetArgs>),
+}
+
+#[derive(clap::Args, Debug)]
+struct ProjectSetArgs {
+    /// The name of the project.
+    name: String,
+
+    /// A tag to add to every entry, replacing the current ones. Pass an empty tag to clear them.
+    #[arg(long = "tag")]
+    tags: Option<Vec<String>>,
+
+    /// Whether time spent on the project can be billed.
+    #[arg(long)]
+    billable: Option<bool>,
+
+    /// The hourly rate. Pass 0 to clear it.
+    #[arg(long)]
+    rate: Option<f64>,
+
+    /// The currency of the rate, such as `EUR`. Pass `none` to clear it.
+    #[arg(long)]
+    currency: Option<String>,
+
+    /// Round entries to the nearest multiple of this many minutes for billing, keeping the
+    /// tracked time as well. Pass 0 to turn it off.
+    #[arg(long)]
+    round: Option<u64>,
+
+    /// Stop running timers at this time of day (HH:MM, in UTC). Pass `none` to turn it off.
+    #[arg(long)]
+    auto_stop: Option<String>,
+
+    /// How long the project is expected to take in total, such as `40h`. Pass `none` to clear it.
+    #[arg(long)]
+    estimate: Option<String>,
+
+    /// What the project is about. Pass an empty value to clear this or any of the fields below.
+    #[arg(long = "desc")]
+    description: Option<String>,
+
+    /// The client the project is for.
+    #[arg(long)]
+    client: Option<String>,
+
+    /// The color the project's name is shown in, such as `magenta`, `bright blue`, or `#ff8800`.
+    #[arg(long)]
+    color: Option<String>,
+
+    /// A link to the project, such as its repository or issue tracker.
+    #[arg(long)]
+    url: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct PeriodArgs {
+    /// The ISO week, such as `2024-W23`.
+    #[arg(long, value_parser = parse_week_arg, conflicts_with_all = ["month", "from", "to"])]
+    week: Option<Duration>,
+
+    /// The month, such as `2024-06`.
+    #[arg(long, value_parser = parse_month_arg, conflicts_with_all = ["from", "to"])]
+    month: Option<Duration>,
+
+    /// Only include entries starting on or after this date, such as `2024-06-01` or `last monday`.
+    #[arg(long, value_parser = parse_date_arg)]
+    from: Option<Duration>,
+
+    /// Only include entries starting on or before this date, such as `2024-06-01` or `last monday`.
+    #[arg(long, value_parser = parse_date_arg)]
+    to: Option<Duration>,
+}
+
+/// A range of days chosen with [`PeriodArgs`], including both ends.
+struct Period {
+    /// The week or month, if one was chosen.
+    name: Option<String>,
+    from: Duration,
+    to: Duration,
+}
+
+const ONE_DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+impl Period {
+    /// The week starting on `start`, named after the ISO week most of it falls in.
+    fn week(start: Duration) -> Self {
+        let (year, week) = date::iso_week(start + 3 * ONE_DAY);
+
+        Self {
+            name: Some(format!("week {year}-W{week:02}")),
+            from: start,
+            to: start + 6 * ONE_DAY,
+        }
+    }
+
+    /// The month starting on `start`.
+    fn month(start: Duration) -> Self {
+        Self {
+            name: Some(date::format_date(start)[..7].to_string()),
+            from: start,
+            to: date::start_of_next_month(start) - ONE_DAY,
+        }
+    }
+}
+
+impl PeriodArgs {
+    /// The days chosen, or the current week if none were.
+    fn resolve(&self, week_start: WeekStart) -> Result<Period> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+        Ok(if let Some(monday) = self.week {
+            Period::week(week_start.start_of_iso_week(monday))
+        } else if let Some(month) = self.month {
+            Period::month(month)
+        } else if self.from.is_some() || self.to.is_some() {
+            Period {
+                name: None,
+                from: self.from.unwrap_or_default(),
+                to: self.to.unwrap_or(now),
+            }
+        } else {
+            Period::week(week_start.start_of_week(now))
+        })
+    }
+}
+
+/// A period named on the command line, such as `last month`, which is resolved once the day
+/// weeks start on is known.
+#[derive(Debug, Clone, Copy)]
+enum NamedPeriod {
+    /// The week this many weeks before the current one.
+    WeeksAgo(u32),
+    IsoWeek(Duration),
+    /// The month this many months before the current one.
+    MonthsAgo(u32),
+    Month(Duration),
+    /// The year this many years before the current one.
+    YearsAgo(u32),
+    Year(i64),
+    Day(Duration),
+}
+
+impl NamedPeriod {
+    fn resolve(self, week_start: WeekStart, now: Duration) -> Period {
+        let year = |year: i64| Period {
+            name: Some(year.to_string()),
+            from: date::start_of_day(date::days_from_civil(year, 1, 1)),
+            to: date::start_of_day(date::days_from_civil(year, 12, 31)),
+        };
+
+        match self {
+            Self::WeeksAgo(weeks) => {
+                Period::week(week_start.start_of_week(now) - ONE_DAY * 7 * weeks)
+            }
+            Self::IsoWeek(monday) => Period::week(week_start.start_of_iso_week(monday)),
+            Self::MonthsAgo(months) => Period::month(date::add_months(now, -i64::from(months))),
+            Self::Month(month) => Period::month(month),
+            Self::YearsAgo(years) => {
+                year(date::civil_from_days(date::day_number(now)).0 - i64::from(years))
+            }
+            Self::Year(number) => year(number),
+            Self::Day(day) => Period {
+                name: Some(date::format_date(day)),
+                from: day,
+                to: day,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dates = format!(
+            "{} to {}",
+            date::format_date(self.from),
+            date::format_date(self.to)
+        );
+
+        match &self.name {
+            Some(name) => write!(f, "{name} ({dates})"),
+            None => write!(f, "{dates}"),
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Default)]
+struct TagFilter {
+    /// Only include entries with this tag. Repeat to require every tag given.
+    #[arg(long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+
+    /// Leave out entries with this tag. Can be repeated.
+    #[arg(long = "tag-not", value_name = "TAG")]
+    excluded: Vec<String>,
+
+    /// Include entries with any of the tags given with `--tag`, instead of all of them.
+    #[arg(long, requires = "tags")]
+    any_tag: bool,
+}
+
+impl TagFilter {
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.excluded.is_empty()
+    }
+
+    /// Whether an entry passes the filter. Tags are compared ignoring case.
+    fn matches(&self, time: &LoggedTime) -> bool {
+        let has = |tag: &String| time.tags.iter().any(|own| own.eq_ignore_ascii_case(tag));
+
+        let included = if self.tags.is_empty() {
+            true
+        } else if self.any_tag {
+            self.tags.iter().any(has)
+        } else {
+            self.tags.iter().all(has)
+        };
+
+        included && !self.excluded.iter().any(has)
+    }
+}
+
+impl fmt::Display for TagFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+
+        if !self.tags.is_empty() {
+            let joiner = if self.any_tag { " or " } else { " and " };
+            parts.push(format!("tagged {}", self.tags.join(joiner)));
+        }
+
+        if !self.excluded.is_empty() {
+            parts.push(format!("not tagged {}", self.excluded.join(" or ")));
+        }
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+#[derive(Parser, Debug)]
+enum InvoicesCommand {
+    /// List every invoice with its client, period, amount, and status.
+    List {
+        /// Only list invoices that have not been paid.
+        #[arg(long)]
+        outstanding: bool,
+    },
+
+    /// Record that an invoice was paid.
+    MarkPaid {
+        /// The number of the invoice, as shown by `invoices list`.
+        number: u64,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum TrashCommand {
+    /// List everything in the trash.
+    List,
+}
+
+#[derive(Parser, Debug)]
+enum TagCommand {
+    /// List every tag with the number of entries and the time it is on.
+    List,
+
+    /// Rename a tag on every entry and project that has it.
+    Rename {
+        /// The tag to rename.
+        old: String,
+
+        /// The new name of the tag.
+        #[arg(value_parser = clap::builder::NonEmptyStringValueParser::new())]
+        new: String,
+    },
+
+    /// Replace several tags with one on every entry and project that has them.
+    Merge {
+        /// The tags to merge.
+        #[arg(required = true, num_args = 2..)]
+        tags: Vec<String>,
+
+        /// The tag that replaces them, which may be one of them.
+        #[arg(long, value_parser = clap::builder::NonEmptyStringValueParser::new())]
+        into: String,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum SyncTarget {
+    /// Synchronize the data file with the WebDAV or S3 server in the config file.
+    Remote,
+
+    /// Add the time of entries that mention GitLab issues or merge requests, such as `#12` or
+    /// `!3`, to their time tracking, as `/spend` does.
+    Gitlab,
+
+    /// Comment on the Linear issues mentioned by entries, such as `ENG-42`, with the time
+    /// logged for them.
+    Linear,
+
+    /// Create Redmine time entries for entries that mention Redmine issues, such as `#123`.
+    Redmine,
+}
+
+#[derive(Parser, Debug)]
+enum ExportScope {
+    /// Export every project, entry, and running timer as JSON.
+    All {
+        /// Replace project names, clients, tags, and descriptions with hashes, and drop notes
+        /// and links, so the export can be shared without revealing what was worked on.
+        #[arg(long)]
+        anonymize: bool,
+
+        #[command(flatten)]
+        tags: TagFilter,
+
+        /// Include entries from the archive.
+        #[arg(short = 'a', long)]
+        include_archive: bool,
+    },
+
+    /// Export the data file, invoices, archives, and config together, with a manifest, for
+    /// backups or to hand over all of your data.
+    Everything {
+        /// Whether to write one JSON document or a zip file of JSON files.
+        #[arg(long, value_enum, default_value_t = BundleFormat::Json)]
+        format: BundleFormat,
+
+        /// The file to write to, instead of printing. Required for zip files.
+        #[arg(short, long, required_if_eq("format", "zip"))]
+        output: Option<PathBuf>,
+    },
+
+    /// Export entries as CSV for importing time activities into QuickBooks.
+    #[command(name = "quickbooks")]
+    QuickBooks(BookkeepingArgs),
+
+    /// Export entries as CSV for importing time entries into FreshBooks.
+    #[command(name = "freshbooks")]
+    FreshBooks(BookkeepingArgs),
+
+    /// Export entries as CSV in the columns you choose, for spreadsheets and other tools.
+    Csv(CsvArgs),
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+enum BundleFormat {
+    Json,
+    Zip,
+}
+
+#[derive(clap::Args, Debug)]
+struct BookkeepingArgs {
+    /// Only export entries of this project, instead of every project.
+    #[arg(short, long)]
+    project: Option<String>,
+
+    /// Include entries from the archive.
+    #[arg(short = 'a', long)]
+    include_archive: bool,
+
+    /// Only export entries starting on or after this date, such as `2024-06-01` or `last monday`.
+    #[arg(long, value_parser = parse_date_arg)]
+    from: Option<Duration>,
+
+    /// Only export entries starting on or before this date, such as `2024-06-01` or `last monday`.
+    #[arg(long, value_parser = parse_date_arg)]
+    to: Option<Duration>,
+
+    #[command(flatten)]
+    tags: TagFilter,
+}
+
+#[derive(clap::Args, Debug)]
+struct CsvArgs {
+    #[command(flatten)]
+    entries: BookkeepingArgs,
+
+    /// The columns to export, in order, separated by commas.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    columns: Option<Vec<CsvColumn>>,
+
+    /// The character that separates fields, such as `;` or `tab`.
+    #[arg(long, default_value = ",", value_parser = parse_delimiter_arg)]
+    delimiter: char,
+}
+
+#[derive(Parser, Debug)]
+enum ImportScope {
+    /// Import a full JSON export.
+    All(ImportAllArgs),
+}
+
+#[derive(clap::Args, Debug)]
+#[command(group(ArgGroup::new("mode").required(true)))]
+struct ImportAllArgs {
+    /// The path of the exported file.
+    file: PathBuf,
+
+    /// Merge the imported data with the existing data.
+    #[arg(long, group = "mode")]
+    merge: bool,
+
+    /// Replace all existing data with the imported data.
+    #[arg(long, group = "mode")]
+    replace: bool,
+
+    /// Prefer the imported data when merged entries or timers conflict.
+    #[arg(long, requires = "merge")]
+    prefer_import: bool,
+
+    /// Import entries with the same start and description as existing ones, which are
+    /// skipped as probable duplicates otherwise.
+    #[arg(long, requires = "merge")]
+    allow_duplicate: bool,
+
+    /// Show what would change without saving it.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("Could not parse duration {}. Use a form such as {}, {}, {}, or {}.", .0.bright_cyan(), "1h30m".bright_cyan(), "90 min".bright_cyan(), "1 hour 30".bright_cyan(), "half an hour".bright_cyan())]
+    InvalidDuration(String),
+
+    #[error("Could not parse time of day {}. Use the HH:MM format.", .0.bright_cyan())]
+    InvalidTimeOfDay(String),
+
+    #[error("Could not parse color {}. Use a name such as {} or a hex color such as {}.", .0.bright_cyan(), "bright blue".bright_cyan(), "#ff8800".bright_cyan())]
+    InvalidColor(String),
+
+    #[error("Could not parse pattern {} in the config file: {1}.", .0.bright_cyan())]
+    InvalidPattern(String, String),
+
+    #[error("Could not parse the template {}: {1}.", .0.display().to_string().bright_cyan())]
+    InvalidTemplate(PathBuf, String),
+
+    #[error("Could not parse the schedule {} in the config file. Use a form such as {}, {}, or {}.", .0.bright_cyan(), "friday 17:00".bright_cyan(), "weekdays 09:00".bright_cyan(), "mon,thu 12:00".bright_cyan())]
+    InvalidSchedule(String),
+
+    #[error("An error occurred while trying to get the system's current time.")]
+    SystemTime(#[from] std::time::SystemTimeError),
+
+    #[error("There is no project named {}", .0.bright_cyan())]
+    UnknownProject(String),
+
+    #[error("You do not currently have a project selected.")]
+    NoActiveProject,
+
+    #[error("The active project does not exist anymore.")]
+    UnknownActiveProject,
+
+    #[error("You are already tracking your time.")]
+    AlreadyStarted,
+
+    #[error("You are already tracking time for project {}. Pass {} to track both.", .0.bright_cyan(), "--parallel".bright_cyan())]
+    OtherTimerRunning(String),
+
+    #[error("You have not started tracking your time.")]
+    NotStarted,
+
+    #[error("The timer is already paused.")]
+    AlreadyPaused,
+
+    #[error("The timer is not paused.")]
+    NotPaused,
+
+    #[error("You have not logged any time for this project.")]
+    NoTimeLogged,
+
+    #[error("Cannot log entry with no description.")]
+    NoDescription,
+
+    #[error("project {} already exists", .0.bright_cyan())]
+    ProjectExists(String),
+
+    #[error("An error occurred while accessing a file: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Could not parse the data in {}: {1}", .0.display().to_string().bright_cyan())]
+    InvalidData(PathBuf, serde_json::Error),
+
+    #[error("Could not parse the data in {}: {1}", .0.display().to_string().bright_cyan())]
+    InvalidBinaryData(PathBuf, msgpack::Error),
+
+    #[error("A git command failed: {0}")]
+    Git(String),
+
+    #[error("Set {} in the config file to use git.", "data_dir".bright_cyan())]
+    NoDataDir,
+
+    #[error("An error occurred while running age: {0}")]
+    Encryption(String),
+
+    #[error("An error occurred while running zstd: {0}")]
+    Compression(String),
+
+    #[error("Could not update hat: {0}")]
+    Update(String),
+
+    #[error("Set {} in the config file to use encryption.", "age_identity".bright_cyan())]
+    NoIdentity,
+
+    #[error("The data file is already encrypted.")]
+    AlreadyEncrypted,
+
+    #[error("The data file is not encrypted.")]
+    NotEncrypted,
+
+    #[error("The data file is already stored as {0}.")]
+    AlreadyFormat(Format),
+
+    #[error("An error occurred while syncing with the remote: {0}")]
+    Remote(String),
+
+    #[error("Set {} in the config file to sync with a server.", "remote".bright_cyan())]
+    NoRemote,
+
+    #[error("An error occurred while talking to GitLab: {0}")]
+    GitLab(String),
+
+    #[error("Set {} in the config file to sync with GitLab.", "gitlab".bright_cyan())]
+    NoGitLab,
+
+    #[error("An error occurred while talking to Linear: {0}")]
+    Linear(String),
+
+    #[error("Set {} for project {} in the config file to use Linear.", "linear".bright_cyan(), .0.bright_cyan())]
+    NoLinear(String),
+
+    #[error("An error occurred while talking to Redmine: {0}")]
+    Redmine(String),
+
+    #[error("Set {} in the config file to sync with Redmine.", "redmine".bright_cyan())]
+    NoRedmine,
+
+    #[error("An error occurred while talking to the hat server: {0}")]
+    Server(String),
+
+    #[error("An entry with the same start and description is already logged. Pass {} to log it anyway.", "--allow-duplicate".bright_cyan())]
+    DuplicateEntry,
+
+    #[error("The entry would overlap {0} other entries, which {} in the config file forbids.", "refuse_overlaps".bright_cyan())]
+    Overlap(usize),
+
+    #[error("Could not parse {}. Use a form such as {}, {}, {}, or {}.", .0.bright_cyan(), "2024-06-03 09:00".bright_cyan(), "15:00".bright_cyan(), "yesterday 15:00".bright_cyan(), "2h ago".bright_cyan())]
+    InvalidDateTime(String),
+
+    #[error("The entry must end after it starts.")]
+    EndBeforeStart,
+
+    #[error("The entry can't be shortened by more than its duration.")]
+    NegativeDuration,
+
+    #[error("There is no entry #{0} for this project.")]
+    UnknownEntry(usize),
+
+    #[error("Line {0} of the edited entries is invalid: {1}.")]
+    InvalidEditedLine(usize, String),
+
+    #[error("The editor {} exited with an error.", .0.bright_cyan())]
+    EditorFailed(String),
+
+    #[error("Could not open {} in the browser.", .0.bright_cyan())]
+    BrowserFailed(String),
+
+    #[error("The job scheduled for {} failed: {1}.", .0.bright_cyan())]
+    JobFailed(String, String),
+
+    #[error("There is no template named {} in the config file.", .0.bright_cyan())]
+    UnknownTemplate(String),
+
+    #[error("Pass {} to confirm this outside of an interactive terminal.", "--force".bright_cyan())]
+    ForceRequired,
+
+    #[error("Cancelled.")]
+    Cancelled,
+
+    #[error("There is no item #{0} in the trash.")]
+    UnknownTrashItem(usize),
+
+    #[error("No entry or project has the tag {}.", .0.bright_cyan())]
+    UnknownTag(String),
+
+    #[error("No project has the client {}.", .0.bright_cyan())]
+    UnknownClient(String),
+
+    #[error("Project {} has no hourly rate. Set one with {}.", .0.bright_cyan(), "hat project set --rate".bright_cyan())]
+    NoRate(String),
+
+    #[error("Project {} has no estimate. Set one with {}.", .0.bright_cyan(), "hat project set --estimate".bright_cyan())]
+    NoEstimate(String),
+
+    #[error("Project {} has no URL. Set one with {}.", .0.bright_cyan(), "hat project set --url".bright_cyan())]
+    NoUrl(String),
+
+    #[error("No billable time that is not already invoiced was logged in this period.")]
+    NothingToInvoice,
+
+    #[error("No open Linear issues are assigned to you.")]
+    NoAssignedIssues,
+
+    #[error("There is no invoice {0:04}.")]
+    UnknownInvoice(u64),
+
+    #[error("Could not parse currency {}. Use a code such as {} or {}.", .0.bright_cyan(), "EUR".bright_cyan(), "USD".bright_cyan())]
+    InvalidCurrency(String),
+
+    #[error("Set {} in the config file to combine amounts in different currencies.", "base_currency".bright_cyan())]
+    NoBaseCurrency,
+
+    #[error("Set an exchange rate for {} in {} in the config file.", .0.bright_cyan(), "exchange_rates".bright_cyan())]
+    NoExchangeRate(String),
+
+    #[error("Pass a directory of data files or set {} in the config file.", "server".bright_cyan())]
+    NoTeamData,
+
+    #[error("Add jobs to {} or set {} in the config file to run the daemon.", "schedule".bright_cyan(), "goals".bright_cyan())]
+    NoScheduledJobs,
+}
+
+impl Error {
+    /// The exit code for the error, so that scripts can tell failures apart.
+    /// Usage errors are reported by clap with code 2.
+    fn exit_code(&self) -> i32 {
+        match self {
+            Self::NoActiveProject | Self::UnknownActiveProject => 3,
+            Self::UnknownProject(_)
+            | Self::UnknownEntry(_)
+            | Self::UnknownTemplate(_)
+            | Self::UnknownTrashItem(_)
+            | Self::UnknownTag(_)
+            | Self::UnknownClient(_)
+            | Self::UnknownInvoice(_) => 4,
+            Self::InvalidDuration(_)
+            | Self::InvalidTimeOfDay(_)
+            | Self::InvalidColor(_)
+            | Self::InvalidPattern(..)
+            | Self::InvalidTemplate(..)
+            | Self::InvalidSchedule(_)
+            | Self::InvalidEditedLine(..)
+            | Self::InvalidDateTime(_)
+            | Self::InvalidCurrency(_)
+            | Self::EndBeforeStart
+            | Self::NegativeDuration => 5,
+            Self::AlreadyStarted
+            | Self::OtherTimerRunning(_)
+            | Self::AlreadyPaused
+            | Self::ProjectExists(_)
+            | Self::DuplicateEntry
+            | Self::Overlap(_)
+            | Self::AlreadyEncrypted
+            | Self::AlreadyFormat(_) => 6,
+            Self::NotStarted | Self::NotPaused | Self::NotEncrypted => 7,
+            Self::NoTimeLogged
+            | Self::NoDescription
+            | Self::NothingToInvoice
+            | Self::NoAssignedIssues => 8,
+            Self::ForceRequired | Self::Cancelled => 9,
+            Self::NoDataDir
+            | Self::NoIdentity
+            | Self::NoRemote
+            | Self::NoGitLab
+            | Self::NoLinear(_)
+            | Self::NoRedmine
+            | Self::NoTeamData
+            | Self::NoScheduledJobs
+            | Self::NoRate(_)
+            | Self::NoEstimate(_)
+            | Self::NoUrl(_)
+            | Self::NoBaseCurrency
+            | Self::NoExchangeRate(_) => 10,
+            Self::Io(_)
+            | Self::InvalidData(..)
+            | Self::InvalidBinaryData(..)
+            | Self::Encryption(_)
+            | Self::Compression(_) => 11,
+            Self::Git(_)
+            | Self::Remote(_)
+            | Self::GitLab(_)
+            | Self::Linear(_)
+            | Self::Redmine(_)
+            | Self::Server(_)
+            | Self::Update(_) => 12,
+            Self::SystemTime(_)
+            | Self::EditorFailed(_)
+            | Self::BrowserFailed(_)
+            | Self::JobFailed(..) => 1,
+        }
+    }
+}
+
+impl From<hat_changer::Error> for Error {
+    fn from(err: hat_changer::Error) -> Self {
+        match err {
+            hat_changer::Error::UnknownProject(name) => Self::UnknownProject(name),
+            hat_changer::Error::AlreadyStarted => Self::AlreadyStarted,
+            hat_changer::Error::NotStarted => Self::NotStarted,
+            hat_changer::Error::NoTimeLogged => Self::NoTimeLogged,
+            hat_changer::Error::NoDescription => Self::NoDescription,
+            hat_changer::Error::DuplicateEntry => Self::DuplicateEntry,
+            hat_changer::Error::Store(message) => Self::Io(io::Error::other(message)),
+            hat_changer::Error::InvalidData(err) => Self::Io(err.into()),
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// The text shown in the editor when `log` is run without a description.
+const DESCRIPTION_TEMPLATE: &str =
+    "\n# Describe what you did. Lines starting with # are ignored, and an empty\n# description cancels logging the entry.\n";
+
+fn main() {
+    let home = homedir::get_my_home()
+        .expect("Could not read home directory.")
+        .expect("Home directory not found.");
+
+    let config = Config::load(&home);
+    let argv = expand_alias(&config, env::args().collect());
+
+    // `hat foo` runs the plugin `hat-foo` if there is one, unless foo is a project.
+    let plugin_name = argv
+        .get(1)
+        .filter(|name| Args::command().find_subcommand(name).is_none())
+        .cloned();
+    let plugin = plugin_name.as_deref().and_then(plugin::find);
+
+    let args = match Args::try_parse_from(&argv) {
+        Ok(args) => Some(args),
+        Err(err) if plugin.is_none() || !err.use_stderr() => err.exit(),
+        Err(_) => None,
+    };
+
+    if let Some(args) = &args {
+        QUIET.store(args.quiet, Ordering::Relaxed);
+        log::set_level(args.debug);
+    }
+
+    let mut storage = Storage::new(&config, &home);
+
+    let needs_entries = args
+        .as_ref()
+        .and_then(|args| args.command.as_ref())
+        .is_none_or(|command| command.needs_entries(&config));
+
+    let loaded = if needs_entries {
+        storage.load()
+    } else {
+        storage.load_index()
+    };
+
+    let mut list = match loaded {
+        Ok(list) => list,
+        Err(err) => {
+            eprintln!("{}", err.to_string().bright_yellow());
+            process::exit(err.exit_code());
+        }
+    };
+
+    // Fall back to the default project when none is selected, creating it if needed.
+    if list.active_project.is_none() {
+        if let Some(name) = &config.default_project {
+            log::debug!("Using the default project {name}.");
+            list.projects.entry(name.clone()).or_default();
+            list.active_project = Some(name.clone());
+        }
+    }
+
+    if let (Some(program), Some(name)) = (&plugin, &plugin_name) {
+        if args.is_none() || !list.projects.contains_key(name) {
+            let active = list.active_project.as_deref();
+
+            match plugin::run(program, argv[2..].to_vec(), storage.path(), active) {
+                Ok(code) => process::exit(code),
+                Err(err) => {
+                    eprintln!("{}", err.to_string().bright_yellow());
+                    process::exit(err.exit_code());
+                }
+            }
+        }
+    }
+
+    let args = args.expect("arguments are parsed unless a plugin runs");
+
+    let dry_run = args.command.as_ref().is_some_and(Commands::dry_run);
+    let unchanged = if dry_run {
+        list.clone()
+    } else {
+        ProjectList::default()
+    };
+
+    // `--project` makes another project the active one for this command alone.
+    let active = list.active_project.clone();
+
+    if let Some(name) = &args.project {
+        if let Err(err) = list.project(name) {
+            let err = Error::from(err);
+            eprintln!("{}", err.to_string().bright_yellow());
+            process::exit(err.exit_code());
+        }
+
+        list.active_project = Some(name.clone());
+    }
+
+    let result = match args.command {
+        Some(Commands::List {
+            include_archive,
+            plain,
+            detailed,
+            no_truncate,
+            columns,
+        }) => {
+            let columns = if detailed {
+                Some(ListColumn::DETAILED.to_vec())
+            } else if !columns.is_empty() {
+                Some(columns)
+            } else {
+                config.list_columns.clone()
+            };
+
+            with_archive(&list, &storage, &config, &home, include_archive)
+                .and_then(|list| {
+                    let max_width = table_width(no_truncate);
+                    handle_list(&list, plain, columns, max_width, config.week_start)
+                })
+                .map(|()| false)
+        }
+        Some(Commands::On { parallel }) => handle_on(&mut list, parallel).map(|()| true),
+        Some(Commands::Status) => handle_status(&list).map(|()| false),
+        Some(Commands::Current { running }) => handle_current(&list, running).map(|()| false),
+        Some(Commands::Total {
+            project,
+            today,
+            week,
+            from,
+            to,
+            seconds,
+            include_archive,
+        }) => {
+            let week = week.then_some(config.week_start);
+            with_archive(&list, &storage, &config, &home, include_archive)
+                .and_then(|list| {
+                    handle_total(&list, project.as_deref(), today, week, from, to, seconds)
+                })
+                .map(|()| false)
+        }
+        Some(Commands::Report {
+            project,
+            uninvoiced,
+            tags,
+            compare: Some(periods),
+            include_archive,
+            ..
+        }) => with_archive(&list, &storage, &config, &home, include_archive)
+            .and_then(|list| {
+                handle_report_compare(
+                    &list,
+                    &config,
+                    project.as_deref(),
+                    (periods[0], periods[1]),
+                    uninvoiced,
+                    &tags,
+                )
+            })
+            .map(|()| false),
+        Some(Commands::Report {
+            project,
+            period,
+            uninvoiced,
+            tags,
+            template,
+            compare: None,
+            include_archive,
+        }) => with_archive(&list, &storage, &config, &home, include_archive)
+            .and_then(|list| {
+                handle_report(
+                    &list,
+                    &config,
+                    project.as_deref(),
+                    &period,
+                    uninvoiced,
+                    &tags,
+                    template.as_deref(),
+                )
+            })
+            .map(|()| false),
+        Some(Commands::Invoice {
+            client,
+            project,
+            period,
+            dry_run: _,
+            template,
+        }) => handle_invoice(
+            &mut list,
+            &config,
+            client,
+            project,
+            &period,
+            template.as_deref(),
+        )
+        .map(|()| true),
+        Some(Commands::Invoices {
+            command: InvoicesCommand::List { outstanding },
+        }) => handle_invoices_list(&list, outstanding).map(|()| false),
+        Some(Commands::Invoices {
+            command: InvoicesCommand::MarkPaid { number },
+        }) => handle_invoices_mark_paid(&mut list, number),
+        Some(Commands::Switch {
+            project_name,
+            description,
+        }) => handle_switch(&mut list, &config, &project_name, description).map(|()| true),
+        Some(Commands::Pause { project }) => {
+            handle_pause(&mut list, project.as_deref()).map(|()| true)
+        }
+        Some(Commands::Resume { project }) => {
+            handle_resume(&mut list, project.as_deref()).map(|()| true)
+        }
+        Some(Commands::Off(args)) => handle_off(&mut list, &config, &args).map(|()| true),
+        Some(Commands::Log(args)) => handle_log(&mut list, &config, &args).map(|()| true),
+        Some(Commands::Suggestions) => handle_suggestions(&list).map(|()| false),
+        Some(Commands::Edit {
+            interactive: true,
+            limit,
+            ..
+        }) => handle_edit_interactive(&mut list, &config, limit.unwrap_or(DEFAULT_TIME_LIMIT)),
+        Some(Commands::Edit {
+            id,
+            start,
+            keep_end,
+            duration,
+            ..
+        }) => handle_edit(
+            &mut list,
+            &config,
+            id,
+            start.as_deref(),
+            keep_end,
+            &duration.join(" "),
+        )
+        .map(|()| true),
+        Some(Commands::Amend { .. }) => handle_amend(&mut list, &config).map(|()| true),
+        Some(Commands::Append { text, .. }) => {
+            handle_append(&mut list, &config, &text.join(" ")).map(|()| true)
+        }
+        Some(Commands::Undo) => handle_undo(&mut list).map(|()| true),
+        Some(Commands::Prune {
+            before,
+            project,
+            summarize,
+            force,
+            dry_run,
+        }) => handle_prune(
+            &mut list,
+            &storage,
+            before,
+            project.as_deref(),
+            summarize,
+            force || dry_run,
+            dry_run,
+        ),
+        Some(Commands::Trash {
+            command: TrashCommand::List,
+        }) => handle_trash_list(&list).map(|()| false),
+        Some(Commands::Restore { id }) => handle_restore(&mut list, id).map(|()| true),
+        Some(Commands::Project {
+            command: ProjectCommand::Show { name },
+        }) => handle_project_show(&list, name.as_deref()).map(|()| false),
+        Some(Commands::Project {
+            command: ProjectCommand::Set(args),
+        }) => handle_project_set(&mut list, &args).map(|()| true),
+        Some(Commands::Time(args)) => {
+            with_archive(&list, &storage, &config, &home, args.include_archive)
+                .and_then(|list| handle_time(&list, &args, config.time_columns.as_deref()))
+                .map(|()| false)
+        }
+        Some(Commands::ArchiveEntries { before }) => handle_archive_entries(
+            &mut list,
+            &storage,
+            &config.archive_dir(&home),
+            before,
+            config.compress_archives,
+        ),
+        Some(Commands::BulkEdit(args)) => handle_bulk_edit(&mut list, &args),
+        Some(Commands::Tag {
+            command: TagCommand::List,
+        }) => handle_tag_list(&list).map(|()| false),
+        Some(Commands::Tag {
+            command: TagCommand::Rename { old, new },
+        }) => handle_tag_merge(&mut list, &[old], &new).map(|()| true),
+        Some(Commands::Tag {
+            command: TagCommand::Merge { tags, into },
+        }) => handle_tag_merge(&mut list, &tags, &into).map(|()| true),
+        Some(Commands::Redact(args)) => handle_redact(&mut list, &storage, &config, &home, &args),
+        Some(Commands::Note { id }) => handle_note(&mut list, id),
+        Some(Commands::New { project_name }) => handle_new(&mut list, &project_name).map(|()| true),
+        Some(Commands::Delete {
+            project_name,
+            force,
+            dry_run,
+        }) => handle_delete(&mut list, &project_name, force || dry_run).map(|()| true),
+        Some(Commands::DeleteEntries {
+            project,
+            from,
+            to,
+            force,
+            dry_run,
+        }) => handle_delete_entries(&mut list, project.as_deref(), from, to, force || dry_run),
+        Some(Commands::Doctor) => handle_doctor(&list).map(|()| false),
+        Some(Commands::Fix {
+            dedupe,
+            clamp_future,
+            drop_zero,
+            yes,
+        }) => handle_fix(&mut list, &storage, dedupe, clamp_future, drop_zero, yes),
+        Some(Commands::Export {
+            scope:
+                ExportScope::All {
+                    anonymize,
+                    tags,
+                    include_archive,
+                },
+        }) => with_archive(&list, &storage, &config, &home, include_archive)
+            .and_then(|list| handle_export_all(&list, anonymize, &tags))
+            .map(|()| false),
+        Some(Commands::Export {
+            scope: ExportScope::Everything { format, output },
+        }) => handle_export_everything(&list, &storage, &config, &home, format, output.as_deref())
+            .map(|()| false),
+        Some(Commands::Export {
+            scope: ExportScope::QuickBooks(args),
+        }) => with_archive(&list, &storage, &config, &home, args.include_archive)
+            .and_then(|list| handle_export_bookkeeping(&list, Layout::QuickBooks, &args))
+            .map(|()| false),
+        Some(Commands::Export {
+            scope: ExportScope::FreshBooks(args),
+        }) => with_archive(&list, &storage, &config, &home, args.include_archive)
+            .and_then(|list| handle_export_bookkeeping(&list, Layout::FreshBooks, &args))
+            .map(|()| false),
+        Some(Commands::Export {
+            scope: ExportScope::Csv(args),
+        }) => with_archive(
+            &list,
+            &storage,
+            &config,
+            &home,
+            args.entries.include_archive,
+        )
+        .and_then(|list| handle_export_csv(&list, &args, config.csv_columns.as_deref()))
+        .map(|()| false),
+        Some(Commands::Import {
+            scope: ImportScope::All(args),
+        }) => handle_import_all(&mut list, &storage, &config, &args).map(|()| true),
+        Some(Commands::Sync {
+            target: None,
+            dry_run,
+        }) => handle_sync(&config, dry_run).map(|()| false),
+        Some(Commands::Sync {
+            target: Some(SyncTarget::Remote),
+            dry_run,
+        }) => handle_sync_remote(&mut list, &storage, &config, dry_run).map(|()| false),
+        Some(Commands::Sync {
+            target: Some(SyncTarget::Gitlab),
+            dry_run,
+        }) => handle_sync_gitlab(&list, &storage, &config, dry_run).map(|()| false),
+        Some(Commands::Sync {
+            target: Some(SyncTarget::Linear),
+            dry_run,
+        }) => handle_sync_linear(&list, &storage, &config, dry_run).map(|()| false),
+        Some(Commands::Sync {
+            target: Some(SyncTarget::Redmine),
+            dry_run,
+        }) => handle_sync_redmine(&list, &storage, &config, dry_run).map(|()| false),
+        Some(Commands::Serve { dir, addr }) => server::serve(&addr, &dir).map(|()| false),
+        Some(Commands::Daemon) => handle_daemon(&config, &home, &mut storage).map(|()| false),
+        Some(Commands::TeamReport { dir, from, to }) => {
+            handle_team_report(&config, dir.as_deref(), from, to).map(|()| false)
+        }
+        Some(Commands::Encrypt) => handle_encrypt(&list, &mut storage, &config).map(|()| false),
+        Some(Commands::Decrypt) => handle_decrypt(&list, &mut storage, &config).map(|()| false),
+        Some(Commands::Reindex) => handle_reindex(&mut list),
+        Some(Commands::Normalize) => handle_normalize(&mut list).map(|()| true),
+        Some(Commands::SelfUpdate { check }) => handle_self_update(check).map(|()| false),
+        Some(Commands::Burndown { name }) => {
+            handle_burndown(&list, name.as_deref()).map(|()| false)
+        }
+        Some(Commands::Open { name }) => handle_open(&list, name.as_deref()).map(|()| false),
+        Some(Commands::Chart { days }) => handle_chart(&list, days.into()).map(|()| false),
+        Some(Commands::Stats { storage: true, .. }) => {
+            handle_stats_storage(&list, &storage, &config, &home).map(|()| false)
+        }
+        Some(Commands::Stats {
+            distribution: true,
+            include_archive,
+            ..
+        }) => with_archive(&list, &storage, &config, &home, include_archive)
+            .and_then(|list| handle_stats_distribution(&list))
+            .map(|()| false),
+        Some(Commands::Stats {
+            include_archive, ..
+        }) => with_archive(&list, &storage, &config, &home, include_archive)
+            .and_then(|list| handle_stats(&list))
+            .map(|()| false),
+        Some(Commands::ConvertFormat { format }) => {
+            handle_convert_format(&mut storage, &config, &home, format).map(|()| true)
+        }
+        Some(Commands::MergeFile { file, ours, theirs }) => {
+            handle_merge_file(&mut list, &storage, &file, ours, theirs).map(|()| true)
+        }
+        None => {
+            if let Some(project_name) = args.project_name {
+                handle_hat(&mut list, &project_name).map(|()| true)
+            } else {
+                handle_time(&list, &TimeArgs::default(), config.time_columns.as_deref())
+                    .map(|()| false)
+            }
+        }
+    };
+
+    // The active project is put back, unless the command chose another one itself.
+    if args.project.is_some() && list.active_project == args.project {
+        list.active_project = active;
+    }
+
+    // Only persist the data file if the command succeeded and changed something.
+    match result {
+        Ok(true) if dry_run => {
+            diff::print_changes(&unchanged, &list);
+            println!(
+                "{}",
+                "This was a dry run, so nothing was saved.".bright_yellow()
+            );
+        }
+        Ok(true) => {
+            list.modified_epoch = SystemTime::now().duration_since(UNIX_EPOCH).ok();
+
+            if let Some(now) = list.modified_epoch {
+                trash::purge(&mut list.trash, now, config.trash_retention_days());
+            }
+
+            if config.whole_seconds {
+                list.normalize();
+            }
+
+            // Without the entries, the index would be rebuilt empty.
+            if storage.entries_loaded() {
+                list.link_tickets();
+                list.reindex();
+            }
+
+            let saved = storage
+                .save(&list)
+                .and_then(|()| commit_list(&config, &storage.paths()));
+
+            if let Err(err) = saved {
+                eprintln!("{}", err.to_string().bright_yellow());
+                process::exit(err.exit_code());
+            }
+        }
+        Ok(false) => {}
+        // A reader such as `head` closing the pipe early means it has read all it wanted.
+        Err(Error::Io(err)) if err.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(err) => {
+            eprintln!("{}", err.to_string().bright_yellow());
+            process::exit(err.exit_code());
+        }
+    }
+
+    // Scripts and prompts don't need to hear about new versions.
+    if config.check_for_updates && io::stderr().is_terminal() {
+        notify_update(&config, &home);
+    }
+
+    // Goals can only be checked with every entry loaded.
+    if !dry_run && storage.entries_loaded() {
+        notify_goals(&config, &home, &list);
+    }
+}
+
+/// Mentions the goals reached since they were last mentioned, on stderr if it is a terminal
+/// and as desktop notifications if the config file asks for them. Failures are only logged.
+fn notify_goals(config: &Config, home: &Path, list: &ProjectList) {
+    let Some(goals) = &config.goals else {
+        return;
+    };
+
+    let in_terminal = io::stderr().is_terminal();
+
+    // Goals are only recorded as mentioned if they can be mentioned somewhere.
+    if !in_terminal && !goals.desktop_notifications {
+        return;
+    }
+
+    let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) else {
+        return;
+    };
+
+    let state_path = config.goals_state_path(home);
+
+    match goals::check(goals, list, now, config.week_start, &state_path) {
+        Ok(messages) => {
+            for message in messages {
+                if in_terminal {
+                    eprintln!("{}", message.bright_green());
+                }
+
+                if goals.desktop_notifications {
+                    goals::notify_desktop(&message);
+                }
+            }
+        }
+        Err(err) => log::debug!("Could not check the goals: {err}"),
+    }
+}
+
+/// Mentions a new release if the daily check finds one. Failures are only logged, since the
+/// command itself succeeded.
+fn notify_update(config: &Config, home: &Path) {
+    let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) else {
+        return;
+    };
+
+    match update::check(&config.update_check_path(home), now) {
+        Ok(Some(version)) => eprintln!(
+            "{}",
+            format!(
+                "hat {version} is available, and you have {}. Run {} to install it.",
+                update::CURRENT_VERSION,
+                "hat self-update".bright_cyan()
+            )
+            .bright_yellow()
+        ),
+        Ok(None) => {}
+        Err(err) => log::debug!("Could not check for a new release: {err}"),
+    }
+}
+
+fn commit_list(config: &Config, paths: &[&Path]) -> Result<()> {
+    if !config.auto_commit {
+        return Ok(());
+    }
+
+    let Some(dir) = &config.data_dir else {
+        return Err(Error::NoDataDir);
+    };
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let message = format!("hat {}", args.join(" "));
+
+    git::commit(dir, paths, message.trim())
+}
+
+fn parse_date_arg(text: &str) -> std::result::Result<Duration, String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| err.to_string())?;
+
+    when::parse(text, now).ok_or_else(|| {
+        format!(
+            "invalid date {text}, expected YYYY-MM-DD or a form such as yesterday or last monday"
+        )
+    })
+}
+
+fn parse_delimiter_arg(text: &str) -> std::result::Result<char, String> {
+    let delimiter = match text {
+        "tab" | "\\t" => '\t',
+        _ => {
+            let mut chars = text.chars();
+
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => return Err(format!("invalid delimiter {text}, expected one character")),
+            }
+        }
+    };
+
+    if matches!(delimiter, '"' | '\n' | '\r') {
+        return Err(format!("{delimiter:?} can't be used as a delimiter"));
+    }
+
+    Ok(delimiter)
+}
+
+fn parse_period_arg(text: &str) -> std::result::Result<NamedPeriod, String> {
+    let text = text.trim().to_lowercase();
+
+    let relative = match text.as_str() {
+        "this week" => Some((0, "week")),
+        "last week" => Some((1, "week")),
+        "this month" => Some((0, "month")),
+        "last month" => Some((1, "month")),
+        "this year" => Some((0, "year")),
+        "last year" => Some((1, "year")),
+        _ => text.strip_suffix(" ago").and_then(|amount| {
+            let (count, unit) = amount.split_once(char::is_whitespace)?;
+            Some((count.parse().ok()?, unit.trim().trim_end_matches('s')))
+        }),
+    };
+
+    match relative {
+        Some((count, "week")) => return Ok(NamedPeriod::WeeksAgo(count)),
+        Some((count, "month")) => return Ok(NamedPeriod::MonthsAgo(count)),
+        Some((count, "year")) => return Ok(NamedPeriod::YearsAgo(count)),
+        _ => {}
+    }
+
+    if let Some(monday) = date::parse_iso_week(&text) {
+        return Ok(NamedPeriod::IsoWeek(monday));
+    }
+
+    if text.len() == 4 && text.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(NamedPeriod::Year(text.parse().map_err(|_| text.clone())?));
+    }
+
+    if text.len() == 7 {
+        if let Some(month) = date::parse_month(&text) {
+            return Ok(NamedPeriod::Month(month));
+        }
+    }
+
+    let day = parse_date_arg(&text).map_err(|_| {
+        format!(
+            "invalid period {text}, expected a form such as this month, last week, 3 months ago, 2024-06, 2024-W23, 2024, or a day"
+        )
+    })?;
+
+    Ok(NamedPeriod::Day(date::start_of_day(date::day_number(day))))
+}
+
+fn parse_week_arg(text: &str) -> std::result::Result<Duration, String> {
+    date::parse_iso_week(text).ok_or_else(|| format!("invalid week {text}, expected YYYY-Www"))
+}
+
+fn parse_month_arg(text: &str) -> std::result::Result<Duration, String> {
+    date::parse_month(text).ok_or_else(|| format!("invalid month {text}, expected YYYY-MM"))
+}
+
+fn parse_year_or_date_arg(text: &str) -> std::result::Result<Duration, String> {
+    if text.len() == 4 && text.chars().all(|c| c.is_ascii_digit()) {
+        return parse_date_arg(&format!("{text}-01-01"));
+    }
+
+    parse_date_arg(text)
+}
+
+/// Returns the project list with archived entries added if `include_archive` is set.
+fn with_archive<'a>(
+    list: &'a ProjectList,
+    storage: &Storage,
+    config: &Config,
+    home: &Path,
+    include_archive: bool,
+) -> Result<Cow<'a, ProjectList>> {
+    if include_archive {
+        archive::include(list, storage, &config.archive_dir(home)).map(Cow::Owned)
+    } else {
+        Ok(Cow::Borrowed(list))
+    }
+}
+
+/// The entries of a project starting on or after `from` and on or before the day of `to`,
+/// sorted by start.
+fn entries_in_range(
+    project: &Project,
+    from: Option<Duration>,
+    to: Option<Duration>,
+) -> Vec<&LoggedTime> {
+    let to = to.map(|to| date::start_of_day(date::day_number(to) + 1));
+    project.entries_between(from, to)
+}
+
+/// Whether an entry starts within the given dates, where `to` is the last day included.
+fn in_range(time: &LoggedTime, from: Option<Duration>, to: Option<Duration>) -> bool {
+    let after_start = from.is_none_or(|from| time.start_epoch >= from);
+    let before_end =
+        to.is_none_or(|to| time.start_epoch < date::start_of_day(date::day_number(to) + 1));
+    after_start && before_end
+}
+
+/// The number of descriptions suggested by `off --pick` and shell completion.
+const SUGGESTION_LIMIT: usize = 10;
+
+fn pick_description(logged_times: &[LoggedTime]) -> Result<String> {
+    let suggestions = suggest::descriptions(logged_times, SUGGESTION_LIMIT);
+
+    if suggestions.is_empty() {
+        return Err(Error::NoTimeLogged);
+    }
+
+    pick(&suggestions)
+}
+
+/// Asks which of the open Linear issues assigned to the user was worked on, as its identifier
+/// and title.
+fn pick_issue(config: &Config, project: &str) -> Result<String> {
+    let Some(linear) = config.linear.get(project) else {
+        return Err(Error::NoLinear(project.to_string()));
+    };
+
+    let suggestions: Vec<String> = linear
+        .assigned_issues()?
+        .into_iter()
+        .map(|issue| format!("{} {}", issue.identifier, issue.title))
+        .collect();
+
+    if suggestions.is_empty() {
+        return Err(Error::NoAssignedIssues);
+    }
+
+    pick(&suggestions)
+}
+
+/// Asks for one of some descriptions by number.
+fn pick(suggestions: &[String]) -> Result<String> {
+    for (index, description) in suggestions.iter().enumerate() {
+        let number = format!("{:>2}.", index + 1).bright_yellow();
+        println!("  {number} {}", description.bright_blue());
+    }
+
+    loop {
+        let options = format!("[1-{}]", suggestions.len());
+
+        let Some(answer) = ask("Pick a description:", &options) else {
+            return Err(Error::NoDescription);
+        };
+
+        let picked = answer
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| suggestions.get(number.checked_sub(1)?));
+
+        if let Some(description) = picked {
+            return Ok(description.clone());
+        }
+    }
+}
+
+/// Asks for a description on the terminal, offering the recent descriptions of a project to
+/// pick from by number. Outside of an interactive terminal, there is no one to ask.
+fn prompt_description(logged_times: &[LoggedTime]) -> Result<String> {
+    if !io::stdin().is_terminal() {
+        return Err(Error::NoDescription);
+    }
+
+    let suggestions = suggest::descriptions(logged_times, SUGGESTION_LIMIT);
+
+    for (index, description) in suggestions.iter().enumerate() {
+        let number = format!("{:>2}.", index + 1).bright_yellow();
+        println!("  {number} {}", description.bright_blue());
+    }
+
+    let options = if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!("[1-{} or a new one]", suggestions.len())
+    };
+
+    let answer = read_answer("What did you work on?", &options).unwrap_or_default();
+
+    let picked = answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| suggestions.get(number.checked_sub(1)?));
+
+    match picked {
+        Some(description) => Ok(description.clone()),
+        None if answer.is_empty() => Err(Error::NoDescription),
+        None => Ok(answer),
+    }
+}
+
+/// Asks a question and returns the trimmed answer, or `None` if stdin was closed.
+fn read_answer(question: &str, options: &str) -> Option<String> {
+    if options.is_empty() {
+        print!("{} ", question.bright_yellow());
+    } else {
+        print!("{} {} ", question.bright_yellow(), options.bright_blue());
+    }
+
+    io::stdout().flush().ok()?;
+
+    let mut answer = String::new();
+
+    match io::stdin().read_line(&mut answer) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(answer.trim().to_string()),
+    }
+}
+
+fn ask(question: &str, options: &str) -> Option<String> {
+    read_answer(question, options).map(|answer| answer.to_lowercase())
+}
+
+fn confirm(question: &str) -> bool {
+    matches!(ask(question, "[y/N]").as_deref(), Some("y" | "yes"))
+}
+
+/// Asks before destroying data, unless `force` is set. Without a terminal to ask on,
+/// `force` is required.
+fn confirm_destructive(question: &str, force: bool) -> Result<()> {
+    if force {
+        return Ok(());
+    }
+
+    if !io::stdin().is_terminal() {
+        return Err(Error::ForceRequired);
+    }
+
+    if confirm(question) {
+        Ok(())
+    } else {
+        Err(Error::Cancelled)
+    }
+}
+
+/// Replaces tabs and line breaks so that a value fits in one tab-separated column.
+fn plain_field(text: &str) -> String {
+    text.replace(['\t', '\n', '\r'], " ")
+}
+
+/// Replaces an alias from the config file with the command it stands for, if the first argument
+/// after the global flags is one. Built-in commands can't be overridden.
+fn expand_alias(config: &Config, argv: Vec<String>) -> Vec<String> {
+    let Some(index) = command_index(&argv) else {
+        return argv;
+    };
+
+    let Some(expansion) = Some(&argv[index])
+        .filter(|name| Args::command().find_subcommand(name).is_none())
+        .and_then(|name| config.aliases.get(name))
+    else {
+        return argv;
+    };
+
+    let mut expanded = argv[..index].to_vec();
+    expanded.extend(expansion.split_whitespace().map(str::to_string));
+    expanded.extend(argv.into_iter().skip(index + 1));
+    expanded
+}
+
+/// The index of the first argument that is not a global option or the value of one, such as
+/// the name in `--project NAME`.
+fn command_index(argv: &[String]) -> Option<usize> {
+    let command = Args::command();
+    let mut index = 1;
+
+    while let Some(arg) = argv.get(index) {
+        let option = if let Some(long) = arg.strip_prefix("--") {
+            // The value is part of the argument in `--project=NAME`.
+            (!long.is_empty() && !long.contains('='))
+                .then(|| {
+                    command
+                        .get_arguments()
+                        .find(|option| option.get_long() == Some(long))
+                })
+                .flatten()
+        } else if let Some(shorts) = arg.strip_prefix('-').filter(|shorts| !shorts.is_empty()) {
+            // Only the last of several short flags, as in `-qd`, can take the next argument.
+            let last = shorts.chars().last();
+            command
+                .get_arguments()
+                .find(|option| option.get_short() == last)
+        } else {
+            return Some(index);
+        };
+
+        let takes_value = option.is_some_and(|option| option.get_action().takes_values());
+        index += if takes_value { 2 } else { 1 };
+    }
+
+    None
+}
+
+/// The project a command should act on: the given one, or else the active project.
+fn target_project(list: &ProjectList, project: Option<&str>) -> Result<String> {
+    if let Some(name) = project {
+        if !list.projects.contains_key(name) {
+            return Err(Error::UnknownProject(name.to_string()));
+        }
+
+        return Ok(name.to_string());
+    }
+
+    let Some(active) = list.active_project.clone() else {
+        return Err(Error::NoActiveProject);
+    };
+
+    if !list.projects.contains_key(&active) {
+        return Err(Error::UnknownActiveProject);
+    }
+
+    Ok(active)
+}
+
+fn move_to_trash(list: &mut ProjectList, contents: Trashed) -> Result<()> {
+    let deleted_epoch = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+    list.trash.push(TrashItem {
+        deleted_epoch,
+        contents,
+    });
+
+    Ok(())
+}
+
+fn total_time(logged_times: &[LoggedTime]) -> Duration {
+    logged_times
+        .iter()
+        .fold(Duration::default(), |acc, time| acc + time.duration)
+}
+
+fn handle_list(
+    list: &ProjectList,
+    plain: bool,
+    columns: Option<Vec<ListColumn>>,
+    max_width: Option<usize>,
+    week_start: WeekStart,
+) -> Result<()> {
+    if plain {
+        let mut projects: Vec<(&String, &Project)> = list.projects.iter().collect();
+        projects.sort_by_key(|(name, _)| *name);
+
+        for (name, project) in projects {
+            let start = project
+                .start_epoch
+                .map(|start| start.as_secs().to_string())
+                .unwrap_or_default();
+            let total = project.total_logged().as_secs();
+            println!("{start}\t{total}\t{}", plain_field(name));
+        }
+
+        return Ok(());
+    }
+
+    if list.projects.is_empty() {
+        println!("{}", "No projects found.".bright_red());
+        return Ok(());
+    } else {
+        println!("{}", "Project list:".bright_yellow());
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let mut out = io::stdout().lock();
+
+    // The timer is left out of the default columns when no timer is running.
+    let timers = list
+        .projects
+        .values()
+        .any(|project| project.start_epoch.is_some());
+
+    let columns = columns.unwrap_or_else(|| {
+        ListColumn::DEFAULT
+            .into_iter()
+            .filter(|column| *column != ListColumn::Timer || timers)
+            .collect()
+    });
+
+    project_table(list, now, week_start, &columns)
+        .max_width(max_width)
+        .write(&mut out, "  ")?;
+
+    Ok(())
+}
+
+/// A row for each project with the given columns. The times don't include running timers,
+/// which the timer column shows.
+fn project_table(
+    list: &ProjectList,
+    now: Duration,
+    week_start: WeekStart,
+    columns: &[ListColumn],
+) -> Table {
+    let titles: Vec<(&'static str, Align)> = columns
+        .iter()
+        .map(|column| (column.title(), column.align()))
+        .collect();
+
+    let flexible: Vec<&str> = columns
+        .iter()
+        .filter(|column| column.flexible())
+        .map(|column| column.title())
+        .collect();
+
+    let mut table = Table::new(&titles).flexible(&flexible);
+
+    let mut names: Vec<&String> = list.projects.keys().collect();
+    names.sort();
+
+    let week = week_start.start_of_week(now);
+
+    for name in names {
+        let project = &list.projects[name];
+
+        let cells = columns
+            .iter()
+            .map(|column| match column {
+                ListColumn::Project => {
+                    // Projects without a color of their own show the active project in green.
+                    let styled = match (
+                        list.active_project.as_ref() == Some(name),
+                        &project.metadata.color,
+                    ) {
+                        (true, Some(_)) => project_name(list, name).bold(),
+                        (true, None) => name.bright_green(),
+                        (false, _) => project_name(list, name),
+                    };
+
+                    Cell::styled(name.as_str(), styled)
+                }
+                ListColumn::Client => {
+                    let client = project.metadata.client.as_deref().unwrap_or("-");
+                    Cell::styled(client, client.bright_blue())
+                }
+                ListColumn::LastActive => Cell::plain(
+                    project
+                        .logged_times
+                        .iter()
+                        .map(LoggedTime::end_epoch)
+                        .chain(project.start_epoch.map(|_| now))
+                        .max()
+                        .map(date::format_date)
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                ListColumn::Entries => Cell::plain(project.logged_times.len().to_string()),
+                ListColumn::Week => {
+                    let this_week: Duration = entries_in_range(project, Some(week), None)
+                        .iter()
+                        .map(|time| time.duration)
+                        .sum();
+                    let this_week = pretty_duration(&whole_seconds(this_week), None);
+                    Cell::styled(this_week.as_str(), this_week.bright_red())
+                }
+                ListColumn::Total => {
+                    let total = pretty_duration(&project.total_logged(), None);
+                    Cell::styled(total.as_str(), total.bright_red())
+                }
+                ListColumn::State => {
+                    let mut state = Vec::new();
+
+                    if list.active_project.as_ref() == Some(name) {
+                        state.push("active");
+                    }
+
+                    match (project.start_epoch, project.paused_epoch) {
+                        (Some(_), Some(_)) => state.push("paused"),
+                        (Some(_), None) => state.push("running"),
+                        _ => {}
+                    }
+
+                    let state = state.join(", ");
+                    Cell::styled(state.as_str(), state.bright_green())
+                }
+                ListColumn::Timer => {
+                    let note = timer_note(project, now).unwrap_or_default();
+                    Cell::styled(note.as_str(), note.bright_yellow())
+                }
+            })
+            .collect();
+
+        table.row(cells);
+    }
+
+    table
+}
+
+/// Prints the metadata fields of a project that are set, one per line.
+fn print_metadata(metadata: &ProjectMetadata, indent: &str) {
+    let fields = [
+        ("description", &metadata.description),
+        ("client", &metadata.client),
+        ("color", &metadata.color),
+        ("url", &metadata.url),
+    ];
+
+    for (label, value) in fields {
+        if let Some(value) = value {
+            println!("{indent}{label}: {}", value.bright_blue());
+        }
+    }
+}
+
+fn handle_on(list: &mut ProjectList, parallel: bool) -> Result<()> {
+    let Some(active) = list.active_project.clone() else {
+        return Err(Error::NoActiveProject);
+    };
+
+    if !parallel {
+        let mut running = list
+            .projects
+            .iter()
+            .filter(|(name, project)| **name != active && project.start_epoch.is_some());
+
+        if let Some((name, _)) = running.next() {
+            return Err(Error::OtherTimerRunning(name.clone()));
+        }
+    }
+
+    if !list.projects.contains_key(&active) {
+        return Err(Error::UnknownActiveProject);
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    list.start(&active, now)?;
+
+    let name = project_name(list, &active);
+
+    success!("Now tracking time for project {}.", name);
+
+    Ok(())
+}
+
+fn handle_off(list: &mut ProjectList, config: &Config, args: &OffArgs) -> Result<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    stop_timer(list, config, args, now)?;
+
+    if config.show_streaks {
+        let streaks = streak::streaks(list, date::day_number(now));
+
+        if streaks.current > 1 {
+            success!("You've logged time {} weekdays in a row.", streaks.current);
+        }
+    }
+
+    Ok(())
+}
+
+/// Expands a description that is only a GitHub issue reference into the issue's title, if
+/// GitHub is set up. Without a connection, the reference is kept as it was typed.
+fn expand_issue(config: &Config, name: &str, project: &Project, description: String) -> String {
+    let Some(github) = &config.github else {
+        return description;
+    };
+
+    let number = description
+        .strip_prefix('#')
+        .and_then(|number| number.parse::<u64>().ok());
+
+    let (Some(number), Some(repo)) = (number, github.repo(name, project.metadata.url.as_deref()))
+    else {
+        return description;
+    };
+
+    match github.issue_title(&repo, number) {
+        Some(title) => format!("{title} ({description})"),
+        None => {
+            println!(
+                "{}",
+                format!("Could not look up {description} on GitHub, so it was kept as typed.")
+                    .bright_yellow()
+            );
+            description
+        }
+    }
+}
+
+/// Stops a timer at the given time and logs an entry for it.
+fn stop_timer(
+    list: &mut ProjectList,
+    config: &Config,
+    args: &OffArgs,
+    now: Duration,
+) -> Result<()> {
+    let active = target_project(list, args.project.as_deref())?;
+    let project = list
+        .projects
+        .get_mut(&active)
+        .expect("target project exists");
+
+    let description = args.description.join(" ");
+
+    if project.start_epoch.is_none() {
+        return Err(Error::NotStarted);
+    }
+
+    let same = args
+        .same
+        .then(|| {
+            project
+                .last_logged_index()
+                .map(|index| project.logged_times[index].clone())
+                .ok_or(Error::NoTimeLogged)
+        })
+        .transpose()?;
+
+    let mut notes = None;
+
+    let description = if args.stdin {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+
+        let text = text.trim();
+        let (description, rest) = text.split_once('\n').unwrap_or((text, ""));
+        let rest = rest.trim();
+
+        notes = (!rest.is_empty()).then(|| rest.to_string());
+        description.trim().to_string()
+    } else if let Some(name) = &args.template {
+        let Some(template) = config.templates.get(name) else {
+            return Err(Error::UnknownTemplate(name.to_string()));
+        };
+
+        let expanded = template::expand(template, &active, &project.logged_times, now);
+        format!("{expanded} {}", description.trim())
+            .trim()
+            .to_string()
+    } else if let Some(last) = &same {
+        format!("{} {}", last.description, description.trim())
+            .trim()
+            .to_string()
+    } else if args.pick {
+        let picked = pick_description(&project.logged_times)?;
+        format!("{picked} {}", description.trim())
+            .trim()
+            .to_string()
+    } else if args.pick_issue {
+        let picked = pick_issue(config, &active)?;
+        format!("{picked} {}", description.trim())
+            .trim()
+            .to_string()
+    } else if description.trim().is_empty() {
+        prompt_description(&project.logged_times)?
+    } else {
+        description.trim().to_string()
+    };
+
+    let description = expand_issue(config, &active, project, description);
+
+    let end = project
+        .settings
+        .stop_time(project.start_epoch.unwrap_or(now), now);
+    let pauses = project.pauses(end);
+
+    let time = list.stop(&active, &description, now)?;
+    let (start_epoch, duration) = (time.start_epoch, time.duration);
+
+    log::trace!(
+        "Timer for {active} started at {start_epoch:?} and stopped at {end:?}, tracking {duration:?}."
+    );
+
+    let deducted = breaks::deduction(&config.break_rules, start_epoch, end, &pauses, duration)?;
+    log::trace!("Deducted {deducted:?} for breaks.");
+    let duration = duration - deducted;
+
+    if let Some(minimum) = &config.minimum_duration {
+        let minimum = parse_duration_text(minimum)?;
+
+        if duration < minimum
+            && io::stdin().is_terminal()
+            && !resolve_short_entry(list, &active, duration)?
+        {
+            return Ok(());
+        }
+    }
+
+    let project = list.project_mut(&active)?;
+
+    // Restarting the same task shortly after stopping it continues the previous entry.
+    let merged_gap = match &config.merge_gap {
+        Some(limit) => mergeable_gap(&project.logged_times, parse_duration_text(limit)?),
+        None => None,
+    };
+
+    let total = if merged_gap.is_some() {
+        project.logged_times.pop();
+        let previous = project
+            .logged_times
+            .last()
+            .expect("there is a previous entry");
+        previous.duration + duration
+    } else {
+        duration
+    };
+
+    let round_minutes = args
+        .round
+        .or(project.settings.round_minutes)
+        .or(config.round_minutes)
+        .filter(|minutes| *minutes > 0);
+    let rounded = round_minutes.map(|minutes| round_to_minutes(total, minutes));
+
+    let time = project
+        .logged_times
+        .last_mut()
+        .expect("an entry was just logged");
+    time.duration = total;
+    time.rounded = rounded;
+
+    // Notes of an entry that was merged into the previous one are added to its notes.
+    if let Some(notes) = notes {
+        time.notes = Some(match time.notes.take() {
+            Some(existing) => format!("{existing}\n{notes}"),
+            None => notes,
+        });
+    }
+
+    if let Some(previous) = &same {
+        copy_metadata(time, previous);
+    }
+
+    AutoTagger::new(&config.auto_tags)?.apply(time);
+
+    let name = project_name(list, &active);
+    let time = pretty_duration(&duration, None).bright_red();
+
+    success!("Logged {} for project {}.", time, name);
+
+    if let Some(gap) = merged_gap {
+        let gap = pretty_duration(&whole_seconds(gap), None).bright_red();
+        let total = pretty_duration(&total, None).bright_red();
+        success!("Merged it into the previous entry after a {gap} gap, for {total} in total.");
+    }
+
+    if let Some(rounded) = rounded {
+        let rounded = pretty_duration(&rounded, None).bright_red();
+        success!("Rounded to {rounded} for billing.");
+    }
+
+    if !deducted.is_zero() {
+        let deducted = pretty_duration(&deducted, None).bright_red();
+        success!("Deducted {deducted} for breaks.");
+    }
+
+    if end < now {
+        let ago = pretty_duration(&whole_seconds(now - end), None).bright_red();
+        success!("The timer was stopped automatically {ago} ago.");
+    }
+
+    Ok(())
+}
+
+fn handle_log(list: &mut ProjectList, config: &Config, args: &LogArgs) -> Result<()> {
+    let name = target_project(list, args.project.as_deref())?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+    let start = parse_entry_time(&args.start, now, now)?;
+
+    let duration = match (&args.end, &args.duration) {
+        (Some(end), _) => parse_entry_time(end, start, now)?
+            .checked_sub(start)
+            .filter(|duration| !duration.is_zero())
+            .ok_or(Error::EndBeforeStart)?,
+        (None, Some(duration)) => parse_duration_text(duration)?,
+        (None, None) => unreachable!("clap requires --end or --duration"),
+    };
+
+    let project = list.project(&name)?;
+    let same = args
+        .same
+        .then(|| {
+            project
+                .last_logged_index()
+                .map(|index| project.logged_times[index].clone())
+                .ok_or(Error::NoTimeLogged)
+        })
+        .transpose()?;
+
+    let description = args.description.join(" ");
+    let description = if let Some(previous) = &same {
+        format!("{} {}", previous.description, description.trim())
+            .trim()
+            .to_string()
+    } else if description.trim().is_empty() {
+        editor::edit_line(DESCRIPTION_TEMPLATE)?
+    } else {
+        description.trim().to_string()
+    };
+
+    let description = expand_issue(config, &name, list.project(&name)?, description);
+
+    // Breaks are deducted as when a timer stops. The minimum duration and merge gap are
+    // not applied, since they catch accidental timers and the entry's times were given on
+    // purpose.
+    let deducted = breaks::deduction(&config.break_rules, start, start + duration, &[], duration)?;
+    let duration = duration - deducted;
+
+    let settings = &list.project(&name)?.settings;
+    let round_minutes = settings
+        .round_minutes
+        .or(config.round_minutes)
+        .filter(|minutes| *minutes > 0);
+
+    let mut time = LoggedTime {
+        start_epoch: start,
+        duration,
+        tickets: ticket::find_all(&description),
+        description,
+        notes: None,
+        tags: settings.tags.clone(),
+        billable: None,
+        rounded: round_minutes.map(|minutes| round_to_minutes(duration, minutes)),
+        invoice: None,
+    };
+
+    if let Some(previous) = &same {
+        copy_metadata(&mut time, previous);
+    }
+
+    AutoTagger::new(&config.auto_tags)?.apply(&mut time);
+
+    let index = list.log(&name, time, args.allow_duplicate)?;
+    check_overlaps(list, config, &name, index)?;
+
+    let time = pretty_duration(&duration, None).bright_red();
+    let date = date::format_date(start).bright_blue();
+
+    success!(
+        "Logged {time} on {date} for project {}.",
+        project_name(list, &name)
+    );
+
+    if !deducted.is_zero() {
+        let deducted = pretty_duration(&deducted, None).bright_red();
+        success!("Deducted {deducted} for breaks.");
+    }
+
+    Ok(())
+}
+
+/// Gives an entry the tags and billable setting of the previous entry, for `--same`. Its
+/// tickets come along with its description.
+fn copy_metadata(time: &mut LoggedTime, previous: &LoggedTime) {
+    for tag in &previous.tags {
+        if !time.tags.contains(tag) {
+            time.tags.push(tag.clone());
+        }
+    }
+
+    for ticket in &previous.tickets {
+        if !time.tickets.contains(ticket) {
+            time.tickets.push(ticket.clone());
+        }
+    }
+
+    time.billable = previous.billable;
+}
+
+/// The gap between the last two entries, if they have the same description and the gap is
+/// at most `limit`.
+fn mergeable_gap(logged_times: &[LoggedTime], limit: Duration) -> Option<Duration> {
+    let [.., previous, time] = logged_times else {
+        return None;
+    };
+
+    let gap = time
+        .start_epoch
+        .checked_sub(previous.start_epoch + previous.duration)?;
+
+    (previous.description == time.description && gap <= limit).then_some(gap)
+}
+
+/// Asks whether to discard an entry that was just logged for being too short, merge it into the
+/// previous entry, or keep it. Returns whether it was kept.
+fn resolve_short_entry(list: &mut ProjectList, active: &str, duration: Duration) -> Result<bool> {
+    let time = pretty_duration(&duration, None);
+    let question = format!(
+        "This entry is only {time} long. Discard it, merge it into the previous entry, or keep it?"
+    );
+
+    let answer = ask(&question, "[d/m/K]");
+    let project = list.project_mut(active)?;
+
+    match answer.as_deref() {
+        Some("d" | "discard") => {
+            let mut time = project
+                .logged_times
+                .pop()
+                .expect("an entry was just logged");
+            time.duration = duration;
+
+            move_to_trash(
+                list,
+                Trashed::Entry {
+                    project: active.to_string(),
+                    time,
+                },
+            )?;
+
+            success!("Moved the entry to the trash.");
+            Ok(false)
+        }
+        Some("m" | "merge") if project.logged_times.len() > 1 => {
+            project.logged_times.pop();
+
+            let previous = project
+                .logged_times
+                .last_mut()
+                .expect("there is a previous entry");
+            previous.duration += duration;
+
+            let time = pretty_duration(&duration, None).bright_red();
+            let description = previous.description.bright_blue();
+            success!("Merged {time} into the previous entry: {description}");
+            Ok(false)
+        }
+        _ => Ok(true),
+    }
+}
+
+/// Warns about entries of any project that overlap the entry at `index` of a project, or
+/// refuses the change if the config file says to.
+fn check_overlaps(list: &ProjectList, config: &Config, project: &str, index: usize) -> Result<()> {
+    let overlapping = list.overlapping(project, index);
+
+    if overlapping.is_empty() {
+        return Ok(());
+    }
+
+    if config.refuse_overlaps {
+        return Err(Error::Overlap(overlapping.len()));
+    }
+
+    println!(
+        "{}",
+        format!("Entry #{} overlaps other entries:", index + 1).bright_yellow()
+    );
+
+    for (name, time) in overlapping {
+        println!(
+            "  {}: {} - {}",
+            project_name(list, name),
+            pretty_duration(&time.duration, None).bright_red(),
+            time.description.bright_blue()
+        );
+    }
+
+    Ok(())
+}
+
+/// Parses when an entry started or ended, where a time alone is on the same day as `day` and
+/// relative forms such as `yesterday 15:00` or `2h ago` are relative to `now`.
+fn parse_entry_time(text: &str, day: Duration, now: Duration) -> Result<Duration> {
+    date::parse_date_time(text, day)
+        .or_else(|| when::parse(text, now))
+        .ok_or_else(|| Error::InvalidDateTime(text.to_string()))
+}
+
+/// Parses a duration such as `1h30m` or `1 hour 30`.
+fn parse_duration_text(text: &str) -> Result<Duration> {
+    duration::parse(text).ok_or_else(|| Error::InvalidDuration(text.trim().to_string()))
+}
+
+fn handle_edit(
+    list: &mut ProjectList,
+    config: &Config,
+    id: Option<usize>,
+    start: Option<&str>,
+    keep_end: bool,
+    duration: &str,
+) -> Result<()> {
+    let Some(active) = list.active_project.clone() else {
+        return Err(Error::NoActiveProject);
+    };
+
+    let Some(project) = list.projects.get_mut(&active) else {
+        return Err(Error::UnknownActiveProject);
+    };
+
+    let count = project.logged_times.len();
+
+    let index = match id {
+        Some(id) => id
+            .checked_sub(1)
+            .filter(|index| *index < count)
+            .ok_or(Error::UnknownEntry(id))?,
+        None => project.last_logged_index().ok_or(Error::NoTimeLogged)?,
+    };
+
+    let is_last_logged =
+        project.last_logged.is_some() && project.last_logged_index() == Some(index);
+
+    let time = &mut project.logged_times[index];
+    let (old_start, old_duration) = (time.start_epoch, time.duration);
+
+    let new_start = match start {
+        Some(text) => {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+            parse_entry_time(text, old_start, now)?
+        }
+        None => old_start,
+    };
+
+    let duration = duration.trim();
+
+    let new_duration = if keep_end {
+        time.end_epoch()
+            .checked_sub(new_start)
+            .filter(|duration| !duration.is_zero())
+            .ok_or(Error::EndBeforeStart)?
+    } else if duration.is_empty() {
+        old_duration
+    } else if let Some(added) = duration.strip_prefix('+') {
+        old_duration + parse_duration_text(added)?
+    } else if let Some(removed) = duration.strip_prefix('-') {
+        old_duration
+            .checked_sub(parse_duration_text(removed)?)
+            .ok_or(Error::NegativeDuration)?
+    } else {
+        parse_duration_text(duration)?
+    };
+
+    time.start_epoch = new_start;
+
+    // An edited entry is billed for its new duration, even if it was rounded before.
+    if new_duration != old_duration {
+        time.duration = new_duration;
+        time.rounded = None;
+    }
+
+    // The entry is still the one logged most recently after it is moved.
+    if is_last_logged {
+        project.last_logged = Some(new_start);
+    }
+
+    check_overlaps(list, config, &active, index)?;
+
+    // A moved start may put the entry out of order.
+    list.project_mut(&active)?
+        .logged_times
+        .sort_by_key(|time| time.start_epoch);
+
+    let entry = match id {
+        Some(id) => format!("entry #{id}"),
+        None => "the last entry".to_string(),
+    };
+
+    if new_start != old_start {
+        let old_start = date::format_date_time(old_start).bright_blue();
+        let new_start = date::format_date_time(new_start).bright_blue();
+        success!("Moved the start of {entry} from {old_start} to {new_start}");
+    }
+
+    if start.is_none() || new_duration != old_duration {
+        let old_duration = pretty_duration(&old_duration, None).bright_red();
+        let new_duration = pretty_duration(&new_duration, None).bright_red();
+        success!("Modified {entry} from {old_duration} to {new_duration}");
+    }
+
+    Ok(())
+}
+
+fn handle_amend(list: &mut ProjectList, config: &Config) -> Result<()> {
+    let Some(active) = list.active_project.clone() else {
+        return Err(Error::NoActiveProject);
+    };
+
+    let Some(project) = list.projects.get(&active) else {
+        return Err(Error::UnknownActiveProject);
+    };
+
+    // Extending the entry would count the running timer's time twice.
+    if project.start_epoch.is_some() {
+        return Err(Error::AlreadyStarted);
+    }
+
+    let Some(index) = project.last_logged_index() else {
+        return Err(Error::NoTimeLogged);
+    };
+
+    let last = &project.logged_times[index];
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let added = now.saturating_sub(last.end_epoch());
+    let duration = last.duration + added;
+
+    list.edit_last(&active, duration)?;
+
+    // Only the duration changed, so the entry kept its place.
+    check_overlaps(list, config, &active, index)?;
+
+    let added = pretty_duration(&whole_seconds(added), None).bright_red();
+    let duration = pretty_duration(&whole_seconds(duration), None).bright_red();
+
+    success!("Added {added} to the last entry, which now lasts {duration}.");
+
+    Ok(())
+}
+
+fn handle_append(list: &mut ProjectList, config: &Config, text: &str) -> Result<()> {
+    let text = text.trim();
+
+    if text.is_empty() {
+        return Err(Error::NoDescription);
+    }
+
+    let Some(active) = list.active_project.clone() else {
+        return Err(Error::NoActiveProject);
+    };
+
+    let Some(project) = list.projects.get_mut(&active) else {
+        return Err(Error::UnknownActiveProject);
+    };
+
+    let Some(index) = project.last_logged_index() else {
+        return Err(Error::NoTimeLogged);
+    };
+
+    let last = &mut project.logged_times[index];
+
+    // Text after a sentence or clause that is already punctuated doesn't need a comma.
+    let separator = if last.description.ends_with(['.', ',', ';', ':', '!', '?']) {
+        " "
+    } else {
+        ", "
+    };
+
+    last.description = format!("{}{separator}{text}", last.description);
+    AutoTagger::new(&config.auto_tags)?.apply(last);
+
+    success!("The last entry is now {}.", last.description.bright_blue());
+
+    Ok(())
+}
+
+fn handle_edit_interactive(list: &mut ProjectList, config: &Config, limit: usize) -> Result<bool> {
+    let Some(active) = list.active_project.clone() else {
+        return Err(Error::NoActiveProject);
+    };
+
+    let Some(project) = list.projects.get_mut(&active) else {
+        return Err(Error::UnknownActiveProject);
+    };
+
+    if project.logged_times.is_empty() {
+        return Err(Error::NoTimeLogged);
+    }
+
+    let skip = project.logged_times.len().saturating_sub(limit);
+    let entries: Vec<(usize, &LoggedTime)> =
+        project.logged_times.iter().enumerate().skip(skip).collect();
+
+    let text = editor::edit(&history::format(&active, &entries))?;
+    let edited = history::parse(&text)?;
+
+    // Validate every line before changing anything, so that a mistake saves nothing.
+    let mut changes = Vec::new();
+
+    for entry in edited {
+        let Some(time) = entry
+            .id
+            .checked_sub(1)
+            .and_then(|index| project.logged_times.get(index))
+        else {
+            return Err(Error::UnknownEntry(entry.id));
+        };
+
+        let original = history::parse(&history::line(entry.id, time))?
+            .pop()
+            .expect("an entry's line can be parsed");
+
+        if original != entry {
+            changes.push((entry, original));
+        }
+    }
+
+    if changes.is_empty() {
+        println!("{}", "No entries were changed.".bright_yellow());
+        return Ok(false);
+    }
+
+    // Only fields that were edited are changed, so that the others keep the precision
+    // that the lines leave out.
+    for (change, original) in &changes {
+        let time = &mut project.logged_times[change.id - 1];
+
+        if change.start_epoch != original.start_epoch {
+            time.start_epoch = change.start_epoch;
+        }
+
+        if change.duration != original.duration {
+            time.duration = change.duration;
+            time.rounded = None;
+        }
+
+        if change.description != original.description {
+            time.description.clone_from(&change.description);
+        }
+    }
+
+    for (change, _) in &changes {
+        check_overlaps(list, config, &active, change.id - 1)?;
+    }
+
+    // Changed start times may put entries out of order.
+    list.project_mut(&active)?
+        .logged_times
+        .sort_by_key(|time| time.start_epoch);
+
+    success!(
+        "Updated {} entries for project {}.",
+        changes.len(),
+        project_name(list, &active)
+    );
+
+    Ok(true)
+}
+
+fn handle_undo(list: &mut ProjectList) -> Result<()> {
+    let Some(active) = list.active_project.clone() else {
+        return Err(Error::NoActiveProject);
+    };
+
+    let Some(project) = list.projects.get_mut(&active) else {
+        return Err(Error::UnknownActiveProject);
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+    if let Some((_, duration)) = project.take_timer(now) {
+        let time = pretty_duration(&duration, None).bright_red();
+
+        success!("Cancelled {time} of unlogged time.");
+
+        return Ok(());
+    }
+
+    let Some(index) = project.last_logged_index() else {
+        return Err(Error::NoTimeLogged);
+    };
+
+    let time = project.logged_times.remove(index);
+    project.last_logged = None;
+    project.cached_total = None;
+
+    let description = time.description.bright_blue();
+    let duration = pretty_duration(&time.duration, None).bright_red();
+
+    move_to_trash(
+        list,
+        Trashed::Entry {
+            project: active,
+            time,
+        },
+    )?;
+
+    success!("Removed the last entry with duration {duration}: {description}");
+
+    Ok(())
+}
+
+/// The number of entries shown by `time` unless a limit is given.
+const DEFAULT_TIME_LIMIT: usize = 20;
+
+fn time_limit(args: &TimeArgs, len: usize) -> usize {
+    if args.all {
+        len
+    } else {
+        args.limit.unwrap_or(DEFAULT_TIME_LIMIT)
+    }
+}
+
+fn print_grouped_times<'a>(
+    out: &mut impl Write,
+    logged_times: impl Iterator<Item = (usize, &'a LoggedTime)>,
+    args: &TimeArgs,
+) -> io::Result<()> {
+    // Each group is a description, its total duration, its count, and where it was last used.
+    let mut groups: Vec<(&str, Duration, usize, usize)> = Vec::new();
+
+    for (index, time) in logged_times {
+        match groups.iter_mut().find(|group| group.0 == time.description) {
+            Some(group) => {
+                group.1 += time.duration;
+                group.2 += 1;
+                group.3 = index;
+            }
+            None => groups.push((&time.description, time.duration, 1, index)),
+        }
+    }
+
+    if args.reverse {
+        groups.sort_by_key(|group| group.3);
+    } else {
+        groups.sort_by_key(|group| Reverse(group.3));
+    }
+
+    let limit = time_limit(args, groups.len());
+    let remaining = groups.len().saturating_sub(args.offset + limit);
+
+    let mut table = Table::new(&[
+        ("Duration", Align::Right),
+        ("Count", Align::Right),
+        ("Description", Align::Left),
+    ])
+    .flexible(&["Description"])
+    .max_width(table_width(args.no_truncate));
+
+    for (description, duration, count, _) in groups.into_iter().skip(args.offset).take(limit) {
+        let time = pretty_duration(&duration, None);
+        let count = format!("{count}x");
+
+        table.row(vec![
+            Cell::styled(time.as_str(), time.bright_red()),
+            Cell::styled(count.as_str(), count.bright_yellow()),
+            Cell::styled(description, description.bright_blue()),
+        ]);
+    }
+
+    table.write(out, "  ")?;
+
+    if remaining > 0 {
+        writeln!(
+            out,
+            "{}",
+            format!("  …and {remaining} more").bright_yellow()
+        )?;
+    }
+
+    Ok(())
+}
+
+fn handle_time(
+    list: &ProjectList,
+    args: &TimeArgs,
+    default_columns: Option<&[TimeColumn]>,
+) -> Result<()> {
+    let active = target_project(list, args.project_name.as_deref())?;
+    let project = &list.projects[&active];
+
+    // Plain entries are written as they are formatted, so that piping them elsewhere starts at
+    // once. The table needs every row it shows to lay out its columns.
+    let mut out = BufWriter::new(io::stdout().lock());
+
+    // Entries keep their numbers when others are filtered out, so that they can be edited.
+    let matching = || {
+        project
+            .logged_times
+            .iter()
+            .enumerate()
+            .filter(|(_, time)| args.tags.matches(time))
+            .filter(|(_, time)| {
+                args.tickets.is_empty()
+                    || args.tickets.iter().any(|wanted| {
+                        time.tickets
+                            .iter()
+                            .any(|ticket| ticket.eq_ignore_ascii_case(wanted))
+                    })
+            })
+    };
+
+    if args.plain {
+        for (_, time) in matching() {
+            writeln!(
+                out,
+                "{}\t{}\t{}\t{}",
+                time.start_epoch.as_secs(),
+                time.duration.as_secs(),
+                plain_field(&active),
+                plain_field(&time.description)
+            )?;
+        }
+
+        out.flush()?;
+        return Ok(());
+    }
+
+    let name = project_name(list, &active);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+    // The running timer has no description or tags yet, so filtering by tag or ticket leaves
+    // it out.
+    let running = timer_note(project, now)
+        .filter(|_| args.tags.is_empty() && args.tickets.is_empty())
+        .map(|note| format!("  {note}, not in the total").bright_yellow());
+
+    let count = matching().count();
+
+    let mut tags = if args.tags.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", args.tags)
+    };
+
+    if !args.tickets.is_empty() {
+        tags.push_str(&format!(" mentioning {}", args.tickets.join(" or ")));
+    }
+
+    if count == 0 {
+        println!(
+            "{}",
+            format!("No logged times for project {name}{tags}.").bright_red()
+        );
+
+        if let Some(running) = running {
+            println!("{running}");
+        }
+
+        return Ok(());
+    }
+
+    let total_duration = matching().fold(Duration::default(), |acc, (_, time)| acc + time.duration);
+    let total = pretty_duration(&total_duration, None).bright_red();
+
+    // Entries that are not billable, by themselves or by the project, are left out of the
+    // amount.
+    let billed = matching()
+        .map(|(_, time)| time)
+        .filter(|time| project.settings.is_billable(time))
+        .map(LoggedTime::billed)
+        .sum();
+
+    let billing = billing_note(&project.settings, billed)
+        .map(|note| format!(" ({note})"))
+        .unwrap_or_default();
+
+    let client = project
+        .metadata
+        .client
+        .as_ref()
+        .map(|client| format!(" for {}", client.bright_blue()))
+        .unwrap_or_default();
+
+    println!(
+        "{}",
+        format!("Logged times for {name}{client}{tags}, totaling {total}{billing}:")
+            .bright_yellow()
+    );
+
+    // The running timer is the newest, so it comes first unless the oldest entries do.
+    if let Some(running) = running.as_ref().filter(|_| !args.reverse) {
+        writeln!(out, "{running}")?;
+    }
+
+    if args.group {
+        print_grouped_times(&mut out, matching(), args)?;
+        out.flush()?;
+        return Ok(());
+    }
+
+    let limit = time_limit(args, count);
+    let shown = limit.min(count.saturating_sub(args.offset));
+    let remaining = count.saturating_sub(args.offset + shown);
+
+    let entries = matching();
+    let entries: Box<dyn Iterator<Item = (usize, &LoggedTime)>> = if args.reverse {
+        Box::new(entries)
+    } else {
+        Box::new(entries.rev())
+    };
+
+    let entries: Vec<(usize, &LoggedTime)> = entries.skip(args.offset).take(limit).collect();
+
+    // Columns that were asked for are shown as they are, while the ones shown by default are
+    // left out if they would be empty on every row.
+    let columns: Vec<TimeColumn> = if !args.columns.is_empty() {
+        args.columns.clone()
+    } else if let Some(columns) = default_columns.filter(|_| !args.verbose) {
+        columns.to_vec()
+    } else {
+        let columns: &[TimeColumn] = if args.verbose {
+            &TimeColumn::VERBOSE
+        } else {
+            &TimeColumn::DEFAULT
+        };
+
+        columns
+            .iter()
+            .copied()
+            .filter(|column| entries.iter().any(|(_, time)| column.applies(time)))
+            .collect()
+    };
+
+    let titles: Vec<(&'static str, Align)> = columns
+        .iter()
+        .map(|column| (column.title(), column.align()))
+        .collect();
+
+    let flexible: Vec<&str> = columns
+        .iter()
+        .filter(|column| column.flexible())
+        .map(|column| column.title())
+        .collect();
+
+    let mut table = Table::new(&titles)
+        .flexible(&flexible)
+        .max_width(table_width(args.no_truncate));
+
+    for (index, time) in entries {
+        table.row(
+            columns
+                .iter()
+                .map(|column| column.cell(list, index, time))
+                .collect(),
+        );
+
+        if let Some(notes) = time.notes.as_ref().filter(|_| args.verbose) {
+            for line in notes.lines() {
+                table.note(line);
+            }
+        }
+    }
+
+    table.write(&mut out, "  ")?;
+
+    if remaining > 0 {
+        writeln!(
+            out,
+            "{}",
+            format!("  …and {remaining} more").bright_yellow()
+        )?;
+    }
+
+    if let Some(running) = running.filter(|_| args.reverse) {
+        writeln!(out, "{running}")?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+impl TimeColumn {
+    /// Whether the column has anything to show for an entry.
+    fn applies(self, time: &LoggedTime) -> bool {
+        match self {
+            Self::Billed => time.rounded.is_some(),
+            Self::Tags => !time.tags.is_empty(),
+            Self::Status => time.billable.is_some() || time.invoice.is_some(),
+            _ => true,
+        }
+    }
+
+    fn cell(self, list: &ProjectList, index: usize, time: &LoggedTime) -> Cell {
+        match self {
+            Self::Number => {
+                let number = format!("#{}", index + 1);
+                Cell::styled(number.as_str(), number.bright_yellow())
+            }
+            Self::Start => {
+                let start = date::format_date_time(time.start_epoch);
+                Cell::styled(start.as_str(), start.dimmed())
+            }
+            Self::End => {
+                let end = date::format_date_time(time.end_epoch());
+                Cell::styled(end.as_str(), end.dimmed())
+            }
+            Self::Duration => {
+                let duration = pretty_duration(&time.duration, None);
+                Cell::styled(duration.as_str(), duration.bright_red())
+            }
+            Self::Billed => {
+                let billed = time
+                    .rounded
+                    .map(|rounded| pretty_duration(&rounded, None))
+                    .unwrap_or_default();
+                Cell::styled(billed.as_str(), billed.bright_yellow())
+            }
+            Self::Description => {
+                Cell::styled(time.description.as_str(), time.description.bright_blue())
+            }
+            Self::Tags => {
+                let tags = time.tags.join(", ");
+                Cell::styled(tags.as_str(), tags.bright_magenta())
+            }
+            Self::Status => {
+                let mut status = Vec::new();
+
+                match time.billable {
+                    Some(true) => status.push("billable".to_string()),
+                    Some(false) => status.push("not billable".to_string()),
+                    None => {}
+                }
+
+                if let Some(number) = time.invoice {
+                    let paid = list.invoices.invoices.iter().any(|invoice| {
+                        invoice.number == number && invoice.status == InvoiceStatus::Paid
+                    });
+
+                    status.push(format!("invoice {number:04}"));
+
+                    if paid {
+                        status.push("paid".to_string());
+                    }
+                }
+
+                let status = status.join(", ");
+                Cell::styled(status.as_str(), status.bright_yellow())
+            }
+        }
+    }
+}
+
+/// The width tables are truncated to, which is the terminal's unless `no_truncate` is set.
+fn table_width(no_truncate: bool) -> Option<usize> {
+    if no_truncate {
+        None
+    } else {
+        table::terminal_width()
+    }
+}
+
+/// Describes a project's timer, such as `running since 2024-06-03 09:00, 1h 5m so far`, if
+/// it has one.
+fn timer_note(project: &Project, now: Duration) -> Option<String> {
+    let start = date::format_date_time(project.start_epoch?);
+    let elapsed = pretty_duration(&whole_seconds(project.elapsed(now)?), None);
+
+    Some(if project.paused_epoch.is_some() {
+        format!("paused at {elapsed}, started {start}")
+    } else {
+        format!("running since {start}, {elapsed} so far")
+    })
+}
 
 fn handle_new(list: &mut ProjectList, name: &str) -> Result<()> {
     if list.projects.contains_key(name) {
         return Err(Error::ProjectExists(name.to_string()));
     }
 
-    list.projects.insert(name.to_string(), Project::default());
-    list.active_project = Some(name.to_string());
+    list.projects.insert(name.to_string(), Project::default());
+    list.active_project = Some(name.to_string());
+
+    let name = name.bright_cyan();
+
+    success!("Added project {name}");
+
+    Ok(())
+}
+
+fn handle_delete(list: &mut ProjectList, name: &str, force: bool) -> Result<()> {
+    if let Some(project) = list.projects.get(name) {
+        let count = project.logged_times.len();
+        let total = pretty_duration(&total_time(&project.logged_times), None);
+        let question = format!("Delete project {name} with {count} entries totaling {total}?");
+        confirm_destructive(&question, force)?;
+    }
+
+    if let Some(project) = list.projects.remove(name) {
+        move_to_trash(
+            list,
+            Trashed::Project {
+                name: name.to_string(),
+                project: Box::new(project),
+            },
+        )?;
+
+        let name = name.bright_cyan();
+        success!("Moved project {name} to the trash");
+    } else {
+        return Err(Error::UnknownProject(name.to_string()));
+    }
+
+    if list.active_project == Some(name.to_string()) {
+        list.active_project = None;
+    }
+
+    Ok(())
+}
+
+fn handle_delete_entries(
+    list: &mut ProjectList,
+    project: Option<&str>,
+    from: Option<Duration>,
+    to: Option<Duration>,
+    force: bool,
+) -> Result<bool> {
+    let name = target_project(list, project)?;
+    let project = list.project_mut(&name)?;
+
+    let selected: Vec<&LoggedTime> = project
+        .logged_times
+        .iter()
+        .filter(|time| in_range(time, from, to))
+        .collect();
+
+    if selected.is_empty() {
+        success!("There are no entries to delete.");
+        return Ok(false);
+    }
+
+    let count = selected.len();
+    let total = pretty_duration(&selected.iter().map(|time| time.duration).sum(), None);
+    let question = format!("Delete {count} entries of project {name} totaling {total}?");
+    confirm_destructive(&question, force)?;
+
+    let (removed, kept) = std::mem::take(&mut project.logged_times)
+        .into_iter()
+        .partition(|time| in_range(time, from, to));
+    project.logged_times = kept;
+    project.cached_total = None;
+
+    for time in removed {
+        move_to_trash(
+            list,
+            Trashed::Entry {
+                project: name.clone(),
+                time,
+            },
+        )?;
+    }
+
+    success!(
+        "Moved {count} entries of project {} to the trash.",
+        project_name(list, &name)
+    );
+
+    Ok(true)
+}
+
+fn handle_hat(list: &mut ProjectList, name: &str) -> Result<()> {
+    if list.projects.contains_key(name) {
+        list.active_project = Some(name.to_string());
+        let name = project_name(list, name);
+        success!("Selected project {name}");
+    } else {
+        return Err(Error::UnknownProject(name.to_string()));
+    }
+
+    Ok(())
+}
+
+fn handle_doctor(list: &ProjectList) -> Result<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let issues = doctor::find_issues(list, now);
+
+    if issues.is_empty() {
+        success!("No issues found.");
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("Found {} issue(s):", issues.len()).bright_yellow()
+    );
+
+    for issue in issues.iter() {
+        println!("  {issue}");
+        println!("    {}", issue.suggestion().bright_blue());
+    }
+
+    Ok(())
+}
+
+fn handle_fix(
+    list: &mut ProjectList,
+    storage: &Storage,
+    dedupe: bool,
+    clamp_future: bool,
+    drop_zero: bool,
+    yes: bool,
+) -> Result<bool> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+    let repairs = [
+        (dedupe, Repair::Dedupe),
+        (clamp_future, Repair::ClampFuture),
+        (drop_zero, Repair::DropZero),
+    ];
+
+    let mut fixed = list.clone();
+    let mut applied = 0;
+    let mut skipped = 0;
+
+    for (_, repair) in repairs.into_iter().filter(|(enabled, _)| *enabled) {
+        let mut candidate = fixed.clone();
+        let count = repair.apply(&mut candidate, now);
+
+        if count == 0 {
+            continue;
+        }
+
+        if yes || confirm(&repair.prompt(count)) {
+            fixed = candidate;
+            applied += count;
+        } else {
+            skipped += count;
+        }
+    }
+
+    if applied == 0 && skipped > 0 {
+        success!("Skipped repairing {skipped} issue(s), so nothing was changed.");
+        return Ok(false);
+    }
+
+    if applied == 0 {
+        success!("Nothing to repair.");
+        return Ok(false);
+    }
+
+    let backup = storage.write_backup(list)?;
+
+    *list = fixed;
+
+    let backup = backup.display().to_string().bright_cyan();
+
+    success!("Repaired {applied} issue(s). A backup was written to {backup}.");
+
+    Ok(true)
+}
+
+fn handle_export_all(list: &ProjectList, anonymize: bool, tags: &TagFilter) -> Result<()> {
+    let filtered;
+
+    let list = if tags.is_empty() {
+        list
+    } else {
+        let mut copy = list.clone();
+
+        for project in copy.projects.values_mut() {
+            project.logged_times.retain(|time| tags.matches(time));
+            project.date_index = Default::default();
+            project.cached_total = None;
+        }
+
+        filtered = copy;
+        &filtered
+    };
+
+    let anonymized;
+
+    let list = if anonymize {
+        // A fresh salt for every export keeps hashes from lining up across exports.
+        let salt = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos() as u64;
+        anonymized = anonymize::anonymize(list, salt);
+        &anonymized
+    } else {
+        list
+    };
+
+    // The JSON is written as it is serialized, instead of being built in memory first.
+    let mut out = BufWriter::new(io::stdout().lock());
+    serde_json::to_writer_pretty(&mut out, list).map_err(io::Error::from)?;
+    writeln!(out)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+fn handle_export_everything(
+    list: &ProjectList,
+    storage: &Storage,
+    config: &Config,
+    home: &Path,
+    format: BundleFormat,
+    output: Option<&Path>,
+) -> Result<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let archives = archive::read_all(storage, &config.archive_dir(home))?;
+
+    // Credentials are left out, since the bundle may be handed to someone else.
+    let (settings, redacted) = config.redacted();
+
+    let mut files = vec![
+        (
+            "data.json".to_string(),
+            serde_json::to_value(list).expect("Could not serialize JSON file."),
+        ),
+        (
+            "invoices.json".to_string(),
+            serde_json::to_value(&list.invoices).expect("Could not serialize invoices."),
+        ),
+        ("config.json".to_string(), settings),
+    ];
+
+    for (name, archived) in &archives {
+        let archived = serde_json::to_value(archived).expect("Could not serialize archive.");
+        files.push((format!("archive/{}", archive::plain_name(name)), archived));
+    }
+
+    let entries: usize = list
+        .projects
+        .values()
+        .chain(
+            archives
+                .values()
+                .flat_map(|archived| archived.projects.values()),
+        )
+        .map(|project| project.logged_times.len())
+        .sum();
+
+    let manifest = serde_json::json!({
+        "created": date::format_date_time(now),
+        "version": env!("CARGO_PKG_VERSION"),
+        "files": files.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+        "projects": list.projects.len(),
+        "entries": entries,
+        "invoices": list.invoices.invoices.len(),
+        "redacted": redacted,
+    });
+
+    let bytes = match format {
+        BundleFormat::Json => {
+            let files: serde_json::Map<String, serde_json::Value> = files.into_iter().collect();
+            let bundle = serde_json::json!({ "manifest": manifest, "files": files });
+            let mut text =
+                serde_json::to_string_pretty(&bundle).expect("Could not serialize JSON.");
+            text.push('\n');
+            text.into_bytes()
+        }
+        BundleFormat::Zip => {
+            let mut contents = vec![(
+                "manifest.json".to_string(),
+                serde_json::to_vec_pretty(&manifest).expect("Could not serialize manifest."),
+            )];
+
+            for (name, value) in files {
+                let bytes = serde_json::to_vec_pretty(&value).expect("Could not serialize JSON.");
+                contents.push((name, bytes));
+            }
+
+            zip::write(&contents, now)
+        }
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(path, bytes)?;
+            success!(
+                "Exported everything to {}.",
+                path.display().to_string().bright_cyan()
+            );
+        }
+        None => io::stdout().write_all(&bytes)?,
+    }
+
+    Ok(())
+}
+
+fn handle_export_bookkeeping(
+    list: &ProjectList,
+    layout: Layout,
+    args: &BookkeepingArgs,
+) -> Result<()> {
+    let rows = export_rows(list, args)?;
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    bookkeeping::write_csv(&mut out, layout, &rows)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+fn handle_export_csv(
+    list: &ProjectList,
+    args: &CsvArgs,
+    default_columns: Option<&[CsvColumn]>,
+) -> Result<()> {
+    let rows = export_rows(list, &args.entries)?;
+
+    let columns = args
+        .columns
+        .as_deref()
+        .or(default_columns)
+        .unwrap_or(&CsvColumn::DEFAULT);
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    bookkeeping::write_columns(&mut out, columns, args.delimiter, &rows)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+/// The entries to export, sorted by when they started.
+fn export_rows<'a>(list: &'a ProjectList, args: &BookkeepingArgs) -> Result<Vec<Row<'a>>> {
+    if let Some(name) = &args.project {
+        list.project(name)?;
+    }
+
+    let mut rows: Vec<Row> = Vec::new();
+
+    for (name, project) in &list.projects {
+        if args.project.as_ref().is_some_and(|project| project != name) {
+            continue;
+        }
+
+        let client = project.metadata.client.as_deref().unwrap_or(name);
+
+        for time in &project.logged_times {
+            if !in_range(time, args.from, args.to) || !args.tags.matches(time) {
+                continue;
+            }
+
+            rows.push(Row {
+                start_epoch: time.start_epoch,
+                client,
+                project: name,
+                description: &time.description,
+                tags: &time.tags,
+                tickets: &time.tickets,
+                notes: time.notes.as_deref(),
+                tracked: time.duration,
+                duration: time.billed(),
+                billable: project.settings.is_billable(time),
+                rate: project.settings.rate,
+                invoice: time.invoice,
+            });
+        }
+    }
+
+    rows.sort_by_key(|row| row.start_epoch);
+
+    Ok(rows)
+}
+
+fn handle_import_all(
+    list: &mut ProjectList,
+    storage: &Storage,
+    config: &Config,
+    args: &ImportAllArgs,
+) -> Result<()> {
+    let ImportAllArgs {
+        replace,
+        prefer_import,
+        allow_duplicate,
+        dry_run,
+        ..
+    } = *args;
+
+    let mut imported = storage::read_file(&args.file)?;
+    let tagger = AutoTagger::new(&config.auto_tags)?;
+
+    // Only entries that are new here are tagged, so that existing ones don't conflict.
+    for (name, project) in imported.projects.iter_mut() {
+        let existing = list.projects.get(name).filter(|_| !replace);
+
+        for time in project.logged_times.iter_mut() {
+            let known = existing.is_some_and(|existing| {
+                existing
+                    .logged_times
+                    .iter()
+                    .any(|own| own.start_epoch == time.start_epoch)
+            });
+
+            if !known {
+                tagger.apply(time);
+            }
+        }
+    }
+
+    let backup = if dry_run {
+        None
+    } else {
+        Some(storage.write_backup(list)?)
+    };
+
+    if replace {
+        let count = imported.projects.len();
+        *list = imported;
+
+        success!("Replaced all data with {count} imported project(s).");
+
+        if let Some(backup) = backup {
+            let backup = backup.display().to_string().bright_cyan();
+            success!("A backup was written to {backup}.");
+        }
+
+        return Ok(());
+    }
+
+    // Probable duplicates are set aside, since merging matches them with the existing entries.
+    let mut duplicates: Vec<(String, LoggedTime)> = Vec::new();
+
+    for (name, project) in imported.projects.iter_mut() {
+        let Some(existing) = list.projects.get(name) else {
+            continue;
+        };
+
+        let (found, kept) = std::mem::take(&mut project.logged_times)
+            .into_iter()
+            .partition(|time| existing.is_duplicate(time));
+        project.logged_times = kept;
+
+        duplicates.extend(found.into_iter().map(|time| (name.clone(), time)));
+    }
+
+    let side = if prefer_import {
+        merge::Side::Theirs
+    } else {
+        merge::Side::Ours
+    };
+
+    let mut summary = merge::merge(list, imported, |conflict| {
+        println!("  {conflict}");
+        side
+    });
+
+    if allow_duplicate {
+        for (name, time) in duplicates {
+            list.log(&name, time, true)?;
+            summary.entries_added += 1;
+        }
+    } else if !duplicates.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Skipped {} probable duplicate entries. Pass {} to import them anyway.",
+                duplicates.len(),
+                "--allow-duplicate".bright_cyan()
+            )
+            .bright_yellow()
+        );
+    }
+
+    success!(
+        "Imported {} project(s) and {} entries with {} conflict(s).",
+        summary.projects_added,
+        summary.entries_added,
+        summary.conflicts
+    );
+
+    if let Some(backup) = backup {
+        let backup = backup.display().to_string().bright_cyan();
+        success!("A backup was written to {backup}.");
+    }
+
+    Ok(())
+}
+
+fn handle_sync(config: &Config, dry_run: bool) -> Result<()> {
+    let Some(dir) = &config.data_dir else {
+        return Err(Error::NoDataDir);
+    };
+
+    if dry_run {
+        let (ahead, behind) = git::pending(dir)?;
+        println!(
+            "{}",
+            format!("Would pull {behind} commit(s) and push {ahead} commit(s).").bright_yellow()
+        );
+        return Ok(());
+    }
+
+    git::sync(dir)?;
+
+    success!("Synchronized the data directory.");
+
+    Ok(())
+}
+
+fn handle_merge_file(
+    list: &mut ProjectList,
+    storage: &Storage,
+    file: &Path,
+    ours: bool,
+    theirs: bool,
+) -> Result<()> {
+    let other = storage::read_file(file)?;
+    let backup = storage
+        .write_backup(list)?
+        .display()
+        .to_string()
+        .bright_cyan();
+
+    let summary = merge::merge(list, other, |conflict| {
+        println!("{conflict}");
+
+        if ours {
+            return merge::Side::Ours;
+        } else if theirs {
+            return merge::Side::Theirs;
+        }
+
+        loop {
+            match ask("Keep ours or theirs?", "[o/t]").as_deref() {
+                Some("o" | "ours") | None => return merge::Side::Ours,
+                Some("t" | "theirs") => return merge::Side::Theirs,
+                Some(_) => {}
+            }
+        }
+    });
+
+    success!(
+        "Merged {} new project(s) and {} new entries, resolving {} conflict(s).",
+        summary.projects_added,
+        summary.entries_added,
+        summary.conflicts
+    );
+    success!("A backup was written to {backup}.");
+
+    Ok(())
+}
+
+/// Encrypts the data file. It is saved here rather than after the command, so that success is
+/// only reported once it is written encrypted.
+fn handle_encrypt(list: &ProjectList, storage: &mut Storage, config: &Config) -> Result<()> {
+    if storage.encrypted() {
+        return Err(Error::AlreadyEncrypted);
+    }
+
+    storage.set_encrypted(true)?;
+    storage.save(list)?;
+    commit_list(config, &storage.paths())?;
+
+    success!("The data file is now encrypted.");
+
+    Ok(())
+}
+
+fn handle_decrypt(list: &ProjectList, storage: &mut Storage, config: &Config) -> Result<()> {
+    if !storage.encrypted() {
+        return Err(Error::NotEncrypted);
+    }
+
+    storage.set_encrypted(false)?;
+    storage.save(list)?;
+    commit_list(config, &storage.paths())?;
+
+    success!("The data file is no longer encrypted.");
+
+    Ok(())
+}
+
+fn handle_stats(list: &ProjectList) -> Result<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+    let times = || {
+        list.projects
+            .values()
+            .flat_map(|project| &project.logged_times)
+    };
+
+    let total: Duration = list.projects.values().map(Project::total_logged).sum();
+    let oldest = times().map(|time| time.start_epoch).min();
+    let newest = times().map(|time| time.start_epoch).max();
+
+    println!("{}", "Statistics:".bright_yellow());
+    println!("  Projects: {}", list.projects.len());
+    println!("  Entries: {}", times().count());
+    println!(
+        "  Time logged: {}",
+        pretty_duration(&whole_seconds(total), None).bright_red()
+    );
+
+    if let (Some(oldest), Some(newest)) = (oldest, newest) {
+        println!("  Oldest entry: {}", date::format_date(oldest));
+        println!("  Newest entry: {}", date::format_date(newest));
+    }
+
+    let streaks = streak::streaks(list, date::day_number(now));
+
+    if streaks.longest > 0 {
+        println!(
+            "  Streak: {} weekday{} in a row, {} at the longest",
+            streaks.current.to_string().bright_green(),
+            if streaks.current == 1 { "" } else { "s" },
+            streaks.longest
+        );
+    }
+
+    Ok(())
+}
+
+/// The widest and tallest the chart of `burndown` gets.
+const BURNDOWN_WIDTH: usize = 60;
+const BURNDOWN_HEIGHT: usize = 12;
+
+fn handle_burndown(list: &ProjectList, name: Option<&str>) -> Result<()> {
+    let name = target_project(list, name)?;
+    let project = &list.projects[&name];
+
+    let estimate = project
+        .settings
+        .estimate
+        .ok_or_else(|| Error::NoEstimate(name.clone()))?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let today = date::day_number(now);
+    let format = |duration: Duration| pretty_duration(&whole_seconds(duration), None);
+
+    println!(
+        "{}",
+        format!("Burndown for project {}:", project_name(list, &name)).bright_yellow()
+    );
+
+    let first_day = project
+        .logged_times
+        .iter()
+        .map(|time| date::day_number(time.start_epoch))
+        .chain(project.start_epoch.map(date::day_number))
+        .min();
+
+    let Some(first_day) = first_day.filter(|&day| day <= today) else {
+        println!(
+            "  No time was logged against the estimate of {}.",
+            format(estimate)
+        );
+        return Ok(());
+    };
+
+    // The total at the end of each day, with the running timer counted today.
+    let mut cumulative = vec![Duration::ZERO; (today - first_day + 1) as usize];
+
+    for time in &project.logged_times {
+        let day = date::day_number(time.start_epoch).clamp(first_day, today);
+        cumulative[(day - first_day) as usize] += time.duration;
+    }
+
+    cumulative[(today - first_day) as usize] += project.elapsed(now).unwrap_or_default();
+
+    let mut running = Duration::ZERO;
+
+    for day in &mut cumulative {
+        running += *day;
+        *day = running;
+    }
+
+    let total = cumulative.last().copied().unwrap_or_default();
+
+    let width = table::terminal_width()
+        .map_or(BURNDOWN_WIDTH, |width| width.saturating_sub(12))
+        .clamp(10, BURNDOWN_WIDTH);
+
+    for line in burndown::chart(&cumulative, estimate, width, BURNDOWN_HEIGHT) {
+        println!("  {line}");
+    }
+
+    println!(
+        "  {} to {}",
+        date::format_date(date::start_of_day(first_day)),
+        date::format_date(now)
+    );
+    println!();
+
+    println!(
+        "  Logged {} of {} ({:.0}%).",
+        format(total).bright_red(),
+        format(estimate),
+        total.as_secs_f64() / estimate.as_secs_f64() * 100.0
+    );
+
+    if total >= estimate {
+        println!(
+            "  {}",
+            format!("Over the estimate by {}.", format(total - estimate)).bright_red()
+        );
+        return Ok(());
+    }
+
+    // The pace is the average over every day since the first entry, weekends included.
+    let pace = total / cumulative.len() as u32;
+
+    if pace.is_zero() {
+        return Ok(());
+    }
+
+    let remaining = estimate - total;
+    let days_left = (remaining.as_secs_f64() / pace.as_secs_f64()).ceil() as i64;
+
+    println!(
+        "  At {} a day, the remaining {} will be done around {}.",
+        format(pace),
+        format(remaining),
+        date::format_date(date::start_of_day(today + days_left)).bright_green()
+    );
+
+    Ok(())
+}
+
+fn handle_open(list: &ProjectList, name: Option<&str>) -> Result<()> {
+    let name = target_project(list, name)?;
+
+    let Some(url) = list.projects[&name].metadata.url.as_deref() else {
+        return Err(Error::NoUrl(name));
+    };
+
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+
+    log::debug!("Opening {url} in the browser.");
+
+    let status = command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => {
+            log::debug!("The browser exited with {status}.");
+            return Err(Error::BrowserFailed(url.to_string()));
+        }
+        Err(err) => {
+            log::debug!("Could not start the browser: {err}");
+            return Err(Error::BrowserFailed(url.to_string()));
+        }
+    }
+
+    success!(
+        "Opened {} for project {}.",
+        url.bright_blue(),
+        name.bright_cyan()
+    );
+
+    Ok(())
+}
+
+/// The widest a bar of `stats --distribution` or `chart` gets.
+const MAX_BAR_WIDTH: usize = 40;
+
+/// The short names of the weekdays, starting from Monday.
+const WEEKDAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+fn handle_stats_distribution(list: &ProjectList) -> Result<()> {
+    let times = || {
+        list.projects
+            .values()
+            .flat_map(|project| &project.logged_times)
+    };
+
+    // The bars take up whatever the labels and times leave of the terminal.
+    let bar_width = table::terminal_width()
+        .map_or(MAX_BAR_WIDTH, |width| width.saturating_sub(24))
+        .clamp(10, MAX_BAR_WIDTH);
+
+    let weekdays = histogram::by_weekday(times());
+    let hours = histogram::by_hour(times());
+
+    let rows: Vec<(String, Duration)> = WEEKDAY_NAMES
+        .iter()
+        .map(|name| name.to_string())
+        .zip(weekdays)
+        .collect();
+
+    println!("{}", "By weekday:".bright_yellow());
+    print_chart("Day", &rows, bar_width)?;
+
+    let rows: Vec<(String, Duration)> = (0..24)
+        .map(|hour| format!("{hour:02}:00"))
+        .zip(hours)
+        .collect();
+
+    println!();
+    println!("{}", "By hour of the day (UTC):".bright_yellow());
+    print_chart("Hour", &rows, bar_width)?;
+
+    Ok(())
+}
+
+/// The colors given to the segments of projects without a color of their own, in turn.
+const CHART_COLORS: [Color; 6] = [
+    Color::BrightCyan,
+    Color::BrightMagenta,
+    Color::BrightBlue,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightRed,
+];
+
+fn handle_chart(list: &ProjectList, days: usize) -> Result<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let today = date::day_number(now);
+    let first = today + 1 - days as i64;
+
+    // Running timers count towards the days they cover, as if they stopped now.
+    let mut projects: Vec<(&str, Color, Vec<Duration>)> = Vec::new();
+
+    for (name, project) in &list.projects {
+        let running = project
+            .start_epoch
+            .zip(project.elapsed(now))
+            .map(|(start, elapsed)| LoggedTime {
+                start_epoch: start,
+                duration: elapsed,
+                description: String::new(),
+                notes: None,
+                tags: Vec::new(),
+                billable: None,
+                rounded: None,
+                invoice: None,
+                tickets: Vec::new(),
+            });
+
+        let totals = histogram::by_day(project.logged_times.iter().chain(&running), first, days);
+
+        if totals.iter().all(Duration::is_zero) {
+            continue;
+        }
+
+        let color = project
+            .metadata
+            .color
+            .as_deref()
+            .and_then(parse_color)
+            .unwrap_or(CHART_COLORS[projects.len() % CHART_COLORS.len()]);
+
+        projects.push((name, color, totals));
+    }
+
+    if projects.is_empty() {
+        println!(
+            "{}",
+            format!("No time was logged in the last {days} days.").bright_red()
+        );
+        return Ok(());
+    }
+
+    let day_totals: Vec<Duration> = (0..days)
+        .map(|day| projects.iter().map(|(_, _, totals)| totals[day]).sum())
+        .collect();
+    let max = day_totals.iter().copied().max().unwrap_or_default();
+
+    // The bars take up whatever the labels and times leave of the terminal.
+    let bar_width = table::terminal_width()
+        .map_or(MAX_BAR_WIDTH, |width| width.saturating_sub(32))
+        .clamp(10, MAX_BAR_WIDTH);
+
+    let mut table = Table::new(&[
+        ("Day", Align::Left),
+        ("Time", Align::Right),
+        ("", Align::Left),
+    ]);
+
+    for (day, total) in day_totals.iter().enumerate() {
+        let start = date::start_of_day(first + day as i64);
+        // The epoch was a Thursday, three days after a Monday.
+        let weekday = WEEKDAY_NAMES[(first + day as i64 + 3).rem_euclid(7) as usize];
+        let label = format!("{weekday} {}", date::format_date(start));
+
+        let time = match total.is_zero() {
+            true => String::new(),
+            false => pretty_duration(&whole_seconds(*total), None),
+        };
+
+        let values: Vec<Duration> = projects.iter().map(|(_, _, totals)| totals[day]).collect();
+        let widths = histogram::stacked(&values, max, bar_width);
+
+        let segments: Vec<ColoredString> = widths
+            .iter()
+            .zip(&projects)
+            .map(|(&width, (_, color, _))| "█".repeat(width).color(*color))
+            .collect();
+
+        table.row(vec![
+            Cell::plain(label),
+            Cell::styled(time.clone(), time.bright_red()),
+            Cell::parts("█".repeat(widths.iter().sum()), &segments),
+        ]);
+    }
+
+    println!(
+        "{}",
+        format!("Hours logged on each of the last {days} days:").bright_yellow()
+    );
+    table.write(&mut io::stdout().lock(), "  ")?;
+
+    let legend: Vec<String> = projects
+        .iter()
+        .map(|(name, color, _)| format!("{} {name}", "█".color(*color)))
+        .collect();
+
+    println!();
+    println!("  {}", legend.join("  "));
+
+    Ok(())
+}
+
+/// Prints a bar for each row, as long relative to `bar_width` as its duration is to the
+/// longest.
+fn print_chart(label: &'static str, rows: &[(String, Duration)], bar_width: usize) -> Result<()> {
+    let max = rows
+        .iter()
+        .map(|(_, duration)| *duration)
+        .max()
+        .unwrap_or_default();
+
+    let mut table = Table::new(&[
+        (label, Align::Left),
+        ("Time", Align::Right),
+        ("", Align::Left),
+    ]);
+
+    for (name, duration) in rows {
+        let time = match duration.is_zero() {
+            true => String::new(),
+            false => pretty_duration(&whole_seconds(*duration), None),
+        };
+        let bar = histogram::bar(*duration, max, bar_width);
+
+        table.row(vec![
+            Cell::plain(name.clone()),
+            Cell::styled(time.clone(), time.bright_red()),
+            Cell::styled(bar.clone(), bar.bright_green()),
+        ]);
+    }
+
+    table.write(&mut io::stdout().lock(), "  ")?;
+
+    Ok(())
+}
+
+/// Entries older than this many years before the current one are suggested for archiving.
+const ARCHIVE_SUGGESTION_YEARS: i64 = 2;
+
+/// Data files larger than this are suggested to be stored as MessagePack.
+const LARGE_DATA_FILE: u64 = 10 * 1024 * 1024;
+
+fn handle_stats_storage(
+    list: &ProjectList,
+    storage: &Storage,
+    config: &Config,
+    home: &Path,
+) -> Result<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let year_of = |epoch| date::civil_from_days(date::day_number(epoch)).0;
+
+    println!("{}", "Storage:".bright_yellow());
+
+    let mut data_size = 0;
+
+    if let Some(server) = &config.server {
+        data_size = storage.encode(list)?.len() as u64;
+        println!(
+            "  {} - {}",
+            server.url.bright_cyan(),
+            format_size(data_size)
+        );
+    }
+
+    for path in storage
+        .paths()
+        .into_iter()
+        .filter(|_| config.server.is_none())
+    {
+        let size = disk_size(path)?;
+        data_size += size;
+        println!(
+            "  {} - {}",
+            path.display().to_string().bright_cyan(),
+            format_size(size)
+        );
+    }
+
+    let mut notes = vec![storage.format().to_string()];
+
+    if storage.encrypted() {
+        notes.push("encrypted".to_string());
+    }
+
+    println!("  Format: {}", notes.join(", "));
+
+    let dir = config.archive_dir(home);
+    let archives = archive::read_all(storage, &dir)?;
+    let mut archive_size = 0;
+    let mut compressed = true;
+
+    if !archives.is_empty() {
+        println!("{}", "Archives:".bright_yellow());
+    }
+
+    for (name, archived) in &archives {
+        let size = disk_size(&dir.join(name))?;
+        let count: usize = archived
+            .projects
+            .values()
+            .map(|project| project.logged_times.len())
+            .sum();
+
+        archive_size += size;
+        compressed &= name != archive::plain_name(name);
+
+        println!("  {name} - {} ({count} entries)", format_size(size));
+    }
+
+    let mut projects: Vec<(&String, &Project)> = list.projects.iter().collect();
+    projects.sort_by_key(|(name, project)| (Reverse(project.logged_times.len()), *name));
+
+    println!("{}", "Entries:".bright_yellow());
+
+    for (name, project) in &projects {
+        let count = project.logged_times.len();
+        let oldest = project
+            .logged_times
+            .iter()
+            .map(|time| time.start_epoch)
+            .min();
+
+        match oldest {
+            Some(oldest) => println!(
+                "  {} - {count} entries since {}",
+                project_name(list, name),
+                date::format_date(oldest)
+            ),
+            None => println!("  {} - no entries", project_name(list, name)),
+        }
+    }
+
+    let times = || {
+        list.projects
+            .values()
+            .flat_map(|project| &project.logged_times)
+    };
+
+    if let Some(oldest) = times().map(|time| time.start_epoch).min() {
+        println!("  Oldest entry: {}", date::format_date(oldest));
+    }
+
+    println!("  Trash: {} items", list.trash.len());
+
+    let mut suggestions = Vec::new();
+
+    // Whole years long past are rarely needed, and only slow every command down.
+    let cutoff = year_of(now) - ARCHIVE_SUGGESTION_YEARS;
+    let old: Vec<i64> = times()
+        .map(|time| year_of(time.start_epoch))
+        .filter(|year| *year < cutoff)
+        .collect();
+
+    if let (Some(first), Some(last)) = (old.iter().min(), old.iter().max()) {
+        let years = if first == last {
+            first.to_string()
+        } else {
+            format!("{first}–{last}")
+        };
+
+        suggestions.push(format!(
+            "Consider archiving {years} ({} entries) with {}.",
+            old.len(),
+            format!("hat archive-entries --before {}-01-01", last + 1).bright_cyan()
+        ));
+    }
+
+    if data_size > LARGE_DATA_FILE && storage.format() == Format::Json {
+        suggestions.push(format!(
+            "Consider storing the data file as MessagePack with {}.",
+            "hat convert-format msgpack".bright_cyan()
+        ));
+    }
+
+    if archive_size > LARGE_DATA_FILE && !compressed {
+        suggestions.push(format!(
+            "Consider setting {} in the config file.",
+            "compress_archives".bright_cyan()
+        ));
+    }
+
+    if !suggestions.is_empty() {
+        println!("{}", "Suggestions:".bright_yellow());
+
+        for suggestion in suggestions {
+            println!("  {suggestion}");
+        }
+    }
+
+    Ok(())
+}
+
+/// The size of a file, or of every file in a directory, which is zero if it does not exist.
+fn disk_size(path: &Path) -> Result<u64> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err.into()),
+    };
+
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+
+    let mut size = 0;
+
+    for entry in fs::read_dir(path)? {
+        size += disk_size(&entry?.path())?;
+    }
+
+    Ok(size)
+}
+
+/// Formats a number of bytes with the largest unit that keeps it at one or more.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} bytes");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+fn handle_self_update(check: bool) -> Result<()> {
+    let release = update::latest_release(None)?;
+    let current = update::CURRENT_VERSION;
+
+    if !update::is_newer(&release.version) {
+        success!("hat {current} is the latest version.");
+        return Ok(());
+    }
+
+    let version = release.version.bright_cyan();
+
+    if check {
+        println!(
+            "hat {version} is available, and you have {current}. Run {} to install it.",
+            "hat self-update".bright_cyan()
+        );
+        return Ok(());
+    }
+
+    let path = update::install(&release)?;
+    let path = path.display().to_string().bright_cyan();
+
+    success!("Updated hat from {current} to {version} at {path}.");
+
+    Ok(())
+}
+
+fn handle_reindex(list: &mut ProjectList) -> Result<bool> {
+    let rebuilt = list.reindex();
+
+    if rebuilt == 0 {
+        success!("The date index and totals are up to date.");
+        return Ok(false);
+    }
+
+    success!("Rebuilt the date index and total of {rebuilt} projects.");
+
+    Ok(true)
+}
+
+fn handle_normalize(list: &mut ProjectList) -> Result<()> {
+    let changed = list.normalize();
+
+    if changed == 0 {
+        success!("Every entry and timer was already in whole seconds.");
+    } else {
+        success!("Truncated {changed} entries and timers to whole seconds.");
+    }
+
+    Ok(())
+}
+
+fn handle_convert_format(
+    storage: &mut Storage,
+    config: &Config,
+    home: &Path,
+    format: Format,
+) -> Result<()> {
+    if storage.format() == format {
+        return Err(Error::AlreadyFormat(format));
+    }
+
+    storage.set_format(format);
+
+    // Archive files are written right away, since they aren't part of the data file.
+    let dir = config.archive_dir(home);
+    let archives = archive::read_all(storage, &dir)?;
+
+    for (name, archive) in &archives {
+        archive::replace(storage, &dir, name, archive)?;
+    }
+
+    success!("The data file is now stored as {format}.");
+
+    if !archives.is_empty() {
+        success!(
+            "Converted {} archive files, keeping the old ones with a {} suffix.",
+            archives.len(),
+            ".bak".bright_cyan()
+        );
+    }
+
+    Ok(())
+}
+
+/// Runs the scheduled jobs as their times come, until the process is stopped. A job missed while
+/// the computer was asleep runs once when it wakes. Goals are checked every minute, so that
+/// they are mentioned as soon as a running timer reaches them.
+fn handle_daemon(config: &Config, home: &Path, storage: &mut Storage) -> Result<()> {
+    let jobs = &config.schedule;
+
+    if jobs.is_empty() && config.goals.is_none() {
+        return Err(Error::NoScheduledJobs);
+    }
+
+    let schedules: Vec<Schedule> = jobs
+        .iter()
+        .map(|job| Schedule::parse(&job.when))
+        .collect::<Result<_>>()?;
+
+    let exe = env::current_exe()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let mut next: Vec<Duration> = schedules
+        .iter()
+        .map(|schedule| schedule.next_after(now))
+        .collect();
+
+    match next.iter().min() {
+        Some(&first) => success!(
+            "Running {} scheduled job{}. The next one runs at {}.",
+            jobs.len(),
+            if jobs.len() == 1 { "" } else { "s" },
+            date::format_date_time(first)
+        ),
+        None => success!("Checking the goals every minute."),
+    }
+
+    loop {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+        for (index, job) in jobs.iter().enumerate() {
+            if next[index] > now {
+                continue;
+            }
+
+            // A failed job is reported, and runs again at its next time.
+            if let Err(err) = schedule::run_job(&exe, job) {
+                eprintln!("{}", err.to_string().bright_red());
+            }
+
+            next[index] = schedules[index].next_after(now);
+        }
+
+        // The data file is read again to see the entries logged since the last check.
+        if config.goals.is_some() {
+            match storage.load() {
+                Ok(list) => notify_goals(config, home, &list),
+                Err(err) => eprintln!("{}", err.to_string().bright_red()),
+            }
+        }
+
+        // Sleeping a minute at most notices the clock jumping ahead after a suspend.
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        let wait = next
+            .iter()
+            .min()
+            .map_or(Duration::MAX, |earliest| earliest.saturating_sub(now));
+
+        thread::sleep(wait.min(Duration::from_secs(60)));
+    }
+}
+
+fn handle_sync_gitlab(
+    list: &ProjectList,
+    storage: &Storage,
+    config: &Config,
+    dry_run: bool,
+) -> Result<()> {
+    let Some(gitlab) = &config.gitlab else {
+        return Err(Error::NoGitLab);
+    };
+
+    let state_path = storage.sibling(".gitlab");
+    let mut state = SyncState::load(&state_path);
+    let spends = gitlab.pending(list, &state);
+
+    if spends.is_empty() {
+        success!("Every entry that mentions a GitLab issue or merge request is synced.");
+        return Ok(());
+    }
+
+    let verb = if dry_run { "Would add" } else { "Adding" };
+
+    for spend in &spends {
+        let minutes = Duration::from_secs(spend.minutes.unsigned_abs() * 60);
+        let minutes = pretty_duration(&minutes, None);
+        let date = date::format_date(Duration::from_secs(spend.start_epoch));
+
+        let change = if spend.minutes < 0 {
+            format!("-{minutes}")
+        } else {
+            minutes
+        };
+
+        println!(
+            "{verb} {} to {}{} for the entry of {} on {date}.",
+            change.bright_red(),
+            spend.path.bright_blue(),
+            spend.reference.bright_blue(),
+            spend.project.bright_cyan()
+        );
+
+        if dry_run {
+            continue;
+        }
+
+        // The state is saved as each post succeeds, so that a failure part way through never
+        // leads to time being posted twice.
+        let posted = gitlab.add_spent_time(spend);
+
+        if posted.is_ok() {
+            state.record(spend.key(), spend.minutes);
+        }
+
+        state.save(&state_path)?;
+        posted?;
+    }
+
+    if !dry_run {
+        success!("Synced {} changes with GitLab.", spends.len());
+    }
+
+    Ok(())
+}
+
+fn handle_sync_linear(
+    list: &ProjectList,
+    storage: &Storage,
+    config: &Config,
+    dry_run: bool,
+) -> Result<()> {
+    if config.linear.is_empty() {
+        return Err(Error::NoLinear(String::from("each project")));
+    }
+
+    let state_path = storage.sibling(".linear");
+    let mut state = SyncState::load(&state_path);
+    let verb = if dry_run {
+        "Would comment"
+    } else {
+        "Commenting"
+    };
+    let mut synced = 0;
+
+    for (name, linear) in &config.linear {
+        let Some(project) = list.projects.get(name) else {
+            continue;
+        };
+
+        for time in &project.logged_times {
+            let minutes = (time.duration.as_secs() + 30) as i64 / 60;
+            let date = date::format_date(time.start_epoch);
+
+            for identifier in time.tickets.iter().filter(|ticket| linear.is_issue(ticket)) {
+                let key = tracker::key(name, time.start_epoch.as_secs(), identifier);
+                let posted = state.posted(&key);
+
+                if posted == minutes {
+                    continue;
+                }
+
+                let logged = pretty_duration(&Duration::from_secs(minutes as u64 * 60), None);
+
+                // Comments can't be taken back, so an entry edited since it was synced gets a
+                // correction instead.
+                let body = if posted == 0 {
+                    format!("Logged {logged} on {date}: {}", time.description)
+                } else {
+                    format!("Corrected the time logged on {date} to {logged}.")
+                };
+
+                println!(
+                    "{verb} on {}: {}",
+                    identifier.bright_blue(),
+                    body.bright_yellow()
+                );
+
+                if dry_run {
+                    continue;
+                }
+
+                let commented = linear.comment(identifier, &body);
+
+                if commented.is_ok() {
+                    state.record(key, minutes - posted);
+                    synced += 1;
+                }
+
+                state.save(&state_path)?;
+                commented?;
+            }
+        }
+    }
+
+    if synced == 0 && !dry_run {
+        success!("Every entry that mentions a Linear issue is synced.");
+    } else if !dry_run {
+        success!(
+            "Added {synced} comment{} on Linear issues.",
+            if synced == 1 { "" } else { "s" }
+        );
+    }
+
+    Ok(())
+}
+
+fn handle_sync_redmine(
+    list: &ProjectList,
+    storage: &Storage,
+    config: &Config,
+    dry_run: bool,
+) -> Result<()> {
+    let Some(redmine) = &config.redmine else {
+        return Err(Error::NoRedmine);
+    };
+
+    let state_path = storage.sibling(".redmine");
+    let mut state = SyncState::load(&state_path);
+    let verb = if dry_run { "Would log" } else { "Logging" };
+    let mut synced = 0;
+
+    for (name, mapping) in &redmine.projects {
+        let Some(project) = list.projects.get(name) else {
+            continue;
+        };
+
+        for time in &project.logged_times {
+            let issues: Vec<(&String, u64)> = time
+                .tickets
+                .iter()
+                .filter_map(|ticket| Some((ticket, ticket.strip_prefix('#')?.parse().ok()?)))
+                .collect();
+
+            if issues.is_empty() {
+                continue;
+            }
+
+            // The time of an entry that mentions several issues is split evenly between them.
+            let minutes = (time.duration.as_secs() / issues.len() as u64 + 30) as i64 / 60;
+            let spent_on = date::format_date(time.start_epoch);
+
+            for (ticket, issue_id) in issues {
+                let key = tracker::key(name, time.start_epoch.as_secs(), ticket);
+                let posted = state.posted(&key);
+                let logged = pretty_duration(&Duration::from_secs(minutes as u64 * 60), None);
+
+                if posted == minutes {
+                    continue;
+                }
+
+                // Time entries can't be negative, so edits are left to be made in Redmine.
+                if posted != 0 {
+                    println!(
+                        "{}",
+                        format!(
+                            "The entry of {name} on {spent_on} was edited since it was synced, \
+                             so change its time on {ticket} to {logged} in Redmine."
+                        )
+                        .bright_yellow()
+                    );
+                    continue;
+                }
+
+                println!(
+                    "{verb} {} on {} in {} for the entry of {} on {spent_on}.",
+                    logged.bright_red(),
+                    ticket.bright_blue(),
+                    mapping.project.bright_blue(),
+                    name.bright_cyan()
+                );
+
+                if dry_run {
+                    continue;
+                }
+
+                let created = redmine.create_time_entry(&redmine::TimeEntry {
+                    project: mapping,
+                    issue_id,
+                    spent_on: spent_on.clone(),
+                    minutes,
+                    comments: &time.description,
+                });
+
+                if created.is_ok() {
+                    state.record(key, minutes);
+                    synced += 1;
+                }
+
+                state.save(&state_path)?;
+                created?;
+            }
+        }
+    }
+
+    if synced == 0 && !dry_run {
+        success!("Every entry that mentions a Redmine issue is synced.");
+    } else if !dry_run {
+        success!(
+            "Created {synced} time entr{} in Redmine.",
+            if synced == 1 { "y" } else { "ies" }
+        );
+    }
+
+    Ok(())
+}
+
+fn handle_sync_remote(
+    list: &mut ProjectList,
+    storage: &Storage,
+    config: &Config,
+    dry_run: bool,
+) -> Result<()> {
+    let Some(remote) = &config.remote else {
+        return Err(Error::NoRemote);
+    };
+
+    let state_path = storage.sibling(".sync");
+    let synced: Option<Duration> = fs::read_to_string(&state_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok());
+
+    let Some(bytes) = remote.download()? else {
+        if dry_run {
+            println!(
+                "{}",
+                "Would upload the data file to the remote.".bright_yellow()
+            );
+            return Ok(());
+        }
+
+        remote.upload(&storage.encode(list)?)?;
+        fs::write(
+            &state_path,
+            serde_json::to_string(&list.modified_epoch).expect("Could not serialize JSON file."),
+        )?;
+        success!("Uploaded the data file to the remote.");
+        return Ok(());
+    };
+
+    let remote_list = storage.decode(&bytes)?;
+    let ours = list.modified_epoch;
+    let theirs = remote_list.modified_epoch;
+
+    if ours == theirs {
+        success!("Already up to date.");
+        return Ok(());
+    }
+
+    if dry_run {
+        let message = if theirs == synced {
+            "Would upload local changes to the remote."
+        } else if ours == synced {
+            diff::print_changes(list, &remote_list);
+            "Would download remote changes."
+        } else {
+            "Both sides changed, so the older one would be saved as a conflict copy."
+        };
+
+        println!("{}", message.bright_yellow());
+        return Ok(());
+    }
+
+    let message = if theirs == synced {
+        remote.upload(&storage.encode(list)?)?;
+        "Uploaded local changes to the remote.".to_string()
+    } else if ours == synced {
+        *list = remote_list;
+        storage.save(list)?;
+        "Downloaded remote changes.".to_string()
+    } else {
+        // Both sides changed since the last sync, so the last writer wins and the
+        // other side is kept as a conflict copy next to the data file.
+        let suffix = format!(
+            ".conflict-{}",
+            ours.max(theirs).unwrap_or_default().as_secs()
+        );
+
+        let copy = if theirs > ours {
+            let copy = storage.write_copy(list, &suffix)?;
+            *list = remote_list;
+            storage.save(list)?;
+            copy
+        } else {
+            let copy = storage.write_copy(&remote_list, &suffix)?;
+            remote.upload(&storage.encode(list)?)?;
+            copy
+        };
+
+        let copy = copy.display().to_string().bright_cyan();
+        format!("Both sides changed, so the older one was saved to {copy}.")
+    };
+
+    fs::write(
+        &state_path,
+        serde_json::to_string(&list.modified_epoch).expect("Could not serialize JSON file."),
+    )?;
+
+    success!("{message}");
+
+    Ok(())
+}
+
+fn handle_team_report(
+    config: &Config,
+    dir: Option<&Path>,
+    from: Option<Duration>,
+    to: Option<Duration>,
+) -> Result<()> {
+    let mut team: Vec<(String, ProjectList)> = if let Some(dir) = dir {
+        let mut team = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                team.push((name, storage::read_file(&path)?));
+            }
+        }
+
+        team
+    } else if let Some(server) = &config.server {
+        server.fetch_team()?.into_iter().collect()
+    } else {
+        return Err(Error::NoTeamData);
+    };
+
+    team.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut project_totals: HashMap<String, HashMap<String, Duration>> = HashMap::new();
+
+    println!("{}", "Time per person:".bright_yellow());
+
+    for (person, list) in team.iter() {
+        let mut totals: Vec<(&String, Duration)> = list
+            .projects
+            .iter()
+            .map(|(name, project)| {
+                let total = project
+                    .logged_times
+                    .iter()
+                    .filter(|time| in_range(time, from, to))
+                    .fold(Duration::default(), |acc, time| acc + time.duration);
+                (name, total)
+            })
+            .filter(|(_, total)| !total.is_zero())
+            .collect();
+        totals.sort();
+
+        let total = totals.iter().map(|(_, total)| *total).sum();
+
+        println!(
+            "  {} - {}",
+            person.bright_cyan(),
+            pretty_duration(&total, None).bright_red()
+        );
+
+        for (name, time) in totals {
+            println!(
+                "    {} - {}",
+                name.bright_green(),
+                pretty_duration(&time, None).bright_red()
+            );
+
+            *project_totals
+                .entry(name.clone())
+                .or_default()
+                .entry(person.clone())
+                .or_default() += time;
+        }
+    }
+
+    let mut project_totals: Vec<(String, HashMap<String, Duration>)> =
+        project_totals.into_iter().collect();
+    project_totals.sort_by(|a, b| a.0.cmp(&b.0));
+
+    println!("{}", "Time per project:".bright_yellow());
+
+    for (name, people) in project_totals {
+        let total: Duration = people.values().sum();
+
+        println!(
+            "  {} - {}",
+            name.bright_green(),
+            pretty_duration(&total, None).bright_red()
+        );
+
+        let mut people: Vec<(String, Duration)> = people.into_iter().collect();
+        people.sort();
+
+        for (person, time) in people {
+            println!(
+                "    {} - {}",
+                person.bright_cyan(),
+                pretty_duration(&time, None).bright_red()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_note(list: &mut ProjectList, id: Option<usize>) -> Result<bool> {
+    let Some(active) = list.active_project.clone() else {
+        return Err(Error::NoActiveProject);
+    };
+
+    let Some(project) = list.projects.get_mut(&active) else {
+        return Err(Error::UnknownActiveProject);
+    };
+
+    let index = match id {
+        Some(id) => id.checked_sub(1).ok_or(Error::UnknownEntry(id))?,
+        None => project.last_logged_index().ok_or(Error::NoTimeLogged)?,
+    };
+
+    let Some(time) = project.logged_times.get_mut(index) else {
+        return Err(Error::UnknownEntry(index + 1));
+    };
+
+    let notes = editor::edit(time.notes.as_deref().unwrap_or_default())?;
+    let notes = notes.trim();
+    let notes = (!notes.is_empty()).then(|| notes.to_string());
+
+    if notes == time.notes {
+        println!("{}", "The notes were not changed.".bright_yellow());
+        return Ok(false);
+    }
+
+    time.notes = notes;
+
+    let description = time.description.bright_blue();
+
+    success!("Updated the notes for entry #{}: {description}", index + 1);
+
+    Ok(true)
+}
+
+fn handle_suggestions(list: &ProjectList) -> Result<()> {
+    let Some(active) = list.active_project.clone() else {
+        return Err(Error::NoActiveProject);
+    };
+
+    let Some(project) = list.projects.get(&active) else {
+        return Err(Error::UnknownActiveProject);
+    };
+
+    for description in suggest::descriptions(&project.logged_times, SUGGESTION_LIMIT) {
+        println!("{description}");
+    }
+
+    Ok(())
+}
+
+fn handle_trash_list(list: &ProjectList) -> Result<()> {
+    if list.trash.is_empty() {
+        println!("{}", "The trash is empty.".bright_red());
+        return Ok(());
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+    println!("{}", "Trash:".bright_yellow());
+
+    for (index, item) in list.trash.iter().enumerate() {
+        let id = format!("#{}", index + 1).bright_yellow();
+        let ago = now.saturating_sub(item.deleted_epoch);
+        let ago = pretty_duration(&whole_seconds(ago), None).bright_red();
+
+        println!("  {id} {} (deleted {ago} ago)", item.contents);
+    }
+
+    Ok(())
+}
+
+fn handle_restore(list: &mut ProjectList, id: usize) -> Result<()> {
+    let index = id
+        .checked_sub(1)
+        .filter(|&index| index < list.trash.len())
+        .ok_or(Error::UnknownTrashItem(id))?;
+
+    match &list.trash[index].contents {
+        Trashed::Project { name, .. } if list.projects.contains_key(name) => {
+            return Err(Error::ProjectExists(name.clone()));
+        }
+        Trashed::Entry { project, .. } if !list.projects.contains_key(project) => {
+            return Err(Error::UnknownProject(project.clone()));
+        }
+        _ => {}
+    }
+
+    let item = list.trash.remove(index);
+
+    success!("Restored {}.", item.contents);
+
+    match item.contents {
+        Trashed::Project { name, project } => {
+            list.projects.insert(name, *project);
+        }
+        Trashed::Entry { project, time } => {
+            let project = list
+                .projects
+                .get_mut(&project)
+                .expect("project existence was checked above");
+            project.logged_times.push(time);
+            project.logged_times.sort_by_key(|time| time.start_epoch);
+            project.cached_total = None;
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_prune(
+    list: &mut ProjectList,
+    storage: &Storage,
+    before: Duration,
+    project: Option<&str>,
+    summarize: bool,
+    force: bool,
+    dry_run: bool,
+) -> Result<bool> {
+    if let Some(name) = project {
+        if !list.projects.contains_key(name) {
+            return Err(Error::UnknownProject(name.to_string()));
+        }
+    }
+
+    let selected = |name: &str| project.is_none_or(|project| project == name);
+
+    let old: Vec<&LoggedTime> = list
+        .projects
+        .iter()
+        .filter(|(name, _)| selected(name))
+        .flat_map(|(_, project)| project.logged_times.iter())
+        .filter(|time| time.start_epoch < before)
+        .collect();
+
+    if old.is_empty() {
+        success!("There are no entries to prune.");
+        return Ok(false);
+    }
+
+    let count = old.len();
+    let total = pretty_duration(&old.iter().map(|time| time.duration).sum(), None);
+    let date = date::format_date(before);
+
+    let question = if summarize {
+        format!("Summarize {count} entries totaling {total} before {date} by month?")
+    } else {
+        format!("Permanently remove {count} entries totaling {total} before {date}?")
+    };
+
+    confirm_destructive(&question, force)?;
+
+    let backup = if dry_run {
+        None
+    } else {
+        Some(storage.write_backup(list)?)
+    };
+
+    let mut removed = 0;
+
+    for (name, project) in list.projects.iter_mut() {
+        if selected(name) {
+            removed += prune::prune(&mut project.logged_times, before, summarize);
+            project.cached_total = None;
+        }
+    }
+
+    success!("Pruned {removed} entries.");
+
+    if let Some(backup) = backup {
+        let backup = backup.display().to_string().bright_cyan();
+        success!("A backup was written to {backup}.");
+    }
+
+    Ok(true)
+}
+
+fn handle_archive_entries(
+    list: &mut ProjectList,
+    storage: &Storage,
+    dir: &Path,
+    before: Duration,
+    compressed: bool,
+) -> Result<bool> {
+    let archived = archive::archive(list, storage, dir, before, compressed)?;
+
+    if archived == 0 {
+        success!("There are no entries to archive.");
+        return Ok(false);
+    }
+
+    let dir = dir.display().to_string().bright_cyan();
+
+    success!("Archived {archived} entries to {dir}.");
+
+    Ok(true)
+}
+
+/// Replaces every occurrence of `pattern` in some text, ignoring ASCII case. Returns `None` if
+/// there was none.
+fn redact_text(text: &str, pattern: &str, replacement: &str) -> Option<String> {
+    let mut redacted = String::new();
+    let mut rest = text;
+    let mut found = false;
+
+    while !rest.is_empty() {
+        let matched = rest.len() >= pattern.len()
+            && rest.is_char_boundary(pattern.len())
+            && rest[..pattern.len()].eq_ignore_ascii_case(pattern);
+
+        if matched {
+            redacted.push_str(replacement);
+            rest = &rest[pattern.len()..];
+            found = true;
+        } else {
+            let next = rest.chars().next().map_or(1, char::len_utf8);
+            redacted.push_str(&rest[..next]);
+            rest = &rest[next..];
+        }
+    }
+
+    found.then_some(redacted)
+}
+
+/// Redacts the description and notes of an entry. Returns whether anything changed.
+fn redact_entry(time: &mut LoggedTime, args: &RedactArgs) -> bool {
+    let description = redact_text(&time.description, &args.pattern, &args.replacement);
+    let notes = time
+        .notes
+        .as_deref()
+        .and_then(|notes| redact_text(notes, &args.pattern, &args.replacement));
+
+    let changed = description.is_some() || notes.is_some();
+
+    if let Some(description) = description {
+        time.description = description;
+    }
+
+    if notes.is_some() {
+        time.notes = notes;
+    }
+
+    changed
+}
+
+/// Redacts the entries of the projects selected by `args`. Returns the number changed.
+fn redact_projects<'a>(
+    projects: impl Iterator<Item = (&'a String, &'a mut Project)>,
+    args: &RedactArgs,
+) -> usize {
+    let mut count = 0;
+
+    for (name, project) in projects {
+        if args.project.as_ref().is_some_and(|project| project != name) {
+            continue;
+        }
+
+        for time in &mut project.logged_times {
+            if redact_entry(time, args) {
+                count += 1;
+            }
+        }
+    }
+
+    count
+}
+
+fn handle_redact(
+    list: &mut ProjectList,
+    storage: &Storage,
+    config: &Config,
+    home: &Path,
+    args: &RedactArgs,
+) -> Result<bool> {
+    if let Some(name) = &args.project {
+        list.project(name)?;
+    }
+
+    let backup = if args.dry_run {
+        None
+    } else {
+        Some(storage.write_backup(list)?)
+    };
+
+    let mut count = redact_projects(list.projects.iter_mut(), args);
+
+    for item in &mut list.trash {
+        match &mut item.contents {
+            Trashed::Project { name, project } => {
+                count += redact_projects(std::iter::once((&*name, &mut **project)), args);
+            }
+            Trashed::Entry { project, time } => {
+                if args.project.as_ref().is_none_or(|name| name == project)
+                    && redact_entry(time, args)
+                {
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    // Archive files are written right away, since they aren't part of the data file.
+    let dir = config.archive_dir(home);
+    let mut archived = 0;
+
+    for (name, mut archive) in archive::read_all(storage, &dir)? {
+        let changed = redact_projects(archive.projects.iter_mut(), args);
+
+        if changed > 0 && !args.dry_run {
+            archive::replace(storage, &dir, &name, &archive)?;
+        }
+
+        archived += changed;
+    }
+
+    if count + archived == 0 {
+        success!("No entries matched.");
+        return Ok(false);
+    }
+
+    if archived > 0 {
+        let verb = if args.dry_run {
+            "Would redact"
+        } else {
+            "Redacted"
+        };
+        success!("{verb} {archived} archived entries.");
+    }
+
+    if let Some(backup) = backup.filter(|_| count > 0) {
+        let backup = backup.display().to_string().bright_cyan();
+        success!("Redacted {count} entries. A backup was written to {backup}.");
+        println!(
+            "{}",
+            "The backups still contain the original text, so delete them once you have checked the result."
+                .bright_yellow()
+        );
+    }
+
+    Ok(count > 0)
+}
+
+fn handle_bulk_edit(list: &mut ProjectList, args: &BulkEditArgs) -> Result<bool> {
+    if let Some(name) = &args.project {
+        list.project(name)?;
+    }
+
+    if let Some(description) = &args.set_description {
+        if description.trim().is_empty() {
+            return Err(Error::NoDescription);
+        }
+    }
+
+    let pattern = args.pattern.as_ref().map(|pattern| pattern.to_lowercase());
+    let mut count = 0;
+
+    for (name, project) in list.projects.iter_mut() {
+        if args.project.as_ref().is_some_and(|project| project != name) {
+            continue;
+        }
+
+        for time in project.logged_times.iter_mut() {
+            let matches = pattern
+                .as_ref()
+                .is_none_or(|pattern| time.description.to_lowercase().contains(pattern));
+
+            if !matches || !in_range(time, args.from, args.to) {
+                continue;
+            }
+
+            let before = time.clone();
+
+            for tag in &args.set_tag {
+                if !time.tags.contains(tag) {
+                    time.tags.push(tag.clone());
+                }
+            }
+
+            time.tags.retain(|tag| !args.remove_tag.contains(tag));
+
+            if args.set_billable.is_some() {
+                time.billable = args.set_billable;
+            }
+
+            if let Some(description) = &args.set_description {
+                time.description = description.trim().to_string();
+            }
+
+            if *time != before {
+                count += 1;
+            }
+        }
+    }
+
+    if count == 0 {
+        success!("No entries needed to change.");
+        return Ok(false);
+    }
+
+    success!("Updated {count} entries.");
+
+    Ok(true)
+}
+
+fn handle_tag_list(list: &ProjectList) -> Result<()> {
+    // Each tag with its number of entries and their total duration.
+    let mut tags: HashMap<&str, (usize, Duration)> = HashMap::new();
+
+    for time in list
+        .projects
+        .values()
+        .flat_map(|project| &project.logged_times)
+    {
+        for tag in &time.tags {
+            let (count, duration) = tags.entry(tag).or_default();
+            *count += 1;
+            *duration += time.duration;
+        }
+    }
+
+    if tags.is_empty() {
+        println!("{}", "No entries are tagged.".bright_red());
+        return Ok(());
+    }
+
+    let mut tags: Vec<(&str, (usize, Duration))> = tags.into_iter().collect();
+    tags.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(a.0.cmp(b.0)));
+
+    println!("{}", "Tags:".bright_yellow());
+
+    for (tag, (count, duration)) in tags {
+        let duration = pretty_duration(&whole_seconds(duration), None).bright_red();
+        println!("  {} - {count} entries ({duration})", tag.bright_cyan());
+    }
+
+    Ok(())
+}
+
+/// Replaces each of `tags` with `into` on every entry and in the default tags of every project.
+fn handle_tag_merge(list: &mut ProjectList, tags: &[String], into: &str) -> Result<()> {
+    let into = into.trim();
+
+    for tag in tags {
+        let used = list.projects.values().any(|project| {
+            project.settings.tags.contains(tag)
+                || project
+                    .logged_times
+                    .iter()
+                    .any(|time| time.tags.contains(tag))
+        });
+
+        if !used {
+            return Err(Error::UnknownTag(tag.clone()));
+        }
+    }
+
+    let replace = |own: &mut Vec<String>| -> bool {
+        if !own.iter().any(|tag| tags.contains(tag)) {
+            return false;
+        }
+
+        // The new tag takes the place of the first old one, and duplicates are dropped.
+        let mut replaced: Vec<String> = Vec::with_capacity(own.len());
+
+        for tag in own.drain(..) {
+            let tag = if tags.contains(&tag) {
+                into.to_string()
+            } else {
+                tag
+            };
+
+            if !replaced.contains(&tag) {
+                replaced.push(tag);
+            }
+        }
+
+        *own = replaced;
+        true
+    };
+
+    let mut count = 0;
+
+    for project in list.projects.values_mut() {
+        replace(&mut project.settings.tags);
+
+        for time in project.logged_times.iter_mut() {
+            if replace(&mut time.tags) {
+                count += 1;
+            }
+        }
+    }
+
+    let names: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.bright_cyan().to_string())
+        .collect();
+
+    success!(
+        "Replaced {} with {} on {count} entries.",
+        names.join(", "),
+        into.bright_cyan()
+    );
+
+    Ok(())
+}
+
+fn handle_status(list: &ProjectList) -> Result<()> {
+    let mut running: Vec<(&String, &Project)> = list
+        .projects
+        .iter()
+        .filter(|(_, project)| project.start_epoch.is_some())
+        .collect();
+
+    if running.is_empty() {
+        println!("{}", "No timers are running.".bright_red());
+        return Ok(());
+    }
+
+    running.sort_by_key(|(_, project)| project.start_epoch);
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+    println!("{}", "Running timers:".bright_yellow());
+
+    for (name, project) in running {
+        let elapsed = project.elapsed(now).unwrap_or_default();
+        let elapsed = pretty_duration(&whole_seconds(elapsed), None);
+
+        if project.paused_epoch.is_some() {
+            let paused = "(paused)".bright_yellow();
+            println!(
+                "  {} - {} {paused}",
+                project_name(list, name),
+                elapsed.bright_red()
+            );
+        } else {
+            println!("  {} - {}", project_name(list, name), elapsed.bright_red());
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_switch(
+    list: &mut ProjectList,
+    config: &Config,
+    name: &str,
+    description: Vec<String>,
+) -> Result<()> {
+    if !list.projects.contains_key(name) {
+        return Err(Error::UnknownProject(name.to_string()));
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+    if let Some(active) = list.active_project.clone() {
+        let running = list
+            .projects
+            .get(&active)
+            .is_some_and(|project| project.start_epoch.is_some());
+
+        if active == name && running {
+            return Err(Error::AlreadyStarted);
+        }
+
+        if running {
+            let args = OffArgs {
+                project: Some(active),
+                template: None,
+                pick: false,
+                same: false,
+                pick_issue: false,
+                stdin: false,
+                round: None,
+                dry_run: false,
+                description,
+            };
+
+            stop_timer(list, config, &args, now)?;
+        }
+    }
+
+    let project = list.projects.get_mut(name).expect("project exists");
+
+    if project.start_epoch.is_some() {
+        return Err(Error::AlreadyStarted);
+    }
+
+    project.start_epoch = Some(now);
+    list.active_project = Some(name.to_string());
+
+    let name = project_name(list, name);
+
+    success!("Now tracking time for project {name}.");
+
+    Ok(())
+}
+
+fn handle_pause(list: &mut ProjectList, project: Option<&str>) -> Result<()> {
+    let name = target_project(list, project)?;
+    let project = list.projects.get_mut(&name).expect("target project exists");
+
+    if project.start_epoch.is_none() {
+        return Err(Error::NotStarted);
+    }
+
+    if project.paused_epoch.is_some() {
+        return Err(Error::AlreadyPaused);
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    project.paused_epoch = Some(now);
+
+    let elapsed = project.elapsed(now).unwrap_or_default();
+    let elapsed = pretty_duration(&whole_seconds(elapsed), None).bright_red();
+
+    success!("Paused project {} at {elapsed}.", project_name(list, &name));
+
+    Ok(())
+}
+
+fn handle_resume(list: &mut ProjectList, project: Option<&str>) -> Result<()> {
+    let name = target_project(list, project)?;
+    let project = list.projects.get_mut(&name).expect("target project exists");
+
+    let Some(paused) = project.paused_epoch.take() else {
+        return Err(Error::NotPaused);
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let pause = now.saturating_sub(paused);
+    project.paused_duration += pause;
+    project.pauses.push((paused, now));
+
+    log::trace!(
+        "Paused from {paused:?} to {now:?}, for {:?} in total.",
+        project.paused_duration
+    );
+
+    let pause = pretty_duration(&whole_seconds(pause), None).bright_red();
+
+    success!(
+        "Resumed project {} after a {pause} pause.",
+        project_name(list, &name)
+    );
+
+    Ok(())
+}
+
+fn handle_report(
+    list: &ProjectList,
+    config: &Config,
+    project: Option<&str>,
+    period: &PeriodArgs,
+    uninvoiced: bool,
+    tags: &TagFilter,
+    template: Option<&Path>,
+) -> Result<()> {
+    if let Some(name) = project {
+        list.project(name)?;
+    }
+
+    let template = template
+        .or(config.report_template.as_deref())
+        .map(Template::load)
+        .transpose()?;
+
+    let period = period.resolve(config.week_start)?;
+    let mut title = match period.name {
+        Some(_) => format!("Report for {period}"),
+        None => format!("Report from {period}"),
+    };
+
+    if uninvoiced {
+        title.push_str(", not invoiced yet");
+    }
+
+    if !tags.is_empty() {
+        title.push_str(&format!(", {tags}"));
+    }
+
+    let mut names: Vec<&String> = list
+        .projects
+        .keys()
+        .filter(|name| project.is_none_or(|project| project == name.as_str()))
+        .collect();
+    names.sort();
+
+    // Each project with time in the report, with its entries and the time tracked and billed.
+    let mut lines: Vec<(&String, Vec<&LoggedTime>, Duration, Duration)> = Vec::new();
+
+    for name in names {
+        let project = &list.projects[name];
+        let times = report_entries(project, &period, uninvoiced, tags);
+
+        if times.is_empty() {
+            continue;
+        }
+
+        let duration: Duration = times.iter().map(|time| time.duration).sum();
+        let billed: Duration = times
+            .iter()
+            .filter(|time| project.settings.is_billable(time))
+            .map(|time| time.billed())
+            .sum();
+
+        lines.push((name, times, duration, billed));
+    }
+
+    let total: Duration = lines.iter().map(|(_, _, duration, _)| *duration).sum();
+    let amounts: Vec<(Option<&str>, f64)> = lines
+        .iter()
+        .filter_map(|(name, _, _, billed)| {
+            let settings = &list.projects[*name].settings;
+            settings
+                .amount(*billed)
+                .map(|amount| (settings.currency.as_deref(), amount))
+        })
+        .collect();
+
+    // Convert the amounts if the config file asks for it, and otherwise total each currency.
+    let billed = match (amounts.is_empty(), &config.base_currency) {
+        (true, _) => None,
+        (false, Some(_)) => Some(match convert_to_base(config, &amounts) {
+            Ok((base, amount)) => money::format(amount, Some(base)),
+            Err(err) => {
+                eprintln!("{}", err.to_string().bright_red());
+                format_by_currency(&amounts)
+            }
+        }),
+        (false, None) => Some(format_by_currency(&amounts)),
+    };
+
+    if let Some(template) = template {
+        let projects: Vec<serde_json::Value> = lines
+            .iter()
+            .map(|(name, times, duration, billed)| {
+                let project = &list.projects[*name];
+                let settings = &project.settings;
+                let currency = settings.currency.as_deref();
+
+                serde_json::json!({
+                    "name": name,
+                    "client": project.metadata.client,
+                    "duration": pretty_duration(&whole_seconds(*duration), None),
+                    "hours": format_hours(*duration),
+                    "billed": pretty_duration(&whole_seconds(*billed), None),
+                    "billed_hours": format_hours(*billed),
+                    "rate": settings.rate.map(|rate| money::format(rate, currency)),
+                    "amount": settings.amount(*billed).map(|amount| money::format(amount, currency)),
+                    "currency": currency,
+                    "entries": times
+                        .iter()
+                        .map(|time| entry_context(time, settings))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+
+        let context = serde_json::json!({
+            "title": title,
+            "period": period.to_string(),
+            "from": date::format_date(period.from),
+            "to": date::format_date(period.to),
+            "projects": projects,
+            "total": pretty_duration(&whole_seconds(total), None),
+            "total_hours": format_hours(total),
+            "billed": billed,
+        });
+
+        print!("{}", template.render(&context));
+        return Ok(());
+    }
+
+    println!("{}", format!("{title}:").bright_yellow());
 
-    let name = name.bright_cyan();
+    for (name, _, duration, billed) in &lines {
+        let billing = billing_note(&list.projects[*name].settings, *billed)
+            .map(|note| format!(" ({note})"))
+            .unwrap_or_default();
+
+        println!(
+            "  {} - {}{billing}",
+            project_name(list, name),
+            pretty_duration(&whole_seconds(*duration), None).bright_red()
+        );
+    }
+
+    if total.is_zero() {
+        println!("{}", "  No time was logged.".bright_red());
+        return Ok(());
+    }
 
-    println!("{}", format!("Added project {name}").bright_green());
+    println!(
+        "  {} {}",
+        "Total:".bright_yellow(),
+        pretty_duration(&whole_seconds(total), None).bright_red()
+    );
+
+    if let Some(billed) = billed {
+        println!("  {} {}", "Billed:".bright_yellow(), billed.bright_green());
+    }
 
     Ok(())
 }
 
-fn handle_delete(list: &mut ProjectList, name: &str) -> Result<()> {
-    if list.projects.remove(name).is_some() {
-        let name = name.bright_cyan();
-        println!("{}", format!("Removed project {name}").bright_green());
+/// The entries of a project that a report over `period` includes.
+fn report_entries<'a>(
+    project: &'a Project,
+    period: &Period,
+    uninvoiced: bool,
+    tags: &TagFilter,
+) -> Vec<&'a LoggedTime> {
+    entries_in_range(project, Some(period.from), Some(period.to))
+        .into_iter()
+        .filter(|time| !uninvoiced || time.invoice.is_none())
+        .filter(|time| tags.matches(time))
+        .collect()
+}
+
+/// Shows the time of each project in two periods side by side, with how much it changed from
+/// the second period to the first.
+fn handle_report_compare(
+    list: &ProjectList,
+    config: &Config,
+    project: Option<&str>,
+    periods: (NamedPeriod, NamedPeriod),
+    uninvoiced: bool,
+    tags: &TagFilter,
+) -> Result<()> {
+    if let Some(name) = project {
+        list.project(name)?;
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let current = periods.0.resolve(config.week_start, now);
+    let previous = periods.1.resolve(config.week_start, now);
+
+    let mut title = format!("Comparing {current} with {previous}");
+
+    if uninvoiced {
+        title.push_str(", not invoiced yet");
+    }
+
+    if !tags.is_empty() {
+        title.push_str(&format!(", {tags}"));
+    }
+
+    println!("{}", format!("{title}:").bright_yellow());
+
+    let name_of = |period: &Period| {
+        period.name.clone().unwrap_or_else(|| {
+            format!(
+                "{} to {}",
+                date::format_date(period.from),
+                date::format_date(period.to)
+            )
+        })
+    };
+    let (current_name, previous_name) = (name_of(&current), name_of(&previous));
+
+    let mut names: Vec<&String> = list
+        .projects
+        .keys()
+        .filter(|name| project.is_none_or(|project| project == name.as_str()))
+        .collect();
+    names.sort();
+
+    let total = |period: &Period, project: &Project| -> Duration {
+        report_entries(project, period, uninvoiced, tags)
+            .iter()
+            .map(|time| time.duration)
+            .sum()
+    };
+
+    let rows: Vec<(&String, Duration, Duration)> = names
+        .into_iter()
+        .map(|name| {
+            let project = &list.projects[name];
+            (name, total(&current, project), total(&previous, project))
+        })
+        .filter(|(_, current, previous)| !current.is_zero() || !previous.is_zero())
+        .collect();
+
+    if rows.is_empty() {
+        println!("{}", "  No time was logged in either period.".bright_red());
+        return Ok(());
+    }
+
+    let mut table = Table::new(&[
+        ("Project", Align::Left),
+        (&current_name, Align::Right),
+        (&previous_name, Align::Right),
+        ("Change", Align::Right),
+        ("%", Align::Right),
+    ])
+    .flexible(&["Project"])
+    .max_width(table::terminal_width());
+
+    let format = |duration: Duration| pretty_duration(&whole_seconds(duration), None);
+
+    let mut cells = |name: &str, styled: ColoredString, current: Duration, previous: Duration| {
+        let (sign, change) = if current >= previous {
+            ("+", current - previous)
+        } else {
+            ("-", previous - current)
+        };
+
+        let change_text = match change.is_zero() {
+            true => "0s".to_string(),
+            false => format!("{sign}{}", format(change)),
+        };
+        let percent = match previous.is_zero() {
+            true => "new".to_string(),
+            false => format!(
+                "{:+.0}%",
+                (current.as_secs_f64() - previous.as_secs_f64()) / previous.as_secs_f64() * 100.0
+            ),
+        };
+        let color = |text: &str| match sign {
+            _ if change.is_zero() => text.normal(),
+            "+" => text.bright_green(),
+            _ => text.bright_red(),
+        };
+
+        table.row(vec![
+            Cell::styled(name, styled),
+            Cell::plain(format(current)),
+            Cell::plain(format(previous)),
+            Cell::styled(change_text.clone(), color(&change_text)),
+            Cell::styled(percent.clone(), color(&percent)),
+        ]);
+    };
+
+    for (name, current, previous) in &rows {
+        cells(name, project_name(list, name), *current, *previous);
+    }
+
+    let current_total = rows.iter().map(|(_, current, _)| *current).sum();
+    let previous_total = rows.iter().map(|(_, _, previous)| *previous).sum();
+    cells(
+        "Total",
+        "Total".bright_yellow(),
+        current_total,
+        previous_total,
+    );
+
+    table.write(&mut io::stdout().lock(), "  ")?;
+
+    Ok(())
+}
+
+/// An entry as templates see it.
+fn entry_context(time: &LoggedTime, settings: &ProjectSettings) -> serde_json::Value {
+    serde_json::json!({
+        "date": date::format_date(time.start_epoch),
+        "start": date::format_date_time(time.start_epoch),
+        "end": date::format_date_time(time.end_epoch()),
+        "duration": pretty_duration(&whole_seconds(time.duration), None),
+        "hours": format_hours(time.duration),
+        "billed_hours": format_hours(time.billed()),
+        "description": time.description,
+        "tags": time.tags,
+        "tickets": time.tickets,
+        "notes": time.notes,
+        "billable": settings.is_billable(time),
+        "invoice": time.invoice.map(|number| format!("{number:04}")),
+    })
+}
+
+/// A duration in decimal hours, such as `1.50`.
+fn format_hours(duration: Duration) -> String {
+    format!("{:.2}", duration.as_secs_f64() / 3600.0)
+}
+
+/// Adds up amounts in various currencies into the base currency in the config file.
+fn convert_to_base<'a>(
+    config: &'a Config,
+    amounts: &[(Option<&str>, f64)],
+) -> Result<(&'a str, f64)> {
+    let base = config
+        .base_currency
+        .as_deref()
+        .ok_or(Error::NoBaseCurrency)?;
+    let mut total = 0.0;
+
+    for (currency, amount) in amounts {
+        total += money::convert(*amount, *currency, base, &config.exchange_rates)
+            .ok_or_else(|| Error::NoExchangeRate(currency.unwrap_or_default().to_string()))?;
+    }
+
+    Ok((base, total))
+}
+
+/// Adds up the amounts in each currency, such as `€120.00 + $80.00`.
+fn format_by_currency(amounts: &[(Option<&str>, f64)]) -> String {
+    let mut totals: Vec<(Option<&str>, f64)> = Vec::new();
+
+    for (currency, amount) in amounts {
+        match totals.iter_mut().find(|(known, _)| known == currency) {
+            Some((_, total)) => *total += amount,
+            None => totals.push((*currency, *amount)),
+        }
+    }
+
+    totals
+        .iter()
+        .map(|(currency, amount)| money::format(*amount, *currency))
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn handle_invoice(
+    list: &mut ProjectList,
+    config: &Config,
+    client: Option<String>,
+    project: Option<String>,
+    period: &PeriodArgs,
+    template: Option<&Path>,
+) -> Result<()> {
+    let template = template
+        .or(config.invoice_template.as_deref())
+        .map(Template::load)
+        .transpose()?;
+
+    let (client, mut names): (String, Vec<String>) = match (client, project) {
+        (Some(client), _) => {
+            let names: Vec<String> = list
+                .projects
+                .iter()
+                .filter(|(_, project)| project.metadata.client.as_ref() == Some(&client))
+                .map(|(name, _)| name.clone())
+                .collect();
+
+            if names.is_empty() {
+                return Err(Error::UnknownClient(client));
+            }
+
+            (client, names)
+        }
+        (None, Some(name)) => {
+            let project = list.project(&name)?;
+            let client = project.metadata.client.clone().unwrap_or(name.clone());
+            (client, vec![name])
+        }
+        (None, None) => unreachable!("clap requires a client or a project"),
+    };
+    names.sort();
+
+    let period = period.resolve(config.week_start)?;
+
+    // Each project billed, with its billable time, rate, and amount.
+    let mut lines: Vec<(&str, Duration, f64, f64)> = Vec::new();
+
+    for name in &names {
+        let project = &list.projects[name];
+        let billed: Duration = project
+            .logged_times
+            .iter()
+            .filter(|time| is_invoiceable(&project.settings, time, &period))
+            .map(|time| time.billed())
+            .sum();
+
+        if billed.is_zero() {
+            continue;
+        }
+
+        let (Some(rate), Some(amount)) = (project.settings.rate, project.settings.amount(billed))
+        else {
+            return Err(Error::NoRate(name.clone()));
+        };
+
+        // Each line is rounded to the cent, so that the lines add up to the total shown.
+        let amount = money::round(amount, project.settings.currency.as_deref());
+        lines.push((name, billed, rate, amount));
+    }
+
+    if lines.is_empty() {
+        return Err(Error::NothingToInvoice);
+    }
+
+    let currency = |name: &str| list.projects[name].settings.currency.as_deref();
+    let amounts: Vec<(Option<&str>, f64)> = lines
+        .iter()
+        .map(|(name, _, _, amount)| (currency(name), *amount))
+        .collect();
+
+    // An invoice is in the currency of its projects, or in the base currency if they differ.
+    let (total_currency, net) = if amounts.iter().all(|(other, _)| *other == amounts[0].0) {
+        (amounts[0].0, amounts.iter().map(|(_, amount)| amount).sum())
     } else {
-        return Err(Error::UnknownProject(name.to_string()));
+        let (base, total) = convert_to_base(config, &amounts)?;
+        (Some(base), total)
+    };
+
+    let net = money::round(net, total_currency);
+    let tax_rate = config.tax_rates.get(&client).copied();
+    let tax = tax_rate.map_or(0.0, |rate| money::round(net * rate / 100.0, total_currency));
+    let gross = money::round(net + tax, total_currency);
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let number = list.invoices.next_number();
+
+    if let Some(template) = template {
+        let lines: Vec<serde_json::Value> = lines
+            .iter()
+            .map(|(name, billed, rate, amount)| {
+                let project = &list.projects[*name];
+
+                serde_json::json!({
+                    "project": name,
+                    "billed": pretty_duration(&whole_seconds(*billed), None),
+                    "hours": format_hours(*billed),
+                    "rate": money::format(*rate, currency(name)),
+                    "amount": money::format(*amount, currency(name)),
+                    "entries": project
+                        .logged_times
+                        .iter()
+                        .filter(|time| is_invoiceable(&project.settings, time, &period))
+                        .map(|time| entry_context(time, &project.settings))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+
+        let context = serde_json::json!({
+            "number": format!("{number:04}"),
+            "client": client,
+            "period": period.to_string(),
+            "from": date::format_date(period.from),
+            "to": date::format_date(period.to),
+            "issued": date::format_date(now),
+            "lines": lines,
+            "net": money::format(net, total_currency),
+            "tax_rate": tax_rate,
+            "tax": tax_rate.map(|_| money::format(tax, total_currency)),
+            "total": money::format(gross, total_currency),
+            "currency": total_currency,
+        });
+
+        print!("{}", template.render(&context));
+    } else {
+        println!("{}", format!("Invoice {number:04}").bright_yellow());
+        println!("  client: {}", client.bright_blue());
+        println!("  period: {}", period.to_string().bright_blue());
+        println!("  issued: {}", date::format_date(now).bright_blue());
+        println!();
+
+        for (name, billed, rate, amount) in &lines {
+            println!(
+                "  {} - {} at {}/h: {}",
+                project_name(list, name),
+                pretty_duration(&whole_seconds(*billed), None).bright_red(),
+                money::format(*rate, currency(name)),
+                money::format(*amount, currency(name)).bright_green()
+            );
+        }
+
+        if let Some(rate) = tax_rate {
+            println!(
+                "  {} {}",
+                "Net:".bright_yellow(),
+                money::format(net, total_currency).bright_green()
+            );
+            println!(
+                "  {} {}",
+                format!("Tax at {rate}%:").bright_yellow(),
+                money::format(tax, total_currency).bright_green()
+            );
+        }
+
+        println!(
+            "  {} {}",
+            "Total:".bright_yellow(),
+            money::format(gross, total_currency).bright_green()
+        );
     }
 
-    if list.active_project == Some(name.to_string()) {
-        list.active_project = None;
+    let charge = Charge {
+        amount: gross,
+        tax,
+        currency: total_currency.map(str::to_string),
+    };
+    list.invoices
+        .issue(client, now, (period.from, period.to), charge);
+
+    for name in &names {
+        let project = list.project_mut(name)?;
+
+        for time in &mut project.logged_times {
+            if is_invoiceable(&project.settings, time, &period) {
+                time.invoice = Some(number);
+            }
+        }
     }
 
     Ok(())
 }
 
-fn handle_hat(list: &mut ProjectList, name: &str) -> Result<()> {
-    if list.projects.contains_key(name) {
-        list.active_project = Some(name.to_string());
-        let name = name.bright_cyan();
-        println!("{}", format!("Selected project {name}").bright_green());
+/// Whether an entry within a period is billable, by itself or by its project's setting, and
+/// not on an invoice yet.
+fn is_invoiceable(settings: &ProjectSettings, time: &LoggedTime, period: &Period) -> bool {
+    in_range(time, Some(period.from), Some(period.to))
+        && settings.is_billable(time)
+        && time.invoice.is_none()
+}
+
+fn handle_invoices_mark_paid(list: &mut ProjectList, number: u64) -> Result<bool> {
+    let invoice = list
+        .invoices
+        .invoices
+        .iter_mut()
+        .find(|invoice| invoice.number == number)
+        .ok_or(Error::UnknownInvoice(number))?;
+
+    if invoice.status == InvoiceStatus::Paid {
+        println!(
+            "{}",
+            format!("Invoice {number:04} was already paid.").bright_yellow()
+        );
+        return Ok(false);
+    }
+
+    invoice.status = InvoiceStatus::Paid;
+    success!("Marked invoice {number:04} as paid.");
+
+    Ok(true)
+}
+
+fn handle_invoices_list(list: &ProjectList, outstanding: bool) -> Result<()> {
+    let invoices: Vec<&Invoice> = list
+        .invoices
+        .invoices
+        .iter()
+        .filter(|invoice| !outstanding || invoice.status == InvoiceStatus::Outstanding)
+        .collect();
+
+    if invoices.is_empty() {
+        println!("{}", "No invoices were found.".bright_red());
+        return Ok(());
+    }
+
+    println!("{}", "Invoices:".bright_yellow());
+
+    for invoice in invoices {
+        let status = match invoice.status {
+            InvoiceStatus::Outstanding => invoice.status.to_string().bright_red(),
+            InvoiceStatus::Paid => invoice.status.to_string().bright_green(),
+        };
+
+        println!(
+            "  {} {} - {} to {}: {} ({status})",
+            format!("{:04}", invoice.number).bright_yellow(),
+            invoice.client.bright_blue(),
+            date::format_date(invoice.from_epoch),
+            date::format_date(invoice.to_epoch),
+            money::format(invoice.amount, invoice.currency.as_deref()).bright_green()
+        );
+    }
+
+    Ok(())
+}
+
+fn handle_total(
+    list: &ProjectList,
+    project: Option<&str>,
+    today: bool,
+    week: Option<WeekStart>,
+    from: Option<Duration>,
+    to: Option<Duration>,
+    seconds: bool,
+) -> Result<()> {
+    let name = target_project(list, project)?;
+    let project = &list.projects[&name];
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+    let (from, to) = if today {
+        (Some(date::start_of_day(date::day_number(now))), Some(now))
+    } else if let Some(week_start) = week {
+        (Some(week_start.start_of_week(now)), None)
     } else {
-        return Err(Error::UnknownProject(name.to_string()));
+        (from, to)
+    };
+
+    let mut total: Duration = entries_in_range(project, from, to)
+        .iter()
+        .map(|time| time.duration)
+        .sum();
+
+    if let (Some(start), Some(elapsed)) = (project.start_epoch, project.elapsed(now)) {
+        let running = LoggedTime {
+            start_epoch: start,
+            duration: elapsed,
+            description: String::new(),
+            notes: None,
+            tags: Vec::new(),
+            billable: None,
+            rounded: None,
+            invoice: None,
+            tickets: Vec::new(),
+        };
+
+        if in_range(&running, from, to) {
+            total += elapsed;
+        }
+    }
+
+    if seconds {
+        println!("{}", total.as_secs());
+    } else {
+        println!("{}", pretty_duration(&whole_seconds(total), None));
+    }
+
+    Ok(())
+}
+
+fn handle_current(list: &ProjectList, running: bool) -> Result<()> {
+    let active = list.active_project.as_ref().filter(|name| {
+        !running
+            || list
+                .projects
+                .get(*name)
+                .is_some_and(|project| project.start_epoch.is_some())
+    });
+
+    // Prompts and scripts only need the exit code, so there is no message when nothing matches.
+    let Some(active) = active else {
+        process::exit(1);
+    };
+
+    println!("{active}");
+
+    Ok(())
+}
+
+/// Parses a color name such as `red` or `bright blue`, or a hex color such as `#ff8800`.
+fn parse_color(text: &str) -> Option<Color> {
+    let Some(hex) = text.strip_prefix('#') else {
+        return text.parse().ok();
+    };
+
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+
+    Some(Color::TrueColor {
+        r: channel(0)?,
+        g: channel(2)?,
+        b: channel(4)?,
+    })
+}
+
+/// The name of a project in the color assigned to it, or bright cyan if it has none.
+fn project_name(list: &ProjectList, name: &str) -> ColoredString {
+    let color = list
+        .projects
+        .get(name)
+        .and_then(|project| project.metadata.color.as_deref())
+        .and_then(parse_color);
+
+    match color {
+        Some(color) => name.color(color),
+        None => name.bright_cyan(),
+    }
+}
+
+/// Describes whether a project is billable and what its time is worth, if either is set.
+fn billing_note(settings: &ProjectSettings, duration: Duration) -> Option<String> {
+    let currency = settings.currency.as_deref();
+    let amount = settings
+        .amount(duration)
+        .zip(settings.rate)
+        .map(|(amount, rate)| {
+            format!(
+                "{} at {}/h",
+                money::format(amount, currency),
+                money::format(rate, currency)
+            )
+        });
+
+    match (settings.billable, amount) {
+        (true, Some(amount)) => Some(format!("billable, {amount}")),
+        (true, None) => Some("billable".to_string()),
+        (false, amount) => amount,
+    }
+}
+
+fn handle_project_show(list: &ProjectList, name: Option<&str>) -> Result<()> {
+    let name = target_project(list, name)?;
+    let settings = &list.projects[&name].settings;
+
+    println!(
+        "{}",
+        format!("Settings for project {}:", project_name(list, &name)).bright_yellow()
+    );
+
+    let tags = if settings.tags.is_empty() {
+        "none".to_string()
+    } else {
+        settings.tags.join(", ")
+    };
+
+    println!("  tags: {}", tags.bright_magenta());
+    println!(
+        "  billable: {}",
+        if settings.billable { "yes" } else { "no" }.bright_blue()
+    );
+
+    if let Some(rate) = settings.rate {
+        let rate = money::format(rate, settings.currency.as_deref());
+        println!("  rate: {}", format!("{rate}/h").bright_blue());
+    }
+
+    if let Some(minutes) = settings.round_minutes {
+        println!("  rounding: {}", format!("{minutes} minutes").bright_blue());
+    }
+
+    if let Some(auto_stop) = &settings.auto_stop {
+        println!("  auto-stop: {}", auto_stop.bright_blue());
+    }
+
+    if let Some(estimate) = settings.estimate {
+        println!(
+            "  estimate: {}",
+            pretty_duration(&estimate, None).bright_blue()
+        );
+    }
+
+    print_metadata(&list.projects[&name].metadata, "  ");
+
+    Ok(())
+}
+
+fn handle_project_set(list: &mut ProjectList, args: &ProjectSetArgs) -> Result<()> {
+    let project = list.project_mut(&args.name)?;
+
+    if let Some(color) = args.color.as_deref().map(str::trim) {
+        if !color.is_empty() && parse_color(color).is_none() {
+            return Err(Error::InvalidColor(color.to_string()));
+        }
+    }
+
+    let fields = [
+        (&args.description, &mut project.metadata.description),
+        (&args.client, &mut project.metadata.client),
+        (&args.color, &mut project.metadata.color),
+        (&args.url, &mut project.metadata.url),
+    ];
+
+    for (value, field) in fields {
+        if let Some(value) = value {
+            let value = value.trim();
+            *field = (!value.is_empty()).then(|| value.to_string());
+        }
+    }
+
+    let settings = &mut project.settings;
+
+    if let Some(tags) = &args.tags {
+        settings.tags = tags
+            .iter()
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+    }
+
+    if let Some(billable) = args.billable {
+        settings.billable = billable;
+    }
+
+    if let Some(rate) = args.rate {
+        settings.rate = (rate > 0.0).then_some(rate);
     }
 
+    if let Some(currency) = &args.currency {
+        settings.currency = if currency == "none" {
+            None
+        } else {
+            let code = money::parse_currency(currency)
+                .ok_or_else(|| Error::InvalidCurrency(currency.to_string()))?;
+            Some(code)
+        };
+    }
+
+    if let Some(minutes) = args.round {
+        settings.round_minutes = (minutes > 0).then_some(minutes);
+    }
+
+    if let Some(auto_stop) = &args.auto_stop {
+        settings.auto_stop = if auto_stop == "none" {
+            None
+        } else if date::parse_time_of_day(auto_stop).is_some() {
+            Some(auto_stop.trim().to_string())
+        } else {
+            return Err(Error::InvalidTimeOfDay(auto_stop.to_string()));
+        };
+    }
+
+    if let Some(estimate) = &args.estimate {
+        settings.estimate = if estimate == "none" {
+            None
+        } else {
+            Some(parse_duration_text(estimate)?).filter(|estimate| !estimate.is_zero())
+        };
+    }
+
+    success!(
+        "Updated the settings of project {}.",
+        project_name(list, &args.name)
+    );
+
     Ok(())
 }
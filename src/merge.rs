@@ -0,0 +1,213 @@
+use std::{fmt, time::Duration};
+
+use colored::Colorize;
+use pretty_duration::pretty_duration;
+
+use crate::{LoggedTime, ProjectList};
+
+/// Which side of a merge to keep when both sides disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+/// A disagreement between two project lists that are being merged.
+#[derive(Debug, Clone)]
+pub enum Conflict {
+    /// Both sides have a running timer for the project, started at different times.
+    Timer {
+        project: String,
+        ours: Duration,
+        theirs: Duration,
+    },
+
+    /// Both sides have entries that overlap in time, or start at the same time, but with
+    /// different contents.
+    Entry {
+        project: String,
+        ours: Box<LoggedTime>,
+        theirs: Box<LoggedTime>,
+    },
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timer {
+                project,
+                ours,
+                theirs,
+            } => {
+                let apart = ours.max(theirs).saturating_sub(*ours.min(theirs));
+                write!(
+                    f,
+                    "Project {} has running timers on both sides, started {} apart.",
+                    project.bright_cyan(),
+                    pretty_duration(&apart, None).bright_red()
+                )
+            }
+            Self::Entry {
+                project,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Project {} has conflicting entries: {} - {} (ours) and {} - {} (theirs).",
+                project.bright_cyan(),
+                pretty_duration(&ours.duration, None).bright_red(),
+                ours.description.bright_blue(),
+                pretty_duration(&theirs.duration, None).bright_red(),
+                theirs.description.bright_blue()
+            ),
+        }
+    }
+}
+
+/// Counts of what changed during a merge.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Summary {
+    pub projects_added: usize,
+    pub entries_added: usize,
+    pub conflicts: usize,
+}
+
+/// Adds the projects and entries of `theirs` that `ours` doesn't have yet, for combining
+/// archive files with each other and with the data file. Unlike `merge`, entries only match
+/// when they are identical, so entries that overlap or share a start time are all kept and
+/// nothing is ever dropped. Timers and invoices are left alone, since archive files have none.
+pub fn union(ours: &mut ProjectList, theirs: ProjectList) {
+    for (name, their_project) in theirs.projects {
+        let our_project = ours.projects.entry(name).or_default();
+
+        for their_time in their_project.logged_times {
+            if !our_project.logged_times.contains(&their_time) {
+                our_project.logged_times.push(their_time);
+                our_project.cached_total = None;
+            }
+        }
+
+        our_project
+            .logged_times
+            .sort_by_key(|time| time.start_epoch);
+    }
+}
+
+/// Merges `theirs` into `ours`, unioning projects and entries.
+/// Entries are matched by their start time, or else by overlapping in time, and `resolve`
+/// decides which side wins whenever both sides disagree. The active project of `ours` is kept if it is set.
+pub fn merge(
+    ours: &mut ProjectList,
+    theirs: ProjectList,
+    mut resolve: impl FnMut(&Conflict) -> Side,
+) -> Summary {
+    let mut summary = Summary::default();
+
+    let mut names: Vec<String> = theirs.projects.keys().cloned().collect();
+    names.sort();
+
+    let mut theirs_projects = theirs.projects;
+
+    for name in names {
+        let their_project = theirs_projects
+            .remove(&name)
+            .expect("project name was taken from the map");
+
+        let Some(our_project) = ours.projects.get_mut(&name) else {
+            summary.projects_added += 1;
+            summary.entries_added += their_project.logged_times.len();
+            ours.projects.insert(name, their_project);
+            continue;
+        };
+
+        match (our_project.start_epoch, their_project.start_epoch) {
+            (None, Some(start)) => {
+                our_project.start_epoch = Some(start);
+                our_project.paused_epoch = their_project.paused_epoch;
+                our_project.paused_duration = their_project.paused_duration;
+                our_project.pauses = their_project.pauses.clone();
+            }
+            (Some(our_start), Some(their_start)) if our_start != their_start => {
+                summary.conflicts += 1;
+
+                let conflict = Conflict::Timer {
+                    project: name.clone(),
+                    ours: our_start,
+                    theirs: their_start,
+                };
+
+                if resolve(&conflict) == Side::Theirs {
+                    our_project.start_epoch = Some(their_start);
+                    our_project.paused_epoch = their_project.paused_epoch;
+                    our_project.paused_duration = their_project.paused_duration;
+                    our_project.pauses = their_project.pauses.clone();
+                }
+            }
+            _ => {}
+        }
+
+        for their_time in their_project.logged_times {
+            // The same entry edited on one side may have been moved, so any overlap counts.
+            let index = our_project
+                .logged_times
+                .iter()
+                .position(|time| time.start_epoch == their_time.start_epoch)
+                .or_else(|| {
+                    our_project
+                        .logged_times
+                        .iter()
+                        .position(|time| time.overlaps(&their_time))
+                });
+            let existing = index.map(|index| &mut our_project.logged_times[index]);
+
+            match existing {
+                None => {
+                    our_project.logged_times.push(their_time);
+                    our_project.cached_total = None;
+                    summary.entries_added += 1;
+                }
+                Some(our_time) if *our_time != their_time => {
+                    summary.conflicts += 1;
+
+                    let conflict = Conflict::Entry {
+                        project: name.clone(),
+                        ours: Box::new(our_time.clone()),
+                        theirs: Box::new(their_time.clone()),
+                    };
+
+                    if resolve(&conflict) == Side::Theirs {
+                        *our_time = their_time;
+                        our_project.cached_total = None;
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        our_project
+            .logged_times
+            .sort_by_key(|time| time.start_epoch);
+    }
+
+    if ours.active_project.is_none() {
+        ours.active_project = theirs.active_project;
+    }
+
+    // Invoices are matched by number, keeping ours, so that no number is issued twice.
+    let registry = &mut ours.invoices;
+    registry.last_number = registry.last_number.max(theirs.invoices.last_number);
+
+    for their_invoice in theirs.invoices.invoices {
+        if !registry
+            .invoices
+            .iter()
+            .any(|invoice| invoice.number == their_invoice.number)
+        {
+            registry.invoices.push(their_invoice);
+        }
+    }
+
+    registry.invoices.sort_by_key(|invoice| invoice.number);
+
+    summary
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+/// Currencies written with a symbol before the amount, and how many decimals they use.
+const SYMBOLS: [(&str, &str, usize); 6] = [
+    ("USD", "$", 2),
+    ("EUR", "€", 2),
+    ("GBP", "£", 2),
+    ("JPY", "¥", 0),
+    ("INR", "₹", 2),
+    ("KRW", "₩", 0),
+];
+
+/// Reads a currency code such as `eur`, returning it in upper case.
+pub fn parse_currency(text: &str) -> Option<String> {
+    let code = text.trim().to_uppercase();
+    (code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic())).then_some(code)
+}
+
+/// Formats an amount of money with thousands separators, such as `$1,234.50`, `€80.00`, or
+/// `1,234.50 CHF`. Amounts without a currency are written as a plain number.
+pub fn format(amount: f64, currency: Option<&str>) -> String {
+    let symbol = currency.and_then(|code| SYMBOLS.iter().find(|(known, ..)| *known == code));
+    let decimals = decimals(currency);
+
+    let number = format!("{:.decimals$}", amount.abs());
+    let (whole, fraction) = number.split_at(number.find('.').unwrap_or(number.len()));
+
+    let mut grouped = String::new();
+
+    for (index, digit) in whole.chars().enumerate() {
+        if index > 0 && (whole.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+
+        grouped.push(digit);
+    }
+
+    let sign = if amount < 0.0 { "-" } else { "" };
+
+    match (symbol, currency) {
+        (Some((_, symbol, _)), _) => format!("{sign}{symbol}{grouped}{fraction}"),
+        (None, Some(code)) => format!("{sign}{grouped}{fraction} {code}"),
+        (None, None) => format!("{sign}{grouped}{fraction}"),
+    }
+}
+
+/// The number of decimals amounts in a currency are written with.
+fn decimals(currency: Option<&str>) -> usize {
+    currency
+        .and_then(|code| SYMBOLS.iter().find(|(known, ..)| *known == code))
+        .map_or(2, |(.., decimals)| *decimals)
+}
+
+/// Rounds an amount to the smallest unit of its currency, such as cents, rounding halves away
+/// from zero.
+pub fn round(amount: f64, currency: Option<&str>) -> f64 {
+    let scale = 10_f64.powi(decimals(currency) as i32);
+
+    // Snapping to a millionth of the unit first keeps 1.005 from being read as 1.00499....
+    let units = (amount * scale * 1e6).round() / 1e6;
+    units.round() / scale
+}
+
+/// Converts an amount into the base currency, using rates given as how much of the base
+/// currency one unit of each currency is worth. Amounts without a currency are taken to be
+/// in the base currency already.
+pub fn convert(
+    amount: f64,
+    currency: Option<&str>,
+    base: &str,
+    rates: &HashMap<String, f64>,
+) -> Option<f64> {
+    match currency {
+        None => Some(amount),
+        Some(code) if code.eq_ignore_ascii_case(base) => Some(amount),
+        Some(code) => rates
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(code))
+            .map(|(_, rate)| amount * rate),
+    }
+}
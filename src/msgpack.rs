@@ -0,0 +1,809 @@
+//! A compact binary encoding of the data file in the MessagePack format, which other tools can
+//! read too. Structs are written as maps keyed by field name, like JSON objects, so that fields
+//! can be added later without breaking older files.
+
+use std::fmt;
+
+use serde::{
+    de::{self, DeserializeSeed, IntoDeserializer, Visitor},
+    ser::{self, Serialize},
+    Deserialize,
+};
+
+/// An error while encoding or decoding MessagePack.
+#[derive(Debug)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Encodes a value as MessagePack.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+    let mut serializer = Serializer { output: Vec::new() };
+    value.serialize(&mut serializer)?;
+    Ok(serializer.output)
+}
+
+/// Decodes a value from MessagePack, which must fill all of `bytes`.
+pub fn from_slice<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> Result<T> {
+    let mut deserializer = Deserializer { input: bytes };
+    let value = T::deserialize(&mut deserializer)?;
+
+    if !deserializer.input.is_empty() {
+        return Err(Error("unexpected data after the end".to_string()));
+    }
+
+    Ok(value)
+}
+
+struct Serializer {
+    output: Vec<u8>,
+}
+
+impl Serializer {
+    fn write_uint(&mut self, value: u64) {
+        match value {
+            0..=0x7f => self.output.push(value as u8),
+            0x80..=0xff => self.output.extend_from_slice(&[0xcc, value as u8]),
+            0x100..=0xffff => {
+                self.output.push(0xcd);
+                self.output.extend_from_slice(&(value as u16).to_be_bytes());
+            }
+            0x1_0000..=0xffff_ffff => {
+                self.output.push(0xce);
+                self.output.extend_from_slice(&(value as u32).to_be_bytes());
+            }
+            _ => {
+                self.output.push(0xcf);
+                self.output.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+    }
+
+    fn write_int(&mut self, value: i64) {
+        if value >= 0 {
+            return self.write_uint(value as u64);
+        }
+
+        if value >= -32 {
+            self.output.push(value as u8);
+        } else if value >= i64::from(i8::MIN) {
+            self.output.extend_from_slice(&[0xd0, value as u8]);
+        } else if value >= i64::from(i16::MIN) {
+            self.output.push(0xd1);
+            self.output.extend_from_slice(&(value as i16).to_be_bytes());
+        } else if value >= i64::from(i32::MIN) {
+            self.output.push(0xd2);
+            self.output.extend_from_slice(&(value as i32).to_be_bytes());
+        } else {
+            self.output.push(0xd3);
+            self.output.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    /// Writes the marker of a string, binary, array, or map of the given length, choosing the
+    /// smallest of the sizes the format allows.
+    fn write_header(&mut self, len: usize, fixed: Option<(u8, usize)>, markers: [u8; 3]) {
+        match fixed {
+            Some((marker, limit)) if len < limit => self.output.push(marker | len as u8),
+            _ if len <= 0xff && markers[0] != 0 => {
+                self.output.extend_from_slice(&[markers[0], len as u8])
+            }
+            _ if len <= 0xffff => {
+                self.output.push(markers[1]);
+                self.output.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            _ => {
+                self.output.push(markers[2]);
+                self.output.extend_from_slice(&(len as u32).to_be_bytes());
+            }
+        }
+    }
+
+    fn write_str(&mut self, value: &str) {
+        self.write_header(value.len(), Some((0xa0, 32)), [0xd9, 0xda, 0xdb]);
+        self.output.extend_from_slice(value.as_bytes());
+    }
+
+    fn write_array_header(&mut self, len: usize) {
+        self.write_header(len, Some((0x90, 16)), [0, 0xdc, 0xdd]);
+    }
+
+    fn write_map_header(&mut self, len: usize) {
+        self.write_header(len, Some((0x80, 16)), [0, 0xde, 0xdf]);
+    }
+}
+
+/// Collects the items of an array or map, whose length is only known once they are written.
+struct Compound<'a> {
+    parent: &'a mut Serializer,
+    items: Serializer,
+    count: usize,
+    map: bool,
+
+    /// A variant name to wrap the array or map in, as in `{"Variant": [...]}`.
+    variant: Option<&'static str>,
+}
+
+impl<'a> Compound<'a> {
+    fn new(parent: &'a mut Serializer, map: bool, variant: Option<&'static str>) -> Self {
+        Self {
+            parent,
+            items: Serializer { output: Vec::new() },
+            count: 0,
+            map,
+            variant,
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        if let Some(variant) = self.variant {
+            self.parent.write_map_header(1);
+            self.parent.write_str(variant);
+        }
+
+        if self.map {
+            self.parent.write_map_header(self.count);
+        } else {
+            self.parent.write_array_header(self.count);
+        }
+
+        self.parent.output.extend_from_slice(&self.items.output);
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, value: bool) -> Result<()> {
+        self.output.push(if value { 0xc3 } else { 0xc2 });
+        Ok(())
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<()> {
+        self.serialize_i64(i64::from(value))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<()> {
+        self.serialize_i64(i64::from(value))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<()> {
+        self.serialize_i64(i64::from(value))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<()> {
+        self.write_int(value);
+        Ok(())
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<()> {
+        self.serialize_u64(u64::from(value))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<()> {
+        self.serialize_u64(u64::from(value))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<()> {
+        self.serialize_u64(u64::from(value))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<()> {
+        self.write_uint(value);
+        Ok(())
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<()> {
+        self.output.push(0xca);
+        self.output.extend_from_slice(&value.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<()> {
+        self.output.push(0xcb);
+        self.output.extend_from_slice(&value.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, value: char) -> Result<()> {
+        self.write_str(value.encode_utf8(&mut [0; 4]));
+        Ok(())
+    }
+
+    fn serialize_str(self, value: &str) -> Result<()> {
+        self.write_str(value);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<()> {
+        self.write_header(value.len(), None, [0xc4, 0xc5, 0xc6]);
+        self.output.extend_from_slice(value);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.output.push(0xc0);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.write_str(variant);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.write_map_header(1);
+        self.write_str(variant);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'a>> {
+        Ok(Compound::new(self, false, None))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound<'a>> {
+        Ok(Compound::new(self, false, None))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Compound<'a>> {
+        Ok(Compound::new(self, false, None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>> {
+        Ok(Compound::new(self, false, Some(variant)))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'a>> {
+        Ok(Compound::new(self, true, None))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Compound<'a>> {
+        Ok(Compound::new(self, true, None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>> {
+        Ok(Compound::new(self, true, Some(variant)))
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.count += 1;
+        value.serialize(&mut self.items)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        self.count += 1;
+        key.serialize(&mut self.items)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut self.items)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.count += 1;
+        self.items.write_str(key);
+        value.serialize(&mut self.items)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+struct Deserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Deserializer<'de> {
+    fn take(&mut self, len: usize) -> Result<&'de [u8]> {
+        if self.input.len() < len {
+            return Err(Error("unexpected end of data".to_string()));
+        }
+
+        let (taken, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("took exactly N bytes"))
+    }
+
+    fn peek(&self) -> Result<u8> {
+        self.input
+            .first()
+            .copied()
+            .ok_or_else(|| Error("unexpected end of data".to_string()))
+    }
+
+    /// Reads a length of 1, 2, or 4 bytes.
+    fn take_len(&mut self, size: usize) -> Result<usize> {
+        Ok(match size {
+            1 => usize::from(self.take(1)?[0]),
+            2 => usize::from(u16::from_be_bytes(self.take_array()?)),
+            _ => u32::from_be_bytes(self.take_array()?) as usize,
+        })
+    }
+
+    fn take_str(&mut self, len: usize) -> Result<&'de str> {
+        std::str::from_utf8(self.take(len)?).map_err(|err| Error(err.to_string()))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let marker = self.take(1)?[0];
+
+        match marker {
+            0x00..=0x7f => visitor.visit_u64(u64::from(marker)),
+            0x80..=0x8f => visitor.visit_map(Items::new(self, usize::from(marker & 0x0f))),
+            0x90..=0x9f => visitor.visit_seq(Items::new(self, usize::from(marker & 0x0f))),
+            0xa0..=0xbf => visitor.visit_borrowed_str(self.take_str(usize::from(marker & 0x1f))?),
+            0xc0 => visitor.visit_unit(),
+            0xc2 => visitor.visit_bool(false),
+            0xc3 => visitor.visit_bool(true),
+            0xc4..=0xc6 => {
+                let len = self.take_len(1 << (marker - 0xc4))?;
+                visitor.visit_borrowed_bytes(self.take(len)?)
+            }
+            0xca => visitor.visit_f32(f32::from_be_bytes(self.take_array()?)),
+            0xcb => visitor.visit_f64(f64::from_be_bytes(self.take_array()?)),
+            0xcc => visitor.visit_u64(u64::from(self.take(1)?[0])),
+            0xcd => visitor.visit_u64(u64::from(u16::from_be_bytes(self.take_array()?))),
+            0xce => visitor.visit_u64(u64::from(u32::from_be_bytes(self.take_array()?))),
+            0xcf => visitor.visit_u64(u64::from_be_bytes(self.take_array()?)),
+            0xd0 => visitor.visit_i64(i64::from(self.take(1)?[0] as i8)),
+            0xd1 => visitor.visit_i64(i64::from(i16::from_be_bytes(self.take_array()?))),
+            0xd2 => visitor.visit_i64(i64::from(i32::from_be_bytes(self.take_array()?))),
+            0xd3 => visitor.visit_i64(i64::from_be_bytes(self.take_array()?)),
+            0xd9..=0xdb => {
+                let len = self.take_len(1 << (marker - 0xd9))?;
+                visitor.visit_borrowed_str(self.take_str(len)?)
+            }
+            0xdc | 0xdd => {
+                let len = self.take_len(if marker == 0xdc { 2 } else { 4 })?;
+                visitor.visit_seq(Items::new(self, len))
+            }
+            0xde | 0xdf => {
+                let len = self.take_len(if marker == 0xde { 2 } else { 4 })?;
+                visitor.visit_map(Items::new(self, len))
+            }
+            0xe0..=0xff => visitor.visit_i64(i64::from(marker as i8)),
+            _ => Err(Error(format!("unsupported marker 0x{marker:02x}"))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.peek()? == 0xc0 {
+            self.take(1)?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.peek()? {
+            // A unit variant is written as its name alone.
+            0xa0..=0xbf | 0xd9..=0xdb => {
+                let variant: &str = Deserialize::deserialize(&mut *self)?;
+                visitor.visit_enum(variant.into_deserializer())
+            }
+            0x81 => {
+                self.take(1)?;
+                visitor.visit_enum(Variant { de: self })
+            }
+            marker => Err(Error(format!(
+                "expected an enum, found marker 0x{marker:02x}"
+            ))),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// The items of an array or map, read one at a time.
+struct Items<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    remaining: usize,
+}
+
+impl<'a, 'de> Items<'a, 'de> {
+    fn new(de: &'a mut Deserializer<'de>, len: usize) -> Self {
+        Self { de, remaining: len }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for Items<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Items<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+/// An enum variant with data, written as a map from its name to the data.
+struct Variant<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+}
+
+impl<'a, 'de> de::EnumAccess<'de> for Variant<'a, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let variant = seed.deserialize(&mut *self.de)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Variant<'_, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Deserialize::deserialize(self.de)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_any(self.de, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_any(self.de, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, fmt::Debug, time::Duration};
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{from_slice, to_vec};
+
+    fn round_trip<T: Serialize + for<'de> Deserialize<'de> + PartialEq + Debug>(value: T) {
+        let bytes = to_vec(&value).expect("value encodes");
+        let decoded: T = from_slice(&bytes).expect("value decodes");
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn integers() {
+        for value in [
+            0,
+            1,
+            127,
+            128,
+            255,
+            256,
+            65_535,
+            65_536,
+            u32::MAX as u64,
+            u64::MAX,
+        ] {
+            round_trip(value);
+        }
+
+        for value in [
+            -1,
+            -32,
+            -33,
+            -128,
+            -129,
+            -32_768,
+            -32_769,
+            i32::MIN as i64,
+            i64::MIN,
+        ] {
+            round_trip(value);
+        }
+    }
+
+    #[test]
+    fn integers_use_the_smallest_encoding() {
+        assert_eq!(to_vec(&5u64).unwrap(), [0x05]);
+        assert_eq!(to_vec(&200u64).unwrap(), [0xcc, 200]);
+        assert_eq!(to_vec(&-5i64).unwrap(), [0xfb]);
+        assert_eq!(to_vec(&-100i64).unwrap(), [0xd0, 0x9c]);
+    }
+
+    #[test]
+    fn floats() {
+        for value in [0.0, -1.5, 3.25, f64::MAX, f64::MIN_POSITIVE] {
+            round_trip(value);
+        }
+
+        round_trip(0.1f32);
+    }
+
+    #[test]
+    fn strings() {
+        round_trip(String::new());
+        round_trip("short".to_string());
+        round_trip("ünïcödé and emoji 🎩".to_string());
+        round_trip("a".repeat(31));
+        round_trip("a".repeat(32));
+        round_trip("a".repeat(256));
+        round_trip("a".repeat(70_000));
+    }
+
+    #[test]
+    fn maps_and_sequences() {
+        let map: BTreeMap<String, u64> = (0..20).map(|i| (format!("key {i}"), i)).collect();
+        round_trip(map);
+
+        round_trip(vec![1u8, 2, 3]);
+        round_trip((0..100_000).collect::<Vec<u32>>());
+        round_trip(Vec::<String>::new());
+    }
+
+    #[test]
+    fn nested_options() {
+        round_trip(None::<u64>);
+        round_trip(Some(7u64));
+        round_trip(Some(Some("inner".to_string())));
+        round_trip(vec![Some(1i64), None, Some(-1)]);
+    }
+
+    #[test]
+    fn durations() {
+        round_trip(Duration::ZERO);
+        round_trip(Duration::new(1_717_405_200, 123_456_789));
+        round_trip(Some(Duration::from_secs(3600)));
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Kind {
+        Unit,
+        Tuple(u8, String),
+        Struct { flag: bool },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Entry {
+        start: Duration,
+        description: String,
+        billable: Option<bool>,
+        tags: Vec<String>,
+        kinds: Vec<Kind>,
+    }
+
+    #[test]
+    fn structs_and_enums() {
+        round_trip(Entry {
+            start: Duration::from_secs(60),
+            description: "Write tests".to_string(),
+            billable: Some(false),
+            tags: vec!["dev".to_string()],
+            kinds: vec![
+                Kind::Unit,
+                Kind::Tuple(3, "three".to_string()),
+                Kind::Struct { flag: true },
+            ],
+        });
+    }
+
+    #[test]
+    fn rejects_trailing_data() {
+        let mut bytes = to_vec(&1u64).unwrap();
+        bytes.push(0x01);
+        assert!(from_slice::<u64>(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let bytes = to_vec(&"a string".to_string()).unwrap();
+        assert!(from_slice::<String>(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
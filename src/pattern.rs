@@ -0,0 +1,485 @@
+//! A small regular expression matcher for rules in the config file, which ignores case.
+//! It supports alternatives with `|`, groups, `^` and `$`, `.`, character classes such as
+//! `[a-z]` and `[^0-9]`, the escapes `\d`, `\w`, `\s`, and `\b` for word boundaries, and the
+//! quantifiers `*`, `+`, and `?`.
+
+use std::{iter::Peekable, str::Chars};
+
+use crate::{Error, Result};
+
+/// A compiled pattern. It is run by stepping every state it could be in through the text at
+/// once, instead of backtracking, so matching takes time proportional to the length of the
+/// text times the size of the pattern, however its quantifiers nest.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    program: Vec<Instruction>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Start,
+    End,
+    WordBoundary,
+    Group(Vec<Vec<Node>>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+/// A step of a compiled pattern. The states that consume a character, and `Match`, are the
+/// ones the matcher keeps track of. The others are followed right away.
+#[derive(Debug, Clone)]
+enum Instruction {
+    Char(char),
+    Any,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Start,
+    End,
+    WordBoundary,
+    /// Continues at both instructions.
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+impl Pattern {
+    pub fn parse(source: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::InvalidPattern(source.to_string(), reason.to_string());
+
+        let mut chars = source.chars().peekable();
+        let alternatives = parse_alternatives(&mut chars).map_err(invalid)?;
+
+        if chars.next().is_some() {
+            return Err(invalid("unmatched )"));
+        }
+
+        let mut program = Vec::new();
+        compile_alternatives(&alternatives, &mut program);
+        program.push(Instruction::Match);
+
+        Ok(Self { program })
+    }
+
+    /// Whether the pattern matches anywhere in `text`.
+    pub fn is_match(&self, text: &str) -> bool {
+        let text: Vec<char> = text.chars().collect();
+        let mut current = Vec::new();
+        let mut seen = vec![false; self.program.len()];
+
+        for position in 0..=text.len() {
+            // A match may start anywhere, so every position starts the pattern over as well.
+            self.follow(0, &text, position, &mut current, &mut seen);
+
+            if current
+                .iter()
+                .any(|&pc| matches!(self.program[pc], Instruction::Match))
+            {
+                return true;
+            }
+
+            let Some(&c) = text.get(position) else {
+                break;
+            };
+
+            let mut next = Vec::new();
+            seen.fill(false);
+
+            for pc in current {
+                if self.accepts(pc, c) {
+                    self.follow(pc + 1, &text, position + 1, &mut next, &mut seen);
+                }
+            }
+
+            current = next;
+        }
+
+        false
+    }
+
+    /// Adds the states reachable from `pc` at `position` without consuming a character. A state
+    /// that was already added is skipped, which also stops repeats that matched nothing.
+    fn follow(
+        &self,
+        pc: usize,
+        text: &[char],
+        position: usize,
+        states: &mut Vec<usize>,
+        seen: &mut [bool],
+    ) {
+        if std::mem::replace(&mut seen[pc], true) {
+            return;
+        }
+
+        let holds = match self.program[pc] {
+            Instruction::Jump(to) => return self.follow(to, text, position, states, seen),
+            Instruction::Split(first, second) => {
+                self.follow(first, text, position, states, seen);
+                return self.follow(second, text, position, states, seen);
+            }
+            Instruction::Start => position == 0,
+            Instruction::End => position == text.len(),
+            Instruction::WordBoundary => {
+                let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+                let before = position
+                    .checked_sub(1)
+                    .and_then(|before| text.get(before))
+                    .is_some_and(is_word);
+
+                before != text.get(position).is_some_and(is_word)
+            }
+            _ => {
+                states.push(pc);
+                return;
+            }
+        };
+
+        if holds {
+            self.follow(pc + 1, text, position, states, seen);
+        }
+    }
+
+    /// Whether the state at `pc` consumes the character `c`.
+    fn accepts(&self, pc: usize, c: char) -> bool {
+        match &self.program[pc] {
+            Instruction::Char(expected) => same_letter(*expected, c),
+            Instruction::Any => true,
+            Instruction::Class { ranges, negated } => {
+                let lower = c.to_lowercase().next().unwrap_or(c);
+                let upper = c.to_uppercase().next().unwrap_or(c);
+
+                ranges.iter().any(|&(start, end)| {
+                    [c, lower, upper].iter().any(|c| (start..=end).contains(c))
+                }) != *negated
+            }
+            _ => false,
+        }
+    }
+}
+
+fn parse_alternatives(
+    chars: &mut Peekable<Chars>,
+) -> std::result::Result<Vec<Vec<Node>>, &'static str> {
+    let mut alternatives = vec![Vec::new()];
+
+    while let Some(&c) = chars.peek() {
+        if c == ')' {
+            break;
+        }
+
+        chars.next();
+
+        let node = match c {
+            '|' => {
+                alternatives.push(Vec::new());
+                continue;
+            }
+            '(' => {
+                let group = parse_alternatives(chars)?;
+
+                if chars.next() != Some(')') {
+                    return Err("unmatched (");
+                }
+
+                Node::Group(group)
+            }
+            '*' | '+' | '?' => {
+                let sequence = alternatives.last_mut().expect("there is an alternative");
+
+                let node = match sequence.pop() {
+                    Some(Node::Start | Node::End | Node::WordBoundary | Node::Repeat { .. })
+                    | None => {
+                        return Err("nothing to repeat");
+                    }
+                    Some(node) => Box::new(node),
+                };
+
+                let (min, max) = match c {
+                    '*' => (0, None),
+                    '+' => (1, None),
+                    _ => (0, Some(1)),
+                };
+
+                Node::Repeat { node, min, max }
+            }
+            '^' => Node::Start,
+            '$' => Node::End,
+            '.' => Node::Any,
+            '[' => parse_class(chars)?,
+            '\\' => parse_escape(chars.next().ok_or("trailing \\")?),
+            c => Node::Char(c),
+        };
+
+        alternatives
+            .last_mut()
+            .expect("there is an alternative")
+            .push(node);
+    }
+
+    Ok(alternatives)
+}
+
+fn parse_class(chars: &mut Peekable<Chars>) -> std::result::Result<Node, &'static str> {
+    let negated = chars.next_if_eq(&'^').is_some();
+    let mut ranges = Vec::new();
+    let mut first = true;
+
+    loop {
+        let c = chars.next().ok_or("unmatched [")?;
+
+        // A `]` right after the opening bracket is taken literally.
+        if c == ']' && !first {
+            break;
+        }
+
+        first = false;
+
+        let start = match c {
+            '\\' => match parse_escape(chars.next().ok_or("trailing \\")?) {
+                Node::Char(c) => c,
+                Node::Class {
+                    ranges: escaped,
+                    negated: false,
+                } => {
+                    ranges.extend(escaped);
+                    continue;
+                }
+                _ => return Err("unsupported escape in a class"),
+            },
+            c => c,
+        };
+
+        let end = if chars.peek() == Some(&'-') {
+            chars.next();
+
+            match chars.next().ok_or("unmatched [")? {
+                ']' => {
+                    ranges.extend([(start, start), ('-', '-')]);
+                    break;
+                }
+                '\\' => chars.next().ok_or("trailing \\")?,
+                end => end,
+            }
+        } else {
+            start
+        };
+
+        if end < start {
+            return Err("range out of order");
+        }
+
+        ranges.push((start, end));
+    }
+
+    Ok(Node::Class { ranges, negated })
+}
+
+fn parse_escape(c: char) -> Node {
+    let class = |ranges: &[(char, char)], negated: bool| Node::Class {
+        ranges: ranges.to_vec(),
+        negated,
+    };
+
+    const DIGIT: &[(char, char)] = &[('0', '9')];
+    const WORD: &[(char, char)] = &[('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')];
+    const SPACE: &[(char, char)] = &[(' ', ' '), ('\t', '\r')];
+
+    match c {
+        'd' => class(DIGIT, false),
+        'D' => class(DIGIT, true),
+        'w' => class(WORD, false),
+        'W' => class(WORD, true),
+        's' => class(SPACE, false),
+        'S' => class(SPACE, true),
+        'n' => Node::Char('\n'),
+        't' => Node::Char('\t'),
+        'b' => Node::WordBoundary,
+        c => Node::Char(c),
+    }
+}
+
+fn same_letter(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+fn compile_alternatives(alternatives: &[Vec<Node>], program: &mut Vec<Instruction>) {
+    let mut jumps = Vec::new();
+
+    for (i, sequence) in alternatives.iter().enumerate() {
+        if i + 1 == alternatives.len() {
+            compile_sequence(sequence, program);
+            break;
+        }
+
+        // Each alternative but the last either runs, or skips ahead to the next one.
+        let split = program.len();
+        program.push(Instruction::Split(split + 1, 0));
+        compile_sequence(sequence, program);
+
+        jumps.push(program.len());
+        program.push(Instruction::Jump(0));
+        program[split] = Instruction::Split(split + 1, program.len());
+    }
+
+    for jump in jumps {
+        program[jump] = Instruction::Jump(program.len());
+    }
+}
+
+fn compile_sequence(sequence: &[Node], program: &mut Vec<Instruction>) {
+    for node in sequence {
+        compile_node(node, program);
+    }
+}
+
+fn compile_node(node: &Node, program: &mut Vec<Instruction>) {
+    let instruction = match node {
+        Node::Char(c) => Instruction::Char(*c),
+        Node::Any => Instruction::Any,
+        Node::Class { ranges, negated } => Instruction::Class {
+            ranges: ranges.clone(),
+            negated: *negated,
+        },
+        Node::Start => Instruction::Start,
+        Node::End => Instruction::End,
+        Node::WordBoundary => Instruction::WordBoundary,
+        Node::Group(alternatives) => return compile_alternatives(alternatives, program),
+        Node::Repeat { node, min, max } => return compile_repeat(node, *min, *max, program),
+    };
+
+    program.push(instruction);
+}
+
+/// Compiles the required matches of a repeat one after the other, followed by a loop for an
+/// unlimited repeat, or by optional matches up to the maximum.
+fn compile_repeat(node: &Node, min: usize, max: Option<usize>, program: &mut Vec<Instruction>) {
+    for _ in 0..min {
+        compile_node(node, program);
+    }
+
+    match max {
+        None => {
+            let split = program.len();
+            program.push(Instruction::Split(split + 1, 0));
+            compile_node(node, program);
+            program.push(Instruction::Jump(split));
+            program[split] = Instruction::Split(split + 1, program.len());
+        }
+        Some(max) => {
+            for _ in min..max {
+                let split = program.len();
+                program.push(Instruction::Split(split + 1, 0));
+                compile_node(node, program);
+                program[split] = Instruction::Split(split + 1, program.len());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::Pattern;
+
+    fn matches(pattern: &str, text: &str) -> bool {
+        Pattern::parse(pattern)
+            .unwrap_or_else(|err| panic!("{pattern} is invalid: {err}"))
+            .is_match(text)
+    }
+
+    #[test]
+    fn literals_ignore_case() {
+        assert!(matches("standup", "Daily STANDUP call"));
+        assert!(matches("ÜBER", "über alles"));
+        assert!(!matches("standup", "stand up"));
+        assert!(matches("", "anything"));
+    }
+
+    #[test]
+    fn anchors() {
+        assert!(matches("^fix", "Fix the build"));
+        assert!(!matches("^fix", "Hotfix"));
+        assert!(matches("review$", "Code review"));
+        assert!(!matches("review$", "Review notes"));
+        assert!(matches("^$", ""));
+    }
+
+    #[test]
+    fn alternatives_and_groups() {
+        assert!(matches("meeting|call|sync", "Sync with design"));
+        assert!(!matches("meeting|call|sync", "Write docs"));
+        assert!(matches("^(code|pr) review$", "PR review"));
+        assert!(!matches("^(code|pr) review$", "Design review"));
+        assert!(matches("a(b|c(d|e))f", "acef"));
+    }
+
+    #[test]
+    fn classes_and_escapes() {
+        assert!(matches("[0-9]+", "Ticket 42"));
+        assert!(!matches("^[^0-9]+$", "Ticket 42"));
+        assert!(matches("^[a-c]x$", "Bx"));
+        assert!(matches("\\d\\d", "v10"));
+        assert!(!matches("\\d\\d", "v1"));
+        assert!(matches("\\w+\\s\\w+", "two words"));
+        assert!(matches("1\\.5", "1.5"));
+        assert!(!matches("1\\.5", "125"));
+        assert!(matches("a.c", "abc"));
+    }
+
+    #[test]
+    fn word_boundaries() {
+        assert!(matches("\\bbug\\b", "Fix a bug today"));
+        assert!(matches("\\bbug\\b", "bug"));
+        assert!(!matches("\\bbug\\b", "Debugging"));
+        assert!(!matches("\\bbug\\b", "bugs"));
+    }
+
+    #[test]
+    fn quantifiers() {
+        assert!(matches("^colou?r$", "color"));
+        assert!(matches("^colou?r$", "colour"));
+        assert!(!matches("^colou?r$", "colouur"));
+        assert!(matches("^ab*c$", "ac"));
+        assert!(matches("^ab*c$", "abbbc"));
+        assert!(!matches("^ab+c$", "ac"));
+        assert!(matches("^(ab)+$", "ababab"));
+        assert!(!matches("^(ab)+$", "ababa"));
+
+        // Greedy repeats give back characters when the rest would not match otherwise.
+        assert!(matches("^a*ab$", "aaab"));
+        assert!(matches("^.*review$", "code review"));
+    }
+
+    #[test]
+    fn nested_quantifiers_stay_fast() {
+        let started = Instant::now();
+        let words = "fix the flaky login test in ci today ".repeat(20);
+
+        assert!(matches("^(\\w+\\s?)*$", words.trim_end()));
+        assert!(!matches("^(\\w+\\s?)*$", &format!("{words}!")));
+        assert!(!matches("^(a*)*b$", &"a".repeat(1000)));
+        assert!(matches("^(a|aa)+$", &"a".repeat(1000)));
+        assert!(matches("(x+x+)+y", &format!("{}y", "x".repeat(500))));
+
+        // Backtracking would take longer than the age of the universe on these.
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        for pattern in ["(unclosed", "unopened)", "[a-", "*start", "a**"] {
+            assert!(Pattern::parse(pattern).is_err(), "{pattern}");
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{log, Result};
+
+/// Finds the executable `hat-<name>` on the `PATH`, which runs as `hat <name>`.
+pub fn find(name: &str) -> Option<PathBuf> {
+    if name.is_empty() || name.starts_with('-') || name.contains(['/', '\\']) {
+        return None;
+    }
+
+    let file = format!("hat-{name}{}", env::consts::EXE_SUFFIX);
+
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(&file))
+        .find(|path| is_executable(path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Runs a plugin and returns its exit code.
+///
+/// Plugins receive the path of the data file in `HAT_DATA_FILE`, the active project in
+/// `HAT_ACTIVE_PROJECT`, and the path of the `hat` executable in `HAT_EXE`.
+pub fn run(
+    program: &Path,
+    args: impl IntoIterator<Item = String>,
+    data_path: &Path,
+    active_project: Option<&str>,
+) -> Result<i32> {
+    log::debug!("Running plugin {}.", program.display());
+
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .env("HAT_DATA_FILE", data_path)
+        .env("HAT_ACTIVE_PROJECT", active_project.unwrap_or_default());
+
+    if let Ok(exe) = env::current_exe() {
+        command.env("HAT_EXE", exe);
+    }
+
+    Ok(command.status()?.code().unwrap_or(1))
+}
@@ -0,0 +1,74 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use crate::{date, LoggedTime};
+
+/// Removes the entries starting before `before`. If `summarize` is set, they are replaced
+/// by one summary entry per month so that totals are preserved. Entries on different invoices
+/// or with a different billable override are summarized separately, so that the summaries are
+/// invoiced and billed like the entries were.
+/// Returns the number of entries removed.
+pub fn prune(logged_times: &mut Vec<LoggedTime>, before: Duration, summarize: bool) -> usize {
+    let (old, kept): (Vec<LoggedTime>, Vec<LoggedTime>) = logged_times
+        .drain(..)
+        .partition(|time| time.start_epoch < before);
+
+    let removed = old.len();
+
+    if summarize {
+        let mut summaries: BTreeMap<(i64, u32, Option<u64>, Option<bool>), Summary> =
+            BTreeMap::new();
+
+        for time in old {
+            let (year, month, _) = date::civil_from_days(date::day_number(time.start_epoch));
+            let summary = summaries
+                .entry((year, month, time.invoice, time.billable))
+                .or_insert(Summary {
+                    start_epoch: time.start_epoch,
+                    duration: Duration::ZERO,
+                    rounded: None,
+                    count: 0,
+                });
+
+            // Rounded entries are billed by their rounded duration, and the others by theirs.
+            if time.rounded.is_some() || summary.rounded.is_some() {
+                let rounded = summary.rounded.unwrap_or(summary.duration);
+                summary.rounded = Some(rounded + time.rounded.unwrap_or(time.duration));
+            }
+
+            summary.start_epoch = summary.start_epoch.min(time.start_epoch);
+            summary.duration += time.duration;
+            summary.count += 1;
+        }
+
+        for ((year, month, invoice, billable), summary) in summaries {
+            logged_times.push(LoggedTime {
+                start_epoch: summary.start_epoch,
+                duration: summary.duration,
+                description: format!(
+                    "Summary of {} entries for {year:04}-{month:02}",
+                    summary.count
+                ),
+                notes: None,
+                tags: Vec::new(),
+                billable,
+                rounded: summary.rounded,
+                invoice,
+                tickets: Vec::new(),
+            });
+        }
+
+        logged_times.sort_by_key(|time| time.start_epoch);
+    }
+
+    logged_times.extend(kept);
+    removed
+}
+
+/// The entries of a month that are summarized together.
+struct Summary {
+    /// The start of the first entry.
+    start_epoch: Duration,
+    duration: Duration,
+    rounded: Option<Duration>,
+    count: usize,
+}
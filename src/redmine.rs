@@ -0,0 +1,109 @@
+use std::{
+    collections::HashMap,
+    env,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{log, tempfile, Error, Result};
+
+/// Where `sync redmine` creates time entries, read from the config file.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedmineConfig {
+    /// The Redmine instance, such as `https://redmine.example.com`.
+    pub url: String,
+
+    /// The API key shown on the account page. Defaults to the `REDMINE_API_KEY` environment
+    /// variable.
+    pub api_key: Option<String>,
+
+    /// The Redmine project and activity of each project, keyed by name. Entries of other
+    /// projects are not synced.
+    pub projects: HashMap<String, RedmineProject>,
+}
+
+/// The Redmine project that a project's time is logged to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedmineProject {
+    /// The identifier of the Redmine project, such as `citizen-portal`.
+    pub project: String,
+
+    /// The ID of the activity that time entries are logged as, such as `9` for development.
+    pub activity_id: u64,
+}
+
+/// A time entry to create for an entry that mentions a Redmine issue.
+#[derive(Debug, Clone)]
+pub struct TimeEntry<'a> {
+    pub project: &'a RedmineProject,
+    pub issue_id: u64,
+    pub spent_on: String,
+    pub minutes: i64,
+    pub comments: &'a str,
+}
+
+impl RedmineConfig {
+    /// Creates a time entry with Redmine's REST API.
+    pub fn create_time_entry(&self, entry: &TimeEntry) -> Result<()> {
+        let url = format!("{}/time_entries.json", self.url.trim_end_matches('/'));
+
+        let body = json!({
+            "time_entry": {
+                "project_id": entry.project.project,
+                "issue_id": entry.issue_id,
+                "spent_on": entry.spent_on,
+                "hours": format!("{:.2}", entry.minutes as f64 / 60.0),
+                "activity_id": entry.project.activity_id,
+                "comments": entry.comments,
+            }
+        });
+
+        log::debug!("Sending {body} to {url}.");
+
+        // The key goes in a config file rather than the arguments, which anyone can see.
+        let header = self
+            .api_key
+            .clone()
+            .or_else(|| env::var("REDMINE_API_KEY").ok())
+            .map(|api_key| format!("X-Redmine-API-Key: {api_key}"));
+
+        let options: Vec<(&str, &str)> = header
+            .iter()
+            .map(|header| ("header", header.as_str()))
+            .collect();
+
+        let config = tempfile::curl_config(&options)?;
+
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--request", "POST"])
+            .args(["--header", "Content-Type: application/json"])
+            .arg("--config")
+            .arg(config.path())
+            .args(["--data-binary", "@-"])
+            .arg(&url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(body.to_string().as_bytes())?;
+
+        let output = child.wait_with_output()?;
+
+        if !output.status.success() {
+            return Err(Error::Redmine(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    log,
+    tempfile::{self, TempFile},
+    Error, Result,
+};
+
+/// The kind of server the data file is synchronized with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteKind {
+    WebDav,
+    S3,
+}
+
+/// Where to synchronize the data file to, read from the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteConfig {
+    pub kind: RemoteKind,
+
+    /// The full URL of the remote file, including the bucket for S3.
+    pub url: String,
+
+    /// The WebDAV username, or the S3 access key.
+    pub username: Option<String>,
+
+    /// The WebDAV password, or the S3 secret key.
+    pub password: Option<String>,
+
+    /// The S3 region, which defaults to `us-east-1`.
+    pub region: Option<String>,
+}
+
+impl RemoteConfig {
+    /// A curl command for the remote, with the credentials in a config file that must be kept
+    /// until the command finishes.
+    fn curl(&self) -> Result<(Command, TempFile)> {
+        let user = self.username.as_ref().map(|username| {
+            let password = self.password.as_deref().unwrap_or_default();
+            format!("{username}:{password}")
+        });
+
+        let options: Vec<(&str, &str)> = user.iter().map(|user| ("user", user.as_str())).collect();
+        let config = tempfile::curl_config(&options)?;
+
+        let mut command = Command::new("curl");
+        command
+            .args(["--silent", "--show-error"])
+            .arg("--config")
+            .arg(config.path());
+
+        if self.kind == RemoteKind::S3 {
+            let region = self.region.as_deref().unwrap_or("us-east-1");
+            command
+                .arg("--aws-sigv4")
+                .arg(format!("aws:amz:{region}:s3"));
+        }
+
+        Ok((command, config))
+    }
+
+    /// Downloads the remote file, or returns `None` if it does not exist yet.
+    pub fn download(&self) -> Result<Option<Vec<u8>>> {
+        log::debug!("Downloading {} from the remote.", self.url);
+
+        let (mut command, _config) = self.curl()?;
+        let output = command
+            .args(["--write-out", "\n%{http_code}", "--url", &self.url])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(Error::Remote(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        let mut body = output.stdout;
+        let split = body.iter().rposition(|&byte| byte == b'\n').unwrap_or(0);
+        let status = String::from_utf8_lossy(&body[split..]).trim().to_string();
+        body.truncate(split);
+
+        log::debug!("The remote responded with {status}.");
+
+        match status.as_str() {
+            "404" => Ok(None),
+            status if status.starts_with('2') => Ok(Some(body)),
+            status => Err(Error::Remote(format!(
+                "The server responded with {status}."
+            ))),
+        }
+    }
+
+    /// Uploads the file, replacing the remote copy.
+    pub fn upload(&self, bytes: &[u8]) -> Result<()> {
+        log::debug!("Uploading {} bytes to {}.", bytes.len(), self.url);
+
+        let (mut command, _config) = self.curl()?;
+        let mut child = command
+            .args([
+                "--fail",
+                "--request",
+                "PUT",
+                "--data-binary",
+                "@-",
+                "--url",
+                &self.url,
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(bytes)?;
+
+        let output = child.wait_with_output()?;
+
+        if !output.status.success() {
+            return Err(Error::Remote(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+//! Templates for the output of `report` and `invoice`, in a subset of Handlebars. Values are
+//! written with `{{name}}` or `{{project.name}}`, lists are repeated with `{{#each entries}}`
+//! and `{{/each}}`, and parts are shown conditionally with `{{#if billed}}`, `{{#unless ...}}`,
+//! and `{{else}}`. Inside `each`, `{{this}}` is the current item and `{{@number}}` counts from
+//! one. `{{! ... }}` is a comment. A line holding only a block tag or comment is left out of
+//! the output, so that templates can be laid out readably.
+
+use std::{fs, path::Path};
+
+use serde_json::Value;
+
+use crate::{Error, Result};
+
+/// A parsed template.
+#[derive(Debug)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Value(String),
+    Each {
+        path: String,
+        body: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    If {
+        path: String,
+        negated: bool,
+        body: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+#[derive(Clone)]
+enum Token {
+    Text(String),
+    Tag(String),
+}
+
+impl Template {
+    /// Reads and parses the template file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path)?;
+
+        Self::parse(&source).map_err(|reason| Error::InvalidTemplate(path.to_path_buf(), reason))
+    }
+
+    fn parse(source: &str) -> std::result::Result<Self, String> {
+        let mut tokens = tokenize(source)?.into_iter();
+        let (nodes, end) = parse_nodes(&mut tokens)?;
+
+        match end {
+            None => Ok(Self { nodes }),
+            Some(tag) => Err(format!("unexpected {{{{{tag}}}}}")),
+        }
+    }
+
+    /// Renders the template with `context` as the outermost value that names are looked up in.
+    pub fn render(&self, context: &Value) -> String {
+        let mut out = String::new();
+        render_nodes(&self.nodes, &mut vec![context.clone()], &mut out);
+        out
+    }
+}
+
+/// Splits the source into text and the contents of tags, dropping the lines of standalone
+/// block tags and comments.
+fn tokenize(source: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..].find("}}").ok_or("unclosed {{")?;
+
+        tokens.push(Token::Text(rest[..start].to_string()));
+        tokens.push(Token::Tag(rest[start + 2..start + end].trim().to_string()));
+        rest = &rest[start + end + 2..];
+    }
+
+    tokens.push(Token::Text(rest.to_string()));
+
+    // Whether each tag is alone on its line, decided before any text is trimmed. Text always
+    // comes before and after a tag, since text is pushed between every two tags.
+    let standalone: Vec<bool> = (0..tokens.len())
+        .map(|index| {
+            let Token::Tag(tag) = &tokens[index] else {
+                return false;
+            };
+
+            let (Token::Text(before), Token::Text(after)) =
+                (&tokens[index - 1], &tokens[index + 1])
+            else {
+                return false;
+            };
+
+            let line_start = before.rsplit('\n').next().unwrap_or_default();
+            let line_end = after.split('\n').next().unwrap_or_default();
+
+            is_block(tag)
+                && (before.contains('\n') || index == 1)
+                && (after.contains('\n') || index == tokens.len() - 2)
+                && line_start.trim().is_empty()
+                && line_end.trim().is_empty()
+        })
+        .collect();
+
+    let mut trimmed = Vec::with_capacity(tokens.len());
+
+    for (index, token) in tokens.iter().enumerate() {
+        let Token::Text(text) = token else {
+            trimmed.push(token.clone());
+            continue;
+        };
+
+        // Text after a standalone tag loses the rest of that line, and text before one loses
+        // the start of the tag's line.
+        let start = match index > 0 && standalone[index - 1] {
+            true => text.find('\n').map_or(text.len(), |newline| newline + 1),
+            false => 0,
+        };
+        let end = match standalone.get(index + 1) == Some(&true) {
+            true => text.rfind('\n').map_or(0, |newline| newline + 1),
+            false => text.len(),
+        };
+
+        trimmed.push(Token::Text(text[start..end.max(start)].to_string()));
+    }
+
+    Ok(trimmed)
+}
+
+fn is_block(tag: &str) -> bool {
+    tag.starts_with(['#', '/', '!']) || tag == "else"
+}
+
+/// Parses nodes until the end of the source or a closing or `else` tag, which is returned.
+fn parse_nodes(
+    tokens: &mut impl Iterator<Item = Token>,
+) -> std::result::Result<(Vec<Node>, Option<String>), String> {
+    let mut nodes = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        let tag = match token {
+            Token::Text(text) if text.is_empty() => continue,
+            Token::Text(text) => {
+                nodes.push(Node::Text(text));
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+
+        if tag.starts_with('!') {
+            continue;
+        }
+
+        if tag.starts_with('/') || tag == "else" {
+            return Ok((nodes, Some(tag)));
+        }
+
+        let Some(block) = tag.strip_prefix('#') else {
+            nodes.push(Node::Value(tag));
+            continue;
+        };
+
+        let (helper, path) = block.split_once(char::is_whitespace).unwrap_or((block, ""));
+        let path = path.trim().to_string();
+
+        if !matches!(helper, "each" | "if" | "unless") {
+            return Err(format!("unknown block {{{{#{helper}}}}}"));
+        }
+
+        if path.is_empty() {
+            return Err(format!("{{{{#{helper}}}}} needs a value"));
+        }
+
+        let (body, end) = parse_nodes(tokens)?;
+
+        let (otherwise, end) = match end.as_deref() {
+            Some("else") => parse_nodes(tokens)?,
+            _ => (Vec::new(), end),
+        };
+
+        if end.as_deref() != Some(&format!("/{helper}")) {
+            return Err(format!("unclosed {{{{#{helper} {path}}}}}"));
+        }
+
+        nodes.push(match helper {
+            "each" => Node::Each {
+                path,
+                body,
+                otherwise,
+            },
+            _ => Node::If {
+                path,
+                negated: helper == "unless",
+                body,
+                otherwise,
+            },
+        });
+    }
+
+    Ok((nodes, None))
+}
+
+fn render_nodes(nodes: &[Node], scopes: &mut Vec<Value>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Value(path) => out.push_str(&display(&lookup(scopes, path))),
+            Node::Each {
+                path,
+                body,
+                otherwise,
+            } => {
+                let items = match lookup(scopes, path) {
+                    Value::Array(items) => items,
+                    Value::Object(fields) => fields.into_iter().map(|(_, value)| value).collect(),
+                    _ => Vec::new(),
+                };
+
+                if items.is_empty() {
+                    render_nodes(otherwise, scopes, out);
+                }
+
+                for (index, item) in items.into_iter().enumerate() {
+                    // The position is kept in a scope of its own, so that items which aren't
+                    // objects are still visible as `this`.
+                    scopes.push(serde_json::json!({ "@index": index, "@number": index + 1 }));
+                    scopes.push(item);
+                    render_nodes(body, scopes, out);
+                    scopes.truncate(scopes.len() - 2);
+                }
+            }
+            Node::If {
+                path,
+                negated,
+                body,
+                otherwise,
+            } => {
+                if is_truthy(&lookup(scopes, path)) != *negated {
+                    render_nodes(body, scopes, out);
+                } else {
+                    render_nodes(otherwise, scopes, out);
+                }
+            }
+        }
+    }
+}
+
+/// Looks up a dotted name in the innermost scope that has its first part.
+fn lookup(scopes: &[Value], path: &str) -> Value {
+    if path == "this" || path == "." {
+        return scopes.last().cloned().unwrap_or(Value::Null);
+    }
+
+    let path = path.strip_prefix("this.").unwrap_or(path);
+    let mut parts = path.split('.');
+    let first = parts.next().unwrap_or_default();
+
+    let Some(mut value) = scopes.iter().rev().find_map(|scope| scope.get(first)) else {
+        return Value::Null;
+    };
+
+    for part in parts {
+        match value.get(part) {
+            Some(next) => value = next,
+            None => return Value::Null,
+        }
+    }
+
+    value.clone()
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(display).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_f64() != Some(0.0),
+        Value::String(text) => !text.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(_) => true,
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use hat_changer::date;
+use serde::{Deserialize, Serialize};
+
+use crate::{log, when::WEEKDAYS, Error, Result};
+
+/// A job that `hat daemon` runs at set times, such as a weekly report every Friday.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    /// When the job runs, in UTC, such as `friday 17:00`, `weekdays 09:00`, or `18:30` for
+    /// every day.
+    pub when: String,
+
+    /// The arguments of the `hat` command to run, such as `["report", "--uninvoiced"]`.
+    #[serde(default)]
+    pub command: Vec<String>,
+
+    /// A shell command run after the `hat` command, which gets its output on stdin.
+    #[serde(default)]
+    pub hook: Option<String>,
+}
+
+/// The days and time of day a job runs at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Schedule {
+    /// Whether the job runs on each weekday, starting from Monday.
+    days: [bool; 7],
+    time_of_day: Duration,
+}
+
+impl Schedule {
+    /// Parses a time of day, optionally after the days to run on: `daily`, `weekdays`,
+    /// `weekends`, or weekday names separated by commas, such as `mon,wed`.
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid = || Error::InvalidSchedule(text.to_string());
+        let text = text.trim().to_lowercase();
+
+        let (days, time) = match text.rsplit_once(char::is_whitespace) {
+            Some((days, time)) => (days.trim(), time),
+            None => ("daily", text.as_str()),
+        };
+
+        let time_of_day = date::parse_time_of_day(time)
+            .filter(|time| time.as_secs() < 24 * 60 * 60)
+            .ok_or_else(invalid)?;
+
+        let days = match days {
+            "daily" | "every day" => [true; 7],
+            "weekdays" => [true, true, true, true, true, false, false],
+            "weekends" => [false, false, false, false, false, true, true],
+            names => {
+                let mut days = [false; 7];
+
+                for name in names.split(',').map(str::trim) {
+                    let name = name
+                        .strip_suffix('s')
+                        .filter(|name| name.len() >= 6)
+                        .unwrap_or(name);
+
+                    let weekday = WEEKDAYS
+                        .iter()
+                        .position(|day| name.len() >= 3 && day.starts_with(name))
+                        .ok_or_else(invalid)?;
+
+                    days[weekday] = true;
+                }
+
+                days
+            }
+        };
+
+        Ok(Self { days, time_of_day })
+    }
+
+    /// The first time after `now` that the job runs.
+    pub fn next_after(&self, now: Duration) -> Duration {
+        let today = date::day_number(now);
+
+        (today..=today + 7)
+            .filter(|&day| {
+                // The epoch was a Thursday, three days after a Monday.
+                self.days[(day + 3).rem_euclid(7) as usize]
+            })
+            .map(|day| date::start_of_day(day) + self.time_of_day)
+            .find(|&time| time > now)
+            .expect("every schedule runs at least once a week")
+    }
+}
+
+/// Runs the `hat` command of a job with the executable `exe`, then its hook with the command's
+/// output. Without a hook, the output is printed.
+pub fn run_job(exe: &Path, job: &ScheduledJob) -> Result<()> {
+    log::debug!("Running the job scheduled for {}.", job.when);
+
+    let output = match job.command.is_empty() {
+        true => Vec::new(),
+        false => {
+            let output = Command::new(exe)
+                .args(&job.command)
+                .stderr(Stdio::inherit())
+                .output()?;
+
+            if !output.status.success() {
+                return Err(Error::JobFailed(
+                    job.when.clone(),
+                    output.status.to_string(),
+                ));
+            }
+
+            output.stdout
+        }
+    };
+
+    let Some(hook) = &job.hook else {
+        io::stdout().write_all(&output)?;
+        return Ok(());
+    };
+
+    let mut child = Command::new(if cfg!(windows) { "cmd" } else { "sh" })
+        .arg(if cfg!(windows) { "/C" } else { "-c" })
+        .arg(hook)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    // A hook that doesn't read its input closes the pipe, which isn't an error.
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(&output);
+    }
+
+    let status = child.wait()?;
+
+    if !status.success() {
+        return Err(Error::JobFailed(job.when.clone(), status.to_string()));
+    }
+
+    Ok(())
+}
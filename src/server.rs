@@ -0,0 +1,317 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    compress, crypto, log, msgpack,
+    storage::Format,
+    tempfile::{self, TempFile},
+    Error, ProjectList, Result,
+};
+
+/// The largest request body the server reads, which is far more than any data file needs.
+const MAX_BODY_LENGTH: usize = 64 * 1024 * 1024;
+
+/// The most the server reads of the request line and headers.
+const MAX_HEAD_LENGTH: u64 = 64 * 1024;
+
+/// How long the server waits for a client, since it serves one connection at a time and a
+/// stalled one would hold up everyone else.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A shared server to store the data file on instead of the local disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerConfig {
+    /// The base URL of the server, such as `http://hat.example.com:7878`.
+    pub url: String,
+
+    /// The token identifying the user to the server.
+    pub token: String,
+}
+
+impl ServerConfig {
+    /// A curl command for a path on the server, with the token in a config file that must be
+    /// kept until the command finishes.
+    fn curl(&self, path: &str) -> Result<(Command, TempFile)> {
+        log::debug!("Requesting {}{path} from the server.", self.url);
+
+        let authorization = format!("Authorization: Bearer {}", self.token);
+        let config = tempfile::curl_config(&[("header", &authorization)])?;
+
+        let mut command = Command::new("curl");
+        command
+            .args(["--silent", "--show-error", "--fail"])
+            .arg("--config")
+            .arg(config.path())
+            .arg("--url")
+            .arg(format!("{}{path}", self.url.trim_end_matches('/')));
+
+        Ok((command, config))
+    }
+
+    fn get(&self, path: &str) -> Result<Vec<u8>> {
+        let (mut command, _config) = self.curl(path)?;
+        let output = command.output()?;
+
+        if !output.status.success() {
+            return Err(Error::Server(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        Ok(output.stdout)
+    }
+
+    /// Downloads the user's data file from the server.
+    pub fn fetch(&self) -> Result<Vec<u8>> {
+        self.get("/data")
+    }
+
+    /// Downloads the data of every user on the server, keyed by user name.
+    pub fn fetch_team(&self) -> Result<HashMap<String, ProjectList>> {
+        let bytes = self.get("/team")?;
+        serde_json::from_slice(&bytes).map_err(|err| Error::Server(err.to_string()))
+    }
+
+    /// Uploads the user's data file to the server, replacing the stored copy.
+    pub fn store(&self, bytes: &[u8]) -> Result<()> {
+        let (mut command, _config) = self.curl("/data")?;
+
+        let mut child = command
+            .args(["--request", "PUT", "--data-binary", "@-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(bytes)?;
+
+        let output = child.wait_with_output()?;
+
+        if !output.status.success() {
+            return Err(Error::Server(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    token: Option<String>,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+}
+
+/// Serves the data files in `dir` over HTTP until the process is stopped.
+///
+/// Users are listed in `users.json` in the directory, which maps tokens to user names.
+/// Each user's data is stored in a file named after them.
+pub fn serve(addr: &str, dir: &Path) -> Result<()> {
+    let listener = TcpListener::bind(addr)?;
+
+    println!(
+        "{}",
+        format!("Listening on {}.", addr.bright_cyan()).bright_green()
+    );
+
+    for stream in listener.incoming() {
+        let result = stream.and_then(|stream| handle_connection(stream, dir));
+
+        if let Err(err) = result {
+            eprintln!("{}", err.to_string().bright_yellow());
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream, dir: &Path) -> io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+
+    let response = respond(&mut stream, dir)
+        .unwrap_or_else(|err| Response::new("500 Internal Server Error", err.to_string()));
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.body.len()
+    )?;
+    stream.write_all(&response.body)?;
+    stream.flush()
+}
+
+/// Reads a request and routes it. The token is checked before the body is read, so that
+/// unknown clients can't make the server read or allocate anything large.
+fn respond(stream: &mut TcpStream, dir: &Path) -> Result<Response> {
+    let mut reader = BufReader::new(stream);
+    let (mut request, length) = read_head(&mut (&mut reader).take(MAX_HEAD_LENGTH))?;
+
+    let users = read_users(dir)?;
+
+    let Some(user) = request.token.as_ref().and_then(|token| users.get(token)) else {
+        return Ok(Response::new("401 Unauthorized", "Unknown token."));
+    };
+
+    if length > MAX_BODY_LENGTH {
+        return Ok(Response::new(
+            "413 Content Too Large",
+            "The data file is too large.",
+        ));
+    }
+
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body)?;
+
+    route(&request, user, &users, dir)
+}
+
+/// Reads the request line and headers, returning the request without its body and the length
+/// of the body.
+fn read_head(reader: &mut impl BufRead) -> io::Result<(Request, usize)> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut token = None;
+    let mut length = 0;
+
+    loop {
+        let mut header = String::new();
+
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+
+        let value = value.trim();
+
+        match name.trim().to_lowercase().as_str() {
+            "content-length" => length = value.parse().unwrap_or_default(),
+            "authorization" => token = value.strip_prefix("Bearer ").map(str::to_string),
+            _ => {}
+        }
+    }
+
+    let request = Request {
+        method,
+        path,
+        token,
+        body: Vec::new(),
+    };
+
+    Ok((request, length))
+}
+
+/// The users of the server, keyed by their tokens.
+fn read_users(dir: &Path) -> Result<HashMap<String, String>> {
+    match fs::read_to_string(dir.join("users.json")) {
+        Ok(text) => serde_json::from_str(&text)
+            .map_err(|err| Error::InvalidData(dir.join("users.json"), err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn route(
+    request: &Request,
+    user: &str,
+    users: &HashMap<String, String>,
+    dir: &Path,
+) -> Result<Response> {
+    let file = dir.join(format!("{user}.json"));
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/data") => match fs::read(&file) {
+            Ok(bytes) => Ok(Response::new("200 OK", bytes)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Response::new(
+                "200 OK",
+                serde_json::to_vec(&ProjectList::default())
+                    .expect("Could not serialize JSON file."),
+            )),
+            Err(err) => Err(err.into()),
+        },
+        ("PUT", "/data") => {
+            let valid = crypto::is_encrypted(&request.body) || parse_list(&request.body).is_some();
+
+            if !valid {
+                return Ok(Response::new("400 Bad Request", "Invalid data file."));
+            }
+
+            fs::write(&file, &request.body)?;
+            Ok(Response::new("204 No Content", Vec::new()))
+        }
+        ("GET", "/team") => {
+            let mut team = HashMap::new();
+
+            for user in users.values() {
+                // Encrypted data files can't be read by the server, so they are left out.
+                let Ok(bytes) = fs::read(dir.join(format!("{user}.json"))) else {
+                    continue;
+                };
+
+                if let Some(list) = parse_list(&bytes) {
+                    team.insert(user.clone(), list);
+                }
+            }
+
+            Ok(Response::new(
+                "200 OK",
+                serde_json::to_vec(&team).expect("Could not serialize JSON file."),
+            ))
+        }
+        _ => Ok(Response::new("404 Not Found", "Unknown endpoint.")),
+    }
+}
+
+/// Parses a data file that is not encrypted, in either format and compressed or not.
+fn parse_list(bytes: &[u8]) -> Option<ProjectList> {
+    let bytes = if compress::is_compressed(bytes) {
+        Cow::Owned(compress::decompress(bytes).ok()?)
+    } else {
+        Cow::Borrowed(bytes)
+    };
+
+    match Format::detect(&bytes) {
+        Format::Json => serde_json::from_slice(&bytes).ok(),
+        Format::MessagePack => msgpack::from_slice(&bytes).ok(),
+    }
+}
@@ -0,0 +1,427 @@
+use std::{
+    borrow::Cow,
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    fmt, fs,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+};
+
+use hat_changer::LoggedTime;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    compress, config::Config, crypto, log, msgpack, server::ServerConfig, Error, ProjectList,
+    Result,
+};
+
+/// How stored files are serialized. The format is detected when a file is read, so files in
+/// either format can be mixed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Pretty-printed JSON, which is easy to read and edit by hand.
+    #[default]
+    Json,
+
+    /// MessagePack, a binary format that is much smaller and faster to read for large histories.
+    #[value(name = "msgpack")]
+    MessagePack,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json => write!(f, "JSON"),
+            Self::MessagePack => write!(f, "MessagePack"),
+        }
+    }
+}
+
+impl Format {
+    /// The format of some serialized data. JSON always starts with an object or array, whose
+    /// opening characters can't start a MessagePack map or array.
+    pub fn detect(bytes: &[u8]) -> Self {
+        match bytes.iter().find(|byte| !byte.is_ascii_whitespace()) {
+            Some(b'{' | b'[') | None => Self::Json,
+            Some(_) => Self::MessagePack,
+        }
+    }
+}
+
+/// Reads and writes the data file, encrypting it if needed.
+/// If a server is configured, the data file is stored there instead of on disk.
+pub struct Storage {
+    path: PathBuf,
+    identity: Option<PathBuf>,
+    server: Option<ServerConfig>,
+    encrypted: bool,
+    format: Format,
+
+    /// The directory each project's entries are stored in when they are split from the data
+    /// file, which then only keeps the projects' names, settings, and timers.
+    projects_dir: PathBuf,
+    split: bool,
+
+    /// A hash of each project file as it was loaded, so that unchanged files aren't rewritten.
+    loaded: HashMap<String, u64>,
+
+    /// Whether the project files were read, which they must be before they are written.
+    entries_loaded: bool,
+}
+
+impl Storage {
+    pub fn new(config: &Config, home: &Path) -> Self {
+        Self {
+            path: config.data_path(home),
+            identity: config.age_identity.clone(),
+            server: config.server.clone(),
+            encrypted: false,
+            format: Format::Json,
+            projects_dir: config.projects_dir(home),
+            split: config.split_projects,
+            loaded: HashMap::new(),
+            entries_loaded: true,
+        }
+    }
+
+    /// The files that make up the stored data: the data file, and the directory of project
+    /// files if entries are split from it.
+    pub fn paths(&self) -> Vec<&Path> {
+        if self.split && self.projects_dir.is_dir() {
+            vec![&self.path, &self.projects_dir]
+        } else {
+            vec![&self.path]
+        }
+    }
+
+    /// The path of the data file, which is also used for backups when a server is configured.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the data file is encrypted.
+    pub fn encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    /// Sets whether the data file is encrypted the next time it is saved.
+    pub fn set_encrypted(&mut self, encrypted: bool) -> Result<()> {
+        if encrypted && self.identity.is_none() {
+            return Err(Error::NoIdentity);
+        }
+
+        self.encrypted = encrypted;
+        Ok(())
+    }
+
+    /// Whether every entry was read, rather than only the data file without the project files.
+    pub fn entries_loaded(&self) -> bool {
+        self.entries_loaded
+    }
+
+    /// The format the data file is stored in.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Sets the format every stored file is written in the next time it is saved.
+    pub fn set_format(&mut self, format: Format) {
+        self.format = format;
+
+        // Unchanged project files must be rewritten too.
+        self.loaded.clear();
+    }
+
+    /// Loads the data file without reading the project files, for commands that only need
+    /// projects and their timers. When entries are split from the data file, this skips
+    /// reading every entry, and saving leaves the project files alone.
+    pub fn load_index(&mut self) -> Result<ProjectList> {
+        if !self.split || self.server.is_some() {
+            return self.load();
+        }
+
+        let mut list = self.read_data_file()?;
+
+        // Data files from older versions don't have the totals that listing projects needs.
+        if list
+            .projects
+            .values()
+            .any(|project| project.cached_total.is_none())
+        {
+            log::debug!("Some totals aren't cached yet, so every entry is read.");
+            self.load_projects(&mut list)?;
+            return Ok(list);
+        }
+
+        self.entries_loaded = false;
+        Ok(list)
+    }
+
+    /// Loads the data file, or an empty project list if it does not exist yet.
+    pub fn load(&mut self) -> Result<ProjectList> {
+        let mut list = self.read_data_file()?;
+
+        if self.server.is_none() {
+            self.load_projects(&mut list)?;
+        }
+
+        Ok(list)
+    }
+
+    /// Reads and decodes the data file, from the server if one is configured.
+    fn read_data_file(&mut self) -> Result<ProjectList> {
+        if let Some(server) = &self.server {
+            log::debug!("Loading the data file from the server at {}.", server.url);
+            let bytes = server.fetch()?;
+            self.encrypted = crypto::is_encrypted(&bytes);
+            let (list, format) = self.parse(&bytes, &self.path)?;
+            self.format = format;
+            return Ok(list);
+        }
+
+        log::debug!("Reading data file {}.", self.path.display());
+
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                log::debug!("The data file does not exist yet.");
+                return Ok(ProjectList::default());
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        self.encrypted = crypto::is_encrypted(&bytes);
+        let (list, format) = self.parse(&bytes, &self.path)?;
+        self.format = format;
+        Ok(list)
+    }
+
+    /// Reads the entries of every project that has its own file. Files are read even when
+    /// entries are no longer split, so that they are moved back into the data file.
+    fn load_projects(&mut self, list: &mut ProjectList) -> Result<()> {
+        if !self.projects_dir.is_dir() {
+            return Ok(());
+        }
+
+        for (name, project) in list.projects.iter_mut() {
+            let path = self.projects_dir.join(file_name(name));
+
+            let bytes = match fs::read(&path) {
+                Ok(bytes) => bytes,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            log::debug!("Read project file {}.", path.display());
+
+            let times: Vec<LoggedTime> = self.decode_file(&bytes, &path)?;
+            self.loaded.insert(name.clone(), fingerprint(&times));
+            project.logged_times.extend(times);
+        }
+
+        Ok(())
+    }
+
+    /// Parses the contents of a data file, decrypting them if needed.
+    pub fn decode(&self, bytes: &[u8]) -> Result<ProjectList> {
+        self.decode_file(bytes, &self.path)
+    }
+
+    /// Parses the contents of any stored file, decrypting them if needed.
+    fn decode_file<T: DeserializeOwned>(&self, bytes: &[u8], path: &Path) -> Result<T> {
+        self.parse(bytes, path).map(|(value, _)| value)
+    }
+
+    /// Parses the contents of any stored file, decrypting and decompressing them if needed, and
+    /// returns the format they were in.
+    fn parse<T: DeserializeOwned>(&self, bytes: &[u8], path: &Path) -> Result<(T, Format)> {
+        let mut bytes = if crypto::is_encrypted(bytes) {
+            let Some(identity) = &self.identity else {
+                return Err(Error::NoIdentity);
+            };
+            log::debug!("Decrypting with identity {}.", identity.display());
+            Cow::Owned(crypto::decrypt(identity, bytes)?)
+        } else {
+            Cow::Borrowed(bytes)
+        };
+
+        if compress::is_compressed(&bytes) {
+            log::debug!("Decompressing {}.", path.display());
+            bytes = Cow::Owned(compress::decompress(&bytes)?);
+        }
+
+        let format = Format::detect(&bytes);
+
+        let value = match format {
+            Format::Json => serde_json::from_slice(&bytes)
+                .map_err(|err| Error::InvalidData(path.to_path_buf(), err))?,
+            Format::MessagePack => msgpack::from_slice(&bytes)
+                .map_err(|err| Error::InvalidBinaryData(path.to_path_buf(), err))?,
+        };
+
+        Ok((value, format))
+    }
+
+    /// Writes the project list to the data file.
+    pub fn save(&self, list: &ProjectList) -> Result<()> {
+        if let Some(server) = &self.server {
+            log::debug!("Saving the data file to the server at {}.", server.url);
+            return server.store(&self.encode(list)?);
+        }
+
+        log::debug!("Writing data file {}.", self.path.display());
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        if !self.split {
+            fs::write(&self.path, self.encode(list)?)?;
+            return self.write_projects(&ProjectList::default());
+        }
+
+        // Without the project files, the list only has the entries the data file had.
+        if !self.entries_loaded {
+            fs::write(&self.path, self.encode(list)?)?;
+            return Ok(());
+        }
+
+        self.write_projects(list)?;
+
+        let mut index = list.clone();
+
+        for project in index.projects.values_mut() {
+            project.logged_times.clear();
+        }
+
+        fs::write(&self.path, self.encode(&index)?)?;
+        Ok(())
+    }
+
+    /// Writes the entries of each project to its own file, skipping files that haven't
+    /// changed, and removes the files of projects that no longer exist.
+    fn write_projects(&self, list: &ProjectList) -> Result<()> {
+        let mut names = HashSet::new();
+
+        if !list.projects.is_empty() {
+            fs::create_dir_all(&self.projects_dir)?;
+        }
+
+        for (name, project) in &list.projects {
+            let file = file_name(name);
+            let hash = fingerprint(&project.logged_times);
+            names.insert(file.clone());
+
+            if self.loaded.get(name) == Some(&hash) {
+                continue;
+            }
+
+            let path = self.projects_dir.join(file);
+            log::debug!("Writing project file {}.", path.display());
+
+            fs::write(&path, self.serialize(&project.logged_times, false)?)?;
+        }
+
+        let entries = match fs::read_dir(&self.projects_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+
+        for entry in entries {
+            let path = entry?.path();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+            if name.ends_with(".json") && !names.contains(name.as_ref()) {
+                log::debug!("Removing project file {}.", path.display());
+                fs::remove_file(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the project list to a backup file next to the data file, and returns its path.
+    pub fn write_backup(&self, list: &ProjectList) -> Result<PathBuf> {
+        self.write_copy(list, ".bak")
+    }
+
+    /// Writes the project list next to the data file with the given suffix, and returns its path.
+    pub fn write_copy(&self, list: &ProjectList, suffix: &str) -> Result<PathBuf> {
+        let copy = self.sibling(suffix);
+        log::debug!("Writing a copy of the data file to {}.", copy.display());
+        fs::write(&copy, self.encode(list)?)?;
+        Ok(copy)
+    }
+
+    /// The path of a file next to the data file, named after it with the given suffix.
+    pub fn sibling(&self, suffix: &str) -> PathBuf {
+        let mut path = self.path.as_os_str().to_owned();
+        path.push(suffix);
+        PathBuf::from(path)
+    }
+
+    /// Serializes the project list, encrypting it if the data file is encrypted.
+    pub fn encode(&self, list: &ProjectList) -> Result<Vec<u8>> {
+        self.serialize(list, false)
+    }
+
+    /// Serializes the project list compressed with zstd, encrypting it if the data file is
+    /// encrypted.
+    pub fn encode_compressed(&self, list: &ProjectList) -> Result<Vec<u8>> {
+        self.serialize(list, true)
+    }
+
+    /// Serializes any stored value in the data file's format, compressing it if asked, and
+    /// encrypting it if the data file is encrypted.
+    fn serialize<T: Serialize>(&self, value: &T, compressed: bool) -> Result<Vec<u8>> {
+        let mut bytes = match self.format {
+            Format::Json => {
+                serde_json::to_vec_pretty(value).expect("Could not serialize JSON file.")
+            }
+            Format::MessagePack => {
+                msgpack::to_vec(value).expect("Could not serialize MessagePack file.")
+            }
+        };
+
+        // Encrypted data doesn't compress, so it is compressed first.
+        if compressed {
+            bytes = compress::compress(&bytes)?;
+        }
+
+        match (&self.identity, self.encrypted) {
+            (Some(identity), true) => crypto::encrypt(identity, &bytes),
+            _ => Ok(bytes),
+        }
+    }
+}
+
+/// The name of the file a project's entries are stored in. Characters other than lowercase
+/// letters, digits, `-`, and `_` are escaped, so that names differing only in case don't
+/// collide on case-insensitive file systems.
+fn file_name(project: &str) -> String {
+    let mut name = String::new();
+
+    for byte in project.bytes() {
+        match byte {
+            b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' => name.push(byte as char),
+            _ => name.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    name.push_str(".json");
+    name
+}
+
+/// A hash of a project's entries, to tell whether they changed since they were loaded.
+fn fingerprint(times: &[LoggedTime]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    times.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Reads an unencrypted project list from a file, such as an export.
+pub fn read_file(path: &Path) -> Result<ProjectList> {
+    let text = fs::read_to_string(path)?;
+    serde_json::from_str(&text).map_err(|err| Error::InvalidData(path.to_path_buf(), err))
+}
@@ -0,0 +1,49 @@
+use std::collections::BTreeSet;
+
+use hat_changer::{date, ProjectList};
+
+/// Runs of consecutive weekdays with time logged. Weekends neither break a streak nor extend
+/// it, and today only counts once something is logged, so that a streak isn't broken in the
+/// morning.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Streaks {
+    pub current: usize,
+    pub longest: usize,
+}
+
+/// Finds the streaks in every project, given today as days since the epoch.
+pub fn streaks(list: &ProjectList, today: i64) -> Streaks {
+    let days: BTreeSet<i64> = list
+        .projects
+        .values()
+        .flat_map(|project| &project.logged_times)
+        .map(|time| date::day_number(time.start_epoch))
+        .filter(|&day| day <= today)
+        .collect();
+
+    let Some(&first) = days.first() else {
+        return Streaks::default();
+    };
+
+    let mut longest = 0;
+    let mut run = 0;
+
+    for day in (first..=today).filter(|&day| is_weekday(day)) {
+        if days.contains(&day) {
+            run += 1;
+            longest = longest.max(run);
+        } else if day != today {
+            run = 0;
+        }
+    }
+
+    Streaks {
+        current: run,
+        longest,
+    }
+}
+
+fn is_weekday(day: i64) -> bool {
+    // The epoch was a Thursday, three days after a Monday.
+    (day + 3).rem_euclid(7) < 5
+}
@@ -0,0 +1,25 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::LoggedTime;
+
+/// The descriptions used most often in a project, with ties broken by how recently they
+/// were used.
+pub fn descriptions(logged_times: &[LoggedTime], limit: usize) -> Vec<String> {
+    // Maps each description to how often it was used and the index it was last used at.
+    let mut usage: HashMap<&str, (usize, usize)> = HashMap::new();
+
+    for (index, time) in logged_times.iter().enumerate() {
+        let entry = usage.entry(&time.description).or_default();
+        entry.0 += 1;
+        entry.1 = index;
+    }
+
+    let mut descriptions: Vec<(&str, (usize, usize))> = usage.into_iter().collect();
+    descriptions.sort_by_key(|&(_, usage)| Reverse(usage));
+
+    descriptions
+        .into_iter()
+        .take(limit)
+        .map(|(description, _)| description.to_string())
+        .collect()
+}
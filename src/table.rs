@@ -0,0 +1,297 @@
+use std::{
+    borrow::Cow,
+    env,
+    fs::File,
+    io::{self, IsTerminal, Write},
+    process::{Command, Stdio},
+};
+
+use colored::{ColoredString, Colorize};
+
+/// Flexible columns are not truncated below this width, or the width of their title, so that
+/// some of their text stays readable on narrow terminals.
+const MIN_FLEXIBLE_WIDTH: usize = 10;
+
+/// Which side of its column a value lines up with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// A value in a table, with the text its width is measured from and how it is shown.
+pub struct Cell {
+    text: String,
+    styled: String,
+}
+
+impl Cell {
+    pub fn plain(text: impl Into<String>) -> Self {
+        let text = text.into();
+
+        Self {
+            styled: text.clone(),
+            text,
+        }
+    }
+
+    /// A cell shown colored, which must have the same text as `text`.
+    pub fn styled(text: impl Into<String>, styled: ColoredString) -> Self {
+        Self {
+            text: text.into(),
+            styled: styled.to_string(),
+        }
+    }
+
+    /// A cell made of several colored parts, such as a bar with a segment for each project,
+    /// which together must have the same text as `text`.
+    pub fn parts(text: impl Into<String>, parts: &[ColoredString]) -> Self {
+        Self {
+            text: text.into(),
+            styled: parts.iter().map(ColoredString::to_string).collect(),
+        }
+    }
+
+    /// The cell shortened to `max` columns. A colored cell keeps its color, since the escape
+    /// codes are only around its text.
+    fn truncated(&self, max: usize) -> Cow<'_, str> {
+        if width(&self.text) <= max {
+            return Cow::Borrowed(&self.styled);
+        }
+
+        let truncated = truncate(&self.text, max);
+
+        if self.styled.contains(&self.text) {
+            Cow::Owned(self.styled.replacen(&self.text, &truncated, 1))
+        } else {
+            Cow::Owned(truncated)
+        }
+    }
+}
+
+struct Column {
+    title: String,
+    align: Align,
+    flexible: bool,
+}
+
+/// Rows printed in aligned columns under a header.
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<(Vec<Cell>, Vec<String>)>,
+    max_width: Option<usize>,
+}
+
+impl Table {
+    pub fn new(columns: &[(&str, Align)]) -> Self {
+        Self {
+            columns: columns
+                .iter()
+                .map(|&(title, align)| Column {
+                    title: title.to_string(),
+                    align,
+                    flexible: false,
+                })
+                .collect(),
+            rows: Vec::new(),
+            max_width: None,
+        }
+    }
+
+    /// Lets the columns with these titles be truncated to fit the table in its maximum width.
+    pub fn flexible(mut self, titles: &[&str]) -> Self {
+        for column in self.columns.iter_mut() {
+            column.flexible |= titles.contains(&column.title.as_str());
+        }
+
+        self
+    }
+
+    /// Truncates the flexible columns so that lines fit in `width` columns, if given.
+    pub fn max_width(mut self, width: Option<usize>) -> Self {
+        self.max_width = width;
+        self
+    }
+
+    /// Adds a row, which must have a cell for every column.
+    pub fn row(&mut self, cells: Vec<Cell>) {
+        debug_assert_eq!(cells.len(), self.columns.len());
+        self.rows.push((cells, Vec::new()));
+    }
+
+    /// Adds a line below the last row, such as its notes, which is not part of the columns.
+    pub fn note(&mut self, line: impl Into<String>) {
+        if let Some((_, notes)) = self.rows.last_mut() {
+            notes.push(line.into());
+        }
+    }
+
+    /// The width of each column, shrinking the widest flexible column until the lines fit.
+    fn widths(&self, indent: &str) -> Vec<usize> {
+        let mut widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                self.rows
+                    .iter()
+                    .map(|(cells, _)| width(&cells[index].text))
+                    .chain([width(&column.title)])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let Some(max_width) = self.max_width else {
+            return widths;
+        };
+
+        let line_width = |widths: &[usize]| {
+            width(indent) + widths.iter().sum::<usize>() + 2 * widths.len().saturating_sub(1)
+        };
+
+        while line_width(&widths) > max_width {
+            let widest = widths
+                .iter()
+                .enumerate()
+                .filter(|&(index, &current)| {
+                    let column = &self.columns[index];
+                    column.flexible && current > MIN_FLEXIBLE_WIDTH.max(width(&column.title))
+                })
+                .max_by_key(|&(_, &current)| current)
+                .map(|(index, _)| index);
+
+            match widest {
+                Some(index) => widths[index] -= 1,
+                None => break,
+            }
+        }
+
+        widths
+    }
+
+    /// Writes the header and every row, each line starting with `indent`.
+    pub fn write(&self, out: &mut impl Write, indent: &str) -> io::Result<()> {
+        let widths = self.widths(indent);
+
+        let header: Vec<Cell> = self
+            .columns
+            .iter()
+            .map(|column| Cell::styled(&column.title, column.title.bright_yellow()))
+            .collect();
+
+        let no_notes = Vec::new();
+
+        for (cells, notes) in [(&header, &no_notes)]
+            .into_iter()
+            .chain(self.rows.iter().map(|(cells, notes)| (cells, notes)))
+        {
+            let line: Vec<String> = cells
+                .iter()
+                .zip(&self.columns)
+                .zip(&widths)
+                .map(|((cell, column), &max)| {
+                    let text = cell.truncated(max);
+                    let padding = " ".repeat(max.saturating_sub(width(&cell.text).min(max)));
+
+                    match column.align {
+                        Align::Left => format!("{text}{padding}"),
+                        Align::Right => format!("{padding}{text}"),
+                    }
+                })
+                .collect();
+
+            writeln!(out, "{indent}{}", line.join("  ").trim_end())?;
+
+            for note in notes {
+                writeln!(out, "{indent}    {note}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The number of terminal columns text takes up. East Asian wide characters and emoji take
+/// two, and combining marks take none.
+pub fn width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x0300..=0x036f
+        | 0x1ab0..=0x1aff
+        | 0x1dc0..=0x1dff
+        | 0x200b..=0x200f
+        | 0x20d0..=0x20ff
+        | 0xfe00..=0xfe0f
+        | 0xfe20..=0xfe2f => 0,
+        0x1100..=0x115f
+        | 0x2e80..=0x303e
+        | 0x3041..=0x33ff
+        | 0x3400..=0x4dbf
+        | 0x4e00..=0x9fff
+        | 0xa000..=0xa4cf
+        | 0xac00..=0xd7a3
+        | 0xf900..=0xfaff
+        | 0xfe30..=0xfe4f
+        | 0xff00..=0xff60
+        | 0xffe0..=0xffe6
+        | 0x1f300..=0x1f64f
+        | 0x1f900..=0x1f9ff
+        | 0x20000..=0x3fffd => 2,
+        _ => 1,
+    }
+}
+
+/// Shortens text to at most `max` columns, ending it with an ellipsis if anything was cut.
+pub fn truncate(text: &str, max: usize) -> String {
+    if width(text) <= max {
+        return text.to_string();
+    }
+
+    let mut truncated = String::new();
+    let mut used = 0;
+
+    for c in text.chars() {
+        let next = used + char_width(c);
+
+        // One column is left for the ellipsis.
+        if next + 1 > max {
+            break;
+        }
+
+        truncated.push(c);
+        used = next;
+    }
+
+    truncated.push('…');
+    truncated
+}
+
+/// The width of the terminal that stdout is shown in, or `None` if it isn't a terminal.
+pub fn terminal_width() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+
+    if let Some(columns) = env::var("COLUMNS").ok().and_then(|text| text.parse().ok()) {
+        return Some(columns);
+    }
+
+    // `stty` reports the size of the terminal on its stdin, as `rows columns`.
+    let tty = File::open("/dev/tty").ok()?;
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(tty)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,83 @@
+use std::{
+    collections::hash_map::RandomState,
+    env,
+    fs::{self, OpenOptions},
+    hash::{BuildHasher, Hasher},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A file in the temporary directory that only the user can read, created under a name that
+/// can't be guessed and deleted when dropped. It is never opened through a link planted by
+/// someone else, since creating it fails if anything already has its name.
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    pub fn create(suffix: &str, contents: &[u8]) -> io::Result<Self> {
+        loop {
+            let path = env::temp_dir().join(format!("hat-{:016x}{suffix}", random()));
+
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+
+            match options.open(&path) {
+                Ok(mut file) => {
+                    let temp = Self { path };
+                    file.write_all(contents)?;
+                    return Ok(temp);
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+/// Writes options for curl to a config file passed with `--config`, so that secrets such as
+/// passwords and tokens don't show up in the process list as arguments would.
+pub fn curl_config(options: &[(&str, &str)]) -> io::Result<TempFile> {
+    let text: String = options
+        .iter()
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{name} = \"{value}\"\n")
+        })
+        .collect();
+
+    TempFile::create(".curlrc", text.as_bytes())
+}
+
+/// A number that is different every time, seeded by the standard library's random hash keys.
+fn random() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(process::id());
+
+    if let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) {
+        hasher.write_u128(now.as_nanos());
+    }
+
+    hasher.finish()
+}
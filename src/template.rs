@@ -0,0 +1,32 @@
+use std::{env, time::Duration};
+
+use crate::{date, git, ticket, LoggedTime};
+
+/// Expands the placeholders in a description template.
+///
+/// The supported placeholders are `{date}`, `{project}`, `{branch}` for the git branch of
+/// the current directory, and `{ticket}` for the last ticket ID mentioned in the project.
+pub fn expand(template: &str, project: &str, logged_times: &[LoggedTime], now: Duration) -> String {
+    let mut text = template
+        .replace("{date}", &date::format_date(now))
+        .replace("{project}", project);
+
+    if text.contains("{branch}") {
+        let branch = env::current_dir()
+            .ok()
+            .and_then(|dir| git::current_branch(&dir))
+            .unwrap_or_default();
+        text = text.replace("{branch}", &branch);
+    }
+
+    if text.contains("{ticket}") {
+        let ticket = logged_times
+            .iter()
+            .rev()
+            .find_map(|time| ticket::find(&time.description))
+            .unwrap_or_default();
+        text = text.replace("{ticket}", ticket);
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
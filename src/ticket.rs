@@ -0,0 +1,41 @@
+/// Finds every ticket reference in some text, such as `#123`, `!45` for a merge request, or
+/// `PROJ-42`, in the order they appear and without repeats.
+pub fn find_all(text: &str) -> Vec<String> {
+    let mut tickets: Vec<String> = Vec::new();
+
+    for word in words(text).filter(|word| is_ticket(word)) {
+        if !tickets.iter().any(|ticket| ticket == word) {
+            tickets.push(word.to_string());
+        }
+    }
+
+    tickets
+}
+
+/// Finds the first ticket reference in some text.
+pub fn find(text: &str) -> Option<&str> {
+    words(text).find(|word| is_ticket(word))
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, ',' | ':' | '(' | ')' | '[' | ']'))
+        .map(|word| word.trim_end_matches(['.', ';', '!', '?']))
+}
+
+fn is_ticket(word: &str) -> bool {
+    if let Some(number) = word.strip_prefix('#').or_else(|| word.strip_prefix('!')) {
+        return !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+    }
+
+    let Some((key, number)) = word.split_once('-') else {
+        return false;
+    };
+
+    key.len() >= 2
+        && key.starts_with(|c: char| c.is_ascii_uppercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+}
@@ -0,0 +1,44 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// The minutes already sent to an issue tracker for each entry and ticket, kept next to the
+/// data file between runs so that time is never sent twice.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SyncState {
+    posted: HashMap<String, i64>,
+}
+
+impl SyncState {
+    pub fn load(path: &Path) -> Self {
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(
+            path,
+            serde_json::to_vec_pretty(self).expect("Could not serialize JSON file."),
+        )?;
+        Ok(())
+    }
+
+    /// The minutes sent for a key so far.
+    pub fn posted(&self, key: &str) -> i64 {
+        self.posted.get(key).copied().unwrap_or(0)
+    }
+
+    pub fn record(&mut self, key: String, minutes: i64) {
+        *self.posted.entry(key).or_default() += minutes;
+    }
+}
+
+/// The key that the time sent for an entry and ticket is recorded under.
+pub fn key(project: &str, start_epoch: u64, ticket: &str) -> String {
+    format!("{project}\t{start_epoch}\t{ticket}")
+}
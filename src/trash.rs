@@ -0,0 +1,55 @@
+use std::{fmt, time::Duration};
+
+use colored::Colorize;
+use pretty_duration::pretty_duration;
+use serde::{Deserialize, Serialize};
+
+use crate::{LoggedTime, Project};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Something that was deleted, along with when it was deleted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashItem {
+    pub deleted_epoch: Duration,
+    pub contents: Trashed,
+}
+
+/// The contents of a trash item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Trashed {
+    /// A deleted project and all of its entries.
+    Project { name: String, project: Box<Project> },
+
+    /// An entry removed from a project.
+    Entry { project: String, time: LoggedTime },
+}
+
+impl fmt::Display for Trashed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Project { name, project } => write!(
+                f,
+                "project {} with {} entries",
+                name.bright_cyan(),
+                project.logged_times.len()
+            ),
+            Self::Entry { project, time } => write!(
+                f,
+                "{} - {} from project {}",
+                pretty_duration(&time.duration, None).bright_red(),
+                time.description.bright_blue(),
+                project.bright_cyan()
+            ),
+        }
+    }
+}
+
+/// Removes trash items that were deleted more than `retention_days` ago.
+/// Returns the number of items purged.
+pub fn purge(trash: &mut Vec<TrashItem>, now: Duration, retention_days: u64) -> usize {
+    let retention = Duration::from_secs(retention_days * SECONDS_PER_DAY);
+    let before = trash.len();
+    trash.retain(|item| now.saturating_sub(item.deleted_epoch) < retention);
+    before - trash.len()
+}
@@ -0,0 +1,225 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{log, sha256, Error, Result};
+
+/// The GitHub API endpoint describing the latest release.
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/rigidity/hat-changer/releases/latest";
+
+/// How long to wait for GitHub during the automatic check, so that it never holds up a command.
+const CHECK_TIMEOUT_SECS: &str = "2";
+
+/// How often the automatic check asks GitHub for the latest release.
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The version of this binary.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A published release and the binary built for this platform, if there is one.
+pub struct Release {
+    pub version: String,
+    pub download_url: Option<String>,
+
+    /// Where the SHA-256 checksum of the binary is published, as a `.sha256` file next to it.
+    pub checksum_url: Option<String>,
+}
+
+/// When the automatic check last ran, kept between runs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CheckState {
+    checked_epoch: Duration,
+}
+
+fn curl(args: &[&str]) -> Result<Vec<u8>> {
+    log::debug!("Running curl {}.", args.join(" "));
+
+    let output = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location"])
+        .args(args)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::Update(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+/// The target triple release binaries are named after, such as `x86_64-unknown-linux-gnu`.
+fn target() -> String {
+    let os = match env::consts::OS {
+        "linux" => "unknown-linux-gnu",
+        "macos" => "apple-darwin",
+        "windows" => "pc-windows-msvc",
+        os => os,
+    };
+
+    format!("{}-{os}", env::consts::ARCH)
+}
+
+/// Asks GitHub for the latest release, waiting at most `timeout` seconds if given.
+pub fn latest_release(timeout: Option<&str>) -> Result<Release> {
+    let mut args = vec!["--header", "Accept: application/vnd.github+json"];
+
+    if let Some(timeout) = timeout {
+        args.extend(["--max-time", timeout]);
+    }
+
+    args.push(LATEST_RELEASE_URL);
+
+    let body = curl(&args)?;
+    let release: serde_json::Value = serde_json::from_slice(&body)
+        .map_err(|err| Error::Update(format!("Could not parse the release: {err}")))?;
+
+    let Some(tag) = release["tag_name"].as_str() else {
+        return Err(Error::Update("The release has no version.".to_string()));
+    };
+
+    let asset = format!("hat-{}", target());
+
+    // Finds the URL of the asset whose name, without a Windows extension, is `name`.
+    let find_asset = |name: &str| {
+        release["assets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|item| {
+                item["name"]
+                    .as_str()
+                    .is_some_and(|asset| asset.replace(".exe", "") == name)
+            })
+            .and_then(|item| item["browser_download_url"].as_str())
+            .map(str::to_string)
+    };
+
+    Ok(Release {
+        version: tag.trim_start_matches('v').to_string(),
+        download_url: find_asset(&asset),
+        checksum_url: find_asset(&format!("{asset}.sha256")),
+    })
+}
+
+/// Whether `version` is newer than this binary, comparing the numbers of `1.2.3` in order.
+pub fn is_newer(version: &str) -> bool {
+    let parse = |version: &str| -> Vec<u64> {
+        version
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+
+    parse(version) > parse(CURRENT_VERSION)
+}
+
+/// Downloads a release binary, checks it against the published checksum, and puts it in place
+/// of the running one.
+pub fn install(release: &Release) -> Result<PathBuf> {
+    let Some(url) = &release.download_url else {
+        return Err(Error::Update(format!(
+            "Release {} has no binary for {}.",
+            release.version,
+            target()
+        )));
+    };
+
+    // A binary that can't be checked is never installed, since it replaces the running one.
+    let Some(checksum_url) = &release.checksum_url else {
+        return Err(Error::Update(format!(
+            "Release {} has no checksum for its binary.",
+            release.version
+        )));
+    };
+
+    let checksum = String::from_utf8_lossy(&curl(&[checksum_url])?).to_string();
+
+    // The file is formatted as `sha256sum` prints it, with the file name after the hash.
+    let Some(expected) = checksum.split_whitespace().next().map(str::to_lowercase) else {
+        return Err(Error::Update("The checksum file is empty.".to_string()));
+    };
+
+    let binary = curl(&[url])?;
+    let actual = sha256::hex_digest(&binary);
+
+    log::debug!("The binary has checksum {actual}, and {expected} is expected.");
+
+    if actual != expected {
+        return Err(Error::Update(format!(
+            "The downloaded binary does not match the checksum of release {}.",
+            release.version
+        )));
+    }
+
+    let current = env::current_exe()?;
+    let download = sibling(&current, ".new");
+    let old = sibling(&current, ".old");
+
+    fs::write(&download, &binary)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&download, fs::Permissions::from_mode(0o755))?;
+    }
+
+    // A running binary can be renamed on every platform, but not overwritten on Windows.
+    fs::rename(&current, &old)?;
+
+    if let Err(err) = fs::rename(&download, &current) {
+        fs::rename(&old, &current)?;
+        return Err(err.into());
+    }
+
+    if let Err(err) = fs::remove_file(&old) {
+        log::debug!("Could not remove {}: {err}.", old.display());
+    }
+
+    Ok(current)
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Checks for a new release at most once a day, remembering when it last did in `state_path`.
+/// Returns the newer version if there is one and it was just found.
+pub fn check(state_path: &Path, now: Duration) -> Result<Option<String>> {
+    let state: CheckState = fs::read(state_path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default();
+
+    if now.saturating_sub(state.checked_epoch) < CHECK_INTERVAL {
+        log::debug!("Already checked for a new release within the last day.");
+        return Ok(None);
+    }
+
+    // The time is recorded even if the check fails, so that being offline doesn't slow every
+    // command down.
+    let release = latest_release(Some(CHECK_TIMEOUT_SECS));
+
+    let state = CheckState { checked_epoch: now };
+
+    if let Some(dir) = state_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    fs::write(
+        state_path,
+        serde_json::to_vec_pretty(&state).expect("Could not serialize JSON file."),
+    )?;
+
+    let release = release?;
+
+    Ok(is_newer(&release.version).then_some(release.version))
+}
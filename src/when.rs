@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use crate::{date, duration};
+
+pub const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// Parses a date and time written for people, relative to `now` and in UTC. Accepts the forms
+/// of [`date::parse_date_time`], `now`, `today`, `yesterday`, `tomorrow`, weekdays such as
+/// `monday` or `last fri`, any of those followed by `HH:MM`, and `2h ago`.
+pub fn parse(text: &str, now: Duration) -> Option<Duration> {
+    let text = text.trim().to_lowercase();
+
+    if let Some(amount) = text.strip_suffix("ago") {
+        return now.checked_sub(duration::parse(amount)?);
+    }
+
+    if text == "now" {
+        return Some(now);
+    }
+
+    if let Some(time) = date::parse_date_time(&text, now) {
+        return Some(time);
+    }
+
+    let (day, time_of_day) = match text.rsplit_once(char::is_whitespace) {
+        Some((day, time)) if time.contains(':') => (day.trim(), date::parse_time_of_day(time)?),
+        _ => (text.as_str(), Duration::ZERO),
+    };
+
+    Some(date::start_of_day(parse_day(day, date::day_number(now))?) + time_of_day)
+}
+
+/// Parses a day relative to today, given as days since the epoch.
+fn parse_day(text: &str, today: i64) -> Option<i64> {
+    match text {
+        "today" => return Some(today),
+        "yesterday" => return Some(today - 1),
+        "tomorrow" => return Some(today + 1),
+        _ => {}
+    }
+
+    let (last, name) = match text.strip_prefix("last ") {
+        Some(name) => (true, name.trim()),
+        None => (false, text),
+    };
+
+    if name.len() < 3 {
+        return None;
+    }
+
+    let weekday = WEEKDAYS.iter().position(|day| day.starts_with(name))? as i64;
+
+    // The epoch was a Thursday, three days after a Monday.
+    let days_back = ((today + 3) - weekday).rem_euclid(7);
+
+    // A weekday alone may be today, but `last` always means an earlier day.
+    Some(today - if last && days_back == 0 { 7 } else { days_back })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::parse;
+    use crate::date;
+
+    const HOUR: u64 = 60 * 60;
+
+    /// Wednesday 2024-06-05 at 14:30 UTC.
+    fn now() -> Duration {
+        date::parse_date_time("2024-06-05 14:30", Duration::ZERO).unwrap()
+    }
+
+    fn day(text: &str) -> Duration {
+        date::parse_date(text).unwrap()
+    }
+
+    #[test]
+    fn relative_days() {
+        assert_eq!(parse("now", now()), Some(now()));
+        assert_eq!(parse("today", now()), Some(day("2024-06-05")));
+        assert_eq!(parse("Yesterday", now()), Some(day("2024-06-04")));
+        assert_eq!(parse("tomorrow", now()), Some(day("2024-06-06")));
+        assert_eq!(
+            parse("yesterday 15:00", now()),
+            Some(day("2024-06-04") + Duration::from_secs(15 * HOUR))
+        );
+    }
+
+    #[test]
+    fn weekdays() {
+        // A weekday is the latest one up to today, and `last` is always before today.
+        assert_eq!(parse("monday", now()), Some(day("2024-06-03")));
+        assert_eq!(parse("wed", now()), Some(day("2024-06-05")));
+        assert_eq!(parse("last wed", now()), Some(day("2024-05-29")));
+        assert_eq!(parse("thursday", now()), Some(day("2024-05-30")));
+        assert_eq!(
+            parse("fri 09:30", now()),
+            Some(day("2024-05-31") + Duration::from_secs(9 * HOUR + 30 * 60))
+        );
+        assert_eq!(parse("mo", now()), None);
+        assert_eq!(parse("someday", now()), None);
+    }
+
+    #[test]
+    fn ago() {
+        assert_eq!(
+            parse("2h ago", now()),
+            Some(now() - Duration::from_secs(2 * HOUR))
+        );
+        assert_eq!(
+            parse("90 min ago", now()),
+            Some(now() - Duration::from_secs(90 * 60))
+        );
+        assert_eq!(parse("1000000h ago", now()), None);
+    }
+
+    #[test]
+    fn absolute_forms() {
+        assert_eq!(parse("2024-06-01", now()), Some(day("2024-06-01")));
+        assert_eq!(
+            parse("2024-06-01 08:00", now()),
+            Some(day("2024-06-01") + Duration::from_secs(8 * HOUR))
+        );
+        assert_eq!(
+            parse("09:00", now()),
+            Some(day("2024-06-05") + Duration::from_secs(9 * HOUR))
+        );
+        assert_eq!(parse("today 25:00", now()), None);
+    }
+}
@@ -0,0 +1,206 @@
+use std::time::Duration;
+
+use hat_changer::date;
+
+/// Writes files into an uncompressed zip archive, dated `epoch` in UTC.
+pub fn write(files: &[(String, Vec<u8>)], epoch: Duration) -> Vec<u8> {
+    let (time, day) = dos_date_time(epoch);
+
+    let mut archive = Vec::new();
+    let mut directory = Vec::new();
+
+    for (name, contents) in files {
+        let offset = archive.len() as u32;
+        let crc = crc32(contents);
+        let size = contents.len() as u32;
+
+        // The fields shared by the local header and the central directory entry: the version
+        // needed, flags (names are UTF-8), method (stored), date, checksum, and sizes.
+        let mut common = Vec::new();
+        common.extend_from_slice(&20u16.to_le_bytes());
+        common.extend_from_slice(&0x0800u16.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+        common.extend_from_slice(&time.to_le_bytes());
+        common.extend_from_slice(&day.to_le_bytes());
+        common.extend_from_slice(&crc.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+
+        archive.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        archive.extend_from_slice(&common);
+        archive.extend_from_slice(name.as_bytes());
+        archive.extend_from_slice(contents);
+
+        directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        directory.extend_from_slice(&20u16.to_le_bytes());
+        directory.extend_from_slice(&common);
+
+        // The comment length, disk number, and file attributes, which are all empty.
+        directory.extend_from_slice(&[0; 10]);
+        directory.extend_from_slice(&offset.to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+    }
+
+    let directory_offset = archive.len() as u32;
+    let count = files.len() as u16;
+
+    archive.extend_from_slice(&directory);
+    archive.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    archive.extend_from_slice(&[0; 4]);
+    archive.extend_from_slice(&count.to_le_bytes());
+    archive.extend_from_slice(&count.to_le_bytes());
+    archive.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+    archive.extend_from_slice(&directory_offset.to_le_bytes());
+    archive.extend_from_slice(&0u16.to_le_bytes());
+
+    archive
+}
+
+/// The time and date in the MS-DOS format zip files use, which can't go before 1980.
+fn dos_date_time(epoch: Duration) -> (u16, u16) {
+    let (year, month, day) = date::civil_from_days(date::day_number(epoch));
+    let seconds = epoch.as_secs() % (24 * 60 * 60);
+
+    if year < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+
+    let time = ((seconds / 3600) << 11) | ((seconds / 60 % 60) << 5) | (seconds % 60 / 2);
+    let date = (((year - 1980) as u32) << 9) | (month << 5) | day;
+
+    (time as u16, date as u16)
+}
+
+/// The CRC-32 checksum of some bytes, as zip files use.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{crc32, dos_date_time, write};
+
+    /// 2024-06-03 09:00:30 UTC.
+    const EPOCH: Duration = Duration::from_secs(1_717_405_230);
+
+    fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn files() -> Vec<(String, Vec<u8>)> {
+        vec![
+            ("data.json".to_string(), b"{\"projects\":{}}".to_vec()),
+            ("archive/2023.json".to_string(), Vec::new()),
+            ("notes/ünïcode.txt".to_string(), b"123456789".to_vec()),
+        ]
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414f_a339
+        );
+    }
+
+    #[test]
+    fn dos_dates() {
+        let (time, date) = dos_date_time(EPOCH);
+        assert_eq!(time, (9 << 11) | 15);
+        assert_eq!(date, ((2024 - 1980) << 9) | (6 << 5) | 3);
+
+        // Times before 1980 are clamped to its first day.
+        assert_eq!(dos_date_time(Duration::ZERO), (0, (1 << 5) | 1));
+    }
+
+    #[test]
+    fn end_of_central_directory() {
+        let archive = write(&files(), EPOCH);
+        let end = archive.len() - 22;
+
+        assert_eq!(u32_at(&archive, end), 0x0605_4b50);
+        assert_eq!(u16_at(&archive, end + 8), 3);
+        assert_eq!(u16_at(&archive, end + 10), 3);
+
+        let size = u32_at(&archive, end + 12) as usize;
+        let offset = u32_at(&archive, end + 16) as usize;
+        assert_eq!(offset + size, end);
+        assert_eq!(u16_at(&archive, end + 20), 0);
+    }
+
+    #[test]
+    fn headers_match_their_files() {
+        let files = files();
+        let archive = write(&files, EPOCH);
+        let (time, date) = dos_date_time(EPOCH);
+
+        let end = archive.len() - 22;
+        let mut central = u32_at(&archive, end + 16) as usize;
+
+        for (name, contents) in &files {
+            // The central directory entry.
+            assert_eq!(u32_at(&archive, central), 0x0201_4b50);
+            assert_eq!(u16_at(&archive, central + 8), 0x0800);
+            assert_eq!(u16_at(&archive, central + 10), 0);
+            assert_eq!(u16_at(&archive, central + 12), time);
+            assert_eq!(u16_at(&archive, central + 14), date);
+            assert_eq!(u32_at(&archive, central + 16), crc32(contents));
+            assert_eq!(u32_at(&archive, central + 20) as usize, contents.len());
+            assert_eq!(u32_at(&archive, central + 24) as usize, contents.len());
+
+            let name_length = u16_at(&archive, central + 28) as usize;
+            assert_eq!(
+                &archive[central + 46..central + 46 + name_length],
+                name.as_bytes()
+            );
+
+            // The local header it points to repeats the same fields before the contents.
+            let local = u32_at(&archive, central + 42) as usize;
+            assert_eq!(u32_at(&archive, local), 0x0403_4b50);
+            assert_eq!(
+                archive[local + 4..local + 30],
+                archive[central + 6..central + 32]
+            );
+
+            let start = local + 30 + name_length;
+            assert_eq!(&archive[local + 30..start], name.as_bytes());
+            assert_eq!(&archive[start..start + contents.len()], contents.as_slice());
+
+            central += 46 + name_length;
+        }
+
+        assert_eq!(central, end);
+    }
+
+    #[test]
+    fn empty_archive() {
+        let archive = write(&[], EPOCH);
+        assert_eq!(archive.len(), 22);
+        assert_eq!(u32_at(&archive, 0), 0x0605_4b50);
+        assert_eq!(u16_at(&archive, 10), 0);
+    }
+}